@@ -2,4 +2,4 @@
 
 packs_file=$(bash scripts/jq/locate-pack-json.sh)
 lang_sub_dir=$(dirname "$packs_file")
-jq --compact-output --slurp 'add' "$lang_sub_dir"/cards_*.json
+jq --compact-output --slurp 'map(if type == "object" then .cards else . end) | add' "$lang_sub_dir"/cards_*.json
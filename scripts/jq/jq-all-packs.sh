@@ -2,4 +2,4 @@
 
 packs_file=$(bash scripts/jq/locate-pack-json.sh)
 lang_sub_dir=$(dirname "$packs_file")
-jq --compact-output '.' "$lang_sub_dir/packs.json"
+jq --compact-output 'if has("schema_version") then .packs else . end' "$lang_sub_dir/packs.json"
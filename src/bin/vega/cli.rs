@@ -0,0 +1,513 @@
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use vegapull::{
+    card::{CardOrder, TextNormalization},
+    language::{LanguageArg, LanguageCode},
+    pack::PackId,
+    scraper::{ImageQuality, DEFAULT_IMAGE_JOBS},
+    storage::{
+        ArchiveFormat, Compression, ImageFormat, ImageLayout, ImageNameTemplate, ImageNames,
+        OutputFormat, StoreLayout, StoreOptions,
+    },
+    user_agent::UserAgentStrategy,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "vega",
+    about = "Scrape cards data from the official One Piece TCG website",
+    long_about = None
+)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    #[command(flatten)]
+    pub verbose: clap_verbosity_flag::Verbosity,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PullSubCommands {
+    /// Download the complete dataset for a given language
+    #[command(name = "all", alias = "records")]
+    All {
+        /// Pull several languages at once, each into its own sub-directory (skips interactive mode)
+        #[arg(
+            short = 'L',
+            long = "languages",
+            value_name = "LANGUAGES",
+            value_delimiter = ',',
+            value_enum
+        )]
+        languages: Vec<LanguageCode>,
+
+        /// Download card images as well (only used with --languages)
+        #[arg(short = 'a', long = "with-images")]
+        with_images: bool,
+    },
+    /// Download the list of existing packs
+    #[command(name = "packs", alias = "pack")]
+    Packs,
+    /// Download all cards for a given pack
+    #[command(name = "cards", alias = "card")]
+    Cards {
+        /// ID of the pack
+        pack_id: PackId,
+
+        /// Download card images as well
+        #[arg(short = 'a', long = "with-images")]
+        with_images: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportSubCommands {
+    /// Write packs and cards as Arrow IPC (Feather) files, for polars and pyarrow
+    #[command(name = "arrow", alias = "feather")]
+    Arrow {
+        /// Directory to write `packs.arrow` and `cards.arrow` to
+        #[arg(short, long = "output", value_name = "PATH")]
+        output_dir: PathBuf,
+    },
+    /// Create or update tables in a PostgreSQL database and upsert packs and cards
+    #[command(name = "postgres", alias = "pg")]
+    Postgres {
+        /// Connection string (e.g. `host=localhost user=vega dbname=cards`)
+        #[arg(long = "dsn", value_name = "CONN")]
+        dsn: String,
+    },
+    /// Composite the card images of a pack into one PNG, with a JSON map of where each card is
+    #[command(name = "sheet", alias = "sprite")]
+    Sheet {
+        /// ID of the pack (e.g. `569101`)
+        #[arg(value_name = "PACK_ID")]
+        pack_id: PackId,
+
+        /// Directory to write `<PACK_ID>.png` and `<PACK_ID>.json` to
+        #[arg(short, long = "output", value_name = "PATH")]
+        output_dir: PathBuf,
+
+        /// Number of cards per row
+        #[arg(long = "columns", value_name = "N", default_value_t = 10)]
+        columns: u32,
+
+        /// Width of each card on the sheet, in pixels
+        #[arg(long = "cell-width", value_name = "PIXELS", default_value_t = 200)]
+        cell_width: u32,
+
+        #[command(flatten)]
+        image_args: ImagePathArgs,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigSubCommands {
+    /// Check every locale file of the config directory for missing, unsupported or ambiguous labels
+    #[command(name = "validate", alias = "check")]
+    Validate,
+    /// Report which canonical keys each locale file of the config directory does not map
+    #[command(name = "coverage")]
+    Coverage,
+    /// Update the locale files of the config directory to the built-in ones, keeping added aliases
+    #[command(name = "sync")]
+    Sync,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LocaleSubCommands {
+    /// Draft a locale file from the labels found on the first packs of a site
+    #[command(name = "init")]
+    Init {
+        /// Code of the locale, the draft is written to `<CODE>.toml` in the config directory
+        code: String,
+
+        /// URL of the site (e.g. `https://en.onepiece-cardgame.com`)
+        #[arg(long = "hostname", value_name = "URL")]
+        hostname: String,
+
+        /// Number of packs to sample labels from
+        #[arg(long = "sample-packs", value_name = "COUNT", default_value_t = 3)]
+        sample_packs: usize,
+
+        /// Write the draft to <PATH> instead of the config directory
+        #[arg(short, long = "output", value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Print the labels of a language by canonical key, as JSON
+    #[command(name = "export")]
+    Export {
+        /// Language to export, or `custom:<code>` for a locale of the config directory
+        #[arg(value_name = "LANGUAGE")]
+        language: LanguageArg,
+
+        /// Write the JSON to <PATH> instead of stdout
+        #[arg(short, long = "output", value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// Download datasets from the official site
+    #[command(name = "pull", alias = "p", alias = "fetch", alias = "punk")]
+    Pull {
+        #[command(subcommand)]
+        command: PullSubCommands,
+
+        /// Dataset to use (card descriptions and images will vary), or `custom:<CODE>` to use
+        /// the `<CODE>.toml` locale of the config directory
+        #[arg(
+            short,
+            long,
+            alias = "lang",
+            value_name = "LANGUAGE",
+            default_value = "english"
+        )]
+        language: LanguageArg,
+
+        /// Save downloaded data to <DIR>
+        #[arg(short, long = "output", value_name = "PATH")]
+        output_dir: Option<PathBuf>,
+
+        /// Path to the config directory (where locales and settings are stored)
+        #[arg(short = 'c', long = "config-dir")]
+        config_path: Option<PathBuf>,
+
+        /// Use the locale file at <PATH> instead of the one of the language (e.g. for an unsupported region)
+        #[arg(long = "locale-file", value_name = "PATH")]
+        locale_file: Option<PathBuf>,
+
+        /// Send User-Agent <NAME> to server
+        #[arg(short = 'A', long = "user-agent", value_name = "NAME")]
+        user_agent: Option<String>,
+
+        /// How to pick the User-Agent for each request [default: fixed]
+        #[arg(long = "user-agent-strategy", value_name = "STRATEGY", value_enum)]
+        user_agent_strategy: Option<UserAgentStrategy>,
+
+        #[command(flatten)]
+        store_args: StoreArgs,
+    },
+    /// Export a pulled dataset to other tools
+    #[command(name = "export")]
+    Export {
+        #[command(subcommand)]
+        command: ExportSubCommands,
+
+        /// Language of the dataset to export
+        #[arg(short, long, alias = "lang", value_name = "LANGUAGE", default_value_t = LanguageCode::English, value_enum)]
+        language: LanguageCode,
+
+        /// Directory the dataset was pulled into
+        #[arg(short, long = "input", value_name = "PATH")]
+        input_dir: PathBuf,
+
+        #[command(flatten)]
+        store_args: ReadStoreArgs,
+    },
+    /// Snapshot a store into a dated `.tar.zst` archive
+    #[command(name = "backup")]
+    Backup {
+        /// Directory of the store to back up
+        store_dir: PathBuf,
+
+        /// Directory to write the backup to
+        dest_dir: PathBuf,
+    },
+    /// Restore a store from a backup, verifying every file against the backup manifest
+    #[command(name = "restore")]
+    Restore {
+        /// Backup archive created by `vega backup`
+        backup_file: PathBuf,
+
+        /// Directory to restore the store to
+        store_dir: PathBuf,
+
+        /// Replace the store if it already exists
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Upgrade a store written by an older vegapull in place
+    #[command(name = "migrate")]
+    Migrate {
+        /// Directory of the store to upgrade
+        store_dir: PathBuf,
+
+        /// Language of the store
+        #[arg(short, long, alias = "lang", value_name = "LANGUAGE", default_value_t = LanguageCode::English, value_enum)]
+        language: LanguageCode,
+
+        #[command(flatten)]
+        store_args: ReadStoreArgs,
+
+        /// Indent the upgraded JSON files (for datasets tracked with git)
+        #[arg(long = "pretty")]
+        pretty: bool,
+    },
+    /// Compare datasets
+    // #[command(name = "diff", alias = "df")]
+    // Diff {
+    //     /// Output differences between two packs.json files
+    //     #[arg(short, long = "packs", num_args = 2, value_names = ["FILE1", "FILE2"])]
+    //     pack_files: Option<Vec<PathBuf>>,
+    // },
+    /// Create locale files for new sites
+    #[command(name = "locale")]
+    Locale {
+        #[command(subcommand)]
+        command: LocaleSubCommands,
+    },
+    /// Output current configuration
+    #[command(name = "config", alias = "conf")]
+    Config {
+        #[command(subcommand)]
+        command: Option<ConfigSubCommands>,
+    },
+}
+
+/// Options controlling how pulled data is laid out on disk
+#[derive(Debug, Clone, Default, Args)]
+pub struct StoreArgs {
+    #[command(flatten)]
+    pub read: ReadStoreArgs,
+
+    #[command(flatten)]
+    pub images: ImagePathArgs,
+
+    /// Indent JSON output (for datasets tracked with git)
+    #[arg(long = "pretty")]
+    pub pretty: bool,
+
+    /// Order of the cards inside data files
+    #[arg(
+        long = "sort-by",
+        value_name = "ORDER",
+        default_value = "set",
+        value_enum
+    )]
+    pub sort_by: CardOrder,
+
+    /// Keep the rarity, category, colors and attributes of cards as shown on the site (`raw_*` fields)
+    #[arg(long = "include-raw")]
+    pub include_raw: bool,
+
+    /// Record when each card was scraped (output then differs between runs)
+    #[arg(long = "scrape-timestamps")]
+    pub scrape_timestamps: bool,
+
+    /// Merge into existing data files instead of overwriting them, recording removed cards in tombstones
+    #[arg(long = "merge")]
+    pub merge: bool,
+
+    /// List alternate arts (`_p1`, `_p2`...) in the `variants` of their base card instead of as separate cards
+    #[arg(long = "group-variants")]
+    pub group_variants: bool,
+
+    /// Normalize the name, effect, trigger and types of cards (e.g. `nfkc,whitespace` for JP/zh datasets)
+    #[arg(
+        long = "normalize-text",
+        value_name = "STEPS",
+        value_delimiter = ',',
+        value_enum
+    )]
+    pub normalize_text: Vec<TextNormalization>,
+
+    /// Write everything into a single archive next to the output directory instead of loose files
+    #[arg(
+        long = "archive",
+        value_name = "FORMAT",
+        value_enum,
+        conflicts_with = "merge"
+    )]
+    pub archive: Option<ArchiveFormat>,
+
+    /// Store images shared between languages as copies instead of hardlinks (with --languages)
+    #[arg(long = "no-image-links")]
+    pub no_image_links: bool,
+
+    /// Write a file for every card even when its image is identical to another card's (by default
+    /// the copy is skipped and images/manifest.json points both cards to one file)
+    #[arg(long = "materialize-images")]
+    pub materialize_images: bool,
+
+    /// Recompress PNG images losslessly before writing them (slower, smaller datasets)
+    #[arg(long = "optimize-images")]
+    pub optimize_images: bool,
+
+    /// Only download the images missing for the cards already in the output directory, without
+    /// scraping the cards again (with `pull cards` and `pull all`)
+    #[arg(long = "images-only", conflicts_with = "archive")]
+    pub images_only: bool,
+
+    /// Convert downloaded images to another format (e.g. `webp` for smaller web assets)
+    #[arg(
+        long = "image-format",
+        value_name = "FORMAT",
+        default_value = "original",
+        value_enum
+    )]
+    pub image_format: ImageFormat,
+
+    /// Download images again even if they are already in the store (with --with-images)
+    #[arg(long = "force-images")]
+    pub force_images: bool,
+
+    /// Ask the site whether images already in the store changed (If-None-Match/If-Modified-Since)
+    /// and download again only those that did (with --with-images)
+    #[arg(long = "check-images", conflicts_with = "force_images")]
+    pub check_images: bool,
+
+    /// Write a placeholder showing the card ID and name for cards whose images fail to download,
+    /// listed in errors.json (with --with-images)
+    #[arg(long = "image-placeholders")]
+    pub image_placeholders: bool,
+
+    /// Number of images downloaded at the same time (with --with-images)
+    #[arg(long = "image-jobs", value_name = "JOBS", default_value_t = DEFAULT_IMAGE_JOBS)]
+    pub image_jobs: usize,
+
+    /// Send at most <RATE> requests per second for pages to each host (unlimited by default)
+    #[arg(long = "page-rate", value_name = "RATE")]
+    pub page_rate: Option<f64>,
+
+    /// Send at most <RATE> requests per second for images to each host, independently of
+    /// --page-rate (unlimited by default)
+    #[arg(long = "image-rate", value_name = "RATE")]
+    pub image_rate: Option<f64>,
+
+    /// Which image of each card to download (with --with-images)
+    #[arg(
+        long = "image-quality",
+        value_name = "QUALITY",
+        default_value = "standard",
+        value_enum
+    )]
+    pub image_quality: ImageQuality,
+
+    /// Also download the product artwork and logo of every pack into images/packs/ (with `pull all`
+    /// and `pull packs`)
+    #[arg(long = "with-pack-images")]
+    pub with_pack_images: bool,
+}
+
+/// Options telling how the data files of a store already pulled are laid out, to read them back
+#[derive(Debug, Clone, Default, Args)]
+pub struct ReadStoreArgs {
+    /// Compress JSON output files
+    #[arg(
+        long = "compress",
+        value_name = "ALGORITHM",
+        default_value = "none",
+        value_enum
+    )]
+    pub compression: Compression,
+
+    /// Format of the data files
+    #[arg(
+        long = "format",
+        value_name = "FORMAT",
+        default_value = "json",
+        value_enum
+    )]
+    pub format: OutputFormat,
+
+    /// Write all packs and their cards into a single dataset.json file
+    #[arg(long = "single-file", conflicts_with = "layout")]
+    pub single_file: bool,
+
+    /// How to organize data files and images in the output directory
+    #[arg(
+        long = "layout",
+        value_name = "LAYOUT",
+        default_value = "flat",
+        value_enum
+    )]
+    pub layout: StoreLayout,
+
+    /// Fail to read data files that do not match SHA256SUMS instead of only warning (files edited
+    /// by hand after a pull do not match it)
+    #[arg(long = "strict-checksums")]
+    pub strict_checksums: bool,
+}
+
+impl ReadStoreArgs {
+    /// Options of the stores to read from
+    pub fn store_options(&self) -> StoreOptions {
+        StoreOptions {
+            compression: self.compression,
+            format: self.format,
+            single_file: self.single_file,
+            layout: self.layout,
+            strict_checksums: self.strict_checksums,
+            ..Default::default()
+        }
+    }
+}
+
+/// Options telling where the images of a store are
+#[derive(Debug, Clone, Default, Args)]
+pub struct ImagePathArgs {
+    /// How to store downloaded images
+    #[arg(
+        long = "image-layout",
+        value_name = "LAYOUT",
+        default_value = "flat",
+        value_enum
+    )]
+    pub image_layout: ImageLayout,
+
+    /// Name images after a template, from {card_id}, {base_id}, {variant}, {pack_id}, {ext} and {filename}
+    /// (e.g. `{pack_id}/{base_id}_{variant}.{ext}`), naming each card apart with {card_id}, {filename}
+    /// or both {base_id} and {variant}
+    #[arg(long = "image-name-template", value_name = "TEMPLATE")]
+    pub image_name_template: Option<ImageNameTemplate>,
+
+    /// How to name images without --image-name-template
+    #[arg(
+        long = "image-names",
+        value_name = "SCHEME",
+        default_value = "site",
+        value_enum,
+        conflicts_with = "image_name_template"
+    )]
+    pub image_names: ImageNames,
+}
+
+impl ImagePathArgs {
+    /// Options of the stores to read data files and images from
+    pub fn store_options(&self, read_args: &ReadStoreArgs) -> StoreOptions {
+        StoreOptions {
+            image_layout: self.image_layout,
+            image_name_template: self
+                .image_name_template
+                .clone()
+                .or_else(|| self.image_names.template()),
+            ..read_args.store_options()
+        }
+    }
+}
+
+impl StoreArgs {
+    /// Options of the stores to write to
+    pub fn store_options(&self) -> StoreOptions {
+        StoreOptions {
+            image_format: self.image_format,
+            pretty: self.pretty,
+            scrape_timestamps: self.scrape_timestamps,
+            merge: self.merge,
+            group_variants: self.group_variants,
+            text_normalization: self.normalize_text.clone(),
+            include_raw: self.include_raw,
+            card_order: self.sort_by,
+            force_images: self.force_images,
+            check_images: self.check_images,
+            image_placeholders: self.image_placeholders,
+            materialize_images: self.materialize_images,
+            optimize_images: self.optimize_images,
+            archive: self.archive,
+            ..self.images.store_options(&self.read)
+        }
+    }
+}
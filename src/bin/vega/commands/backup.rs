@@ -0,0 +1,223 @@
+use anyhow::{bail, ensure, Context, Result};
+use chrono::Local;
+use log::{debug, info};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process,
+};
+
+use vegapull::storage::{
+    checksums, lock::LOCK_FILE, ArchiveBackend, ArchiveFormat, DataStore, StoreBackend, StoreLock,
+};
+
+/// Checksums of every file in a backup, verified on restore
+const BACKUP_MANIFEST: &str = "BACKUP.SHA256SUMS";
+
+/// Every file under `dir`, sorted so backups of identical stores are identical
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("failed to read directory: {}", current.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn relative_name(root_dir: &Path, path: &Path) -> Result<String> {
+    Ok(path
+        .strip_prefix(root_dir)?
+        .to_string_lossy()
+        .replace('\\', "/"))
+}
+
+pub fn backup_store(store_dir: &Path, dest_dir: &Path) -> Result<()> {
+    ensure!(
+        store_dir.is_dir(),
+        "store not found: {}",
+        store_dir.display()
+    );
+
+    // Keep writers out while the snapshot is taken
+    let _lock = StoreLock::acquire(&store_dir.join(LOCK_FILE))?;
+
+    let store_name = store_dir
+        .file_name()
+        .with_context(|| format!("expected a directory name: {}", store_dir.display()))?
+        .to_string_lossy();
+    let timestamp = Local::now().format("%y%m%d_%H%M%S");
+    let format = ArchiveFormat::TarZst;
+    let backup_path = dest_dir.join(format!(
+        "{}-backup-{}.{}",
+        store_name,
+        timestamp,
+        format.extension()
+    ));
+
+    let archive = ArchiveBackend::create_at(store_dir, &backup_path, format)?;
+    let mut manifest = BTreeMap::new();
+
+    for path in list_files(store_dir)? {
+        let name = relative_name(store_dir, &path)?;
+        if name == LOCK_FILE {
+            continue;
+        }
+
+        let data =
+            fs::read(&path).with_context(|| format!("failed to read: {}", path.display()))?;
+        manifest.insert(name, DataStore::sha256_hex(&data));
+        archive.write(&path, &data)?;
+    }
+
+    let manifest_data = checksums::format(&manifest);
+    archive.write(&store_dir.join(BACKUP_MANIFEST), manifest_data.as_bytes())?;
+    archive.finish()?;
+
+    println!(
+        "backed up {} files from {} to: {}",
+        manifest.len(),
+        store_dir.display(),
+        backup_path.display()
+    );
+
+    Ok(())
+}
+
+/// Check every file listed in the manifest of an unpacked backup
+fn verify_backup(dir: &Path) -> Result<usize> {
+    let manifest_path = dir.join(BACKUP_MANIFEST);
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("backup has no manifest ({})", BACKUP_MANIFEST))?;
+    let manifest = checksums::parse(&manifest)?;
+
+    for (name, expected) in &manifest {
+        let path = dir.join(name);
+        let data = fs::read(&path).with_context(|| format!("backup is missing: {}", name))?;
+        let actual = DataStore::sha256_hex(&data);
+        if &actual != expected {
+            bail!(
+                "checksum mismatch for {}: expected {} but got {}, the backup may be corrupted",
+                name,
+                expected,
+                actual
+            );
+        }
+    }
+
+    let unpacked = list_files(dir)?.len() - 1; // Minus the manifest itself
+    ensure!(
+        unpacked == manifest.len(),
+        "backup holds {} files but its manifest lists {}",
+        unpacked,
+        manifest.len()
+    );
+
+    fs::remove_file(manifest_path)?;
+    Ok(manifest.len())
+}
+
+pub fn restore_store(backup_path: &Path, store_dir: &Path, force: bool) -> Result<()> {
+    if store_dir.exists() && !force {
+        bail!(
+            "`{}` already exists, use --force to replace it",
+            store_dir.display()
+        );
+    }
+
+    let store_name = store_dir
+        .file_name()
+        .with_context(|| format!("expected a directory name: {}", store_dir.display()))?
+        .to_string_lossy();
+    let temp_dir = store_dir.with_file_name(format!(".{}.restore-{}", store_name, process::id()));
+
+    info!(
+        "unpacking {} to: {}",
+        backup_path.display(),
+        temp_dir.display()
+    );
+    let file = File::open(backup_path)
+        .with_context(|| format!("failed to open backup: {}", backup_path.display()))?;
+    let result = (|| -> Result<usize> {
+        tar::Archive::new(zstd::Decoder::new(file)?).unpack(&temp_dir)?;
+        verify_backup(&temp_dir)
+    })();
+
+    let file_count = match result {
+        Ok(file_count) => file_count,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(e.context(format!("failed to restore: {}", backup_path.display())));
+        }
+    };
+    debug!("verified {} files", file_count);
+
+    if store_dir.exists() {
+        // Fails if a pull is still writing to the store about to be replaced
+        drop(StoreLock::acquire(&store_dir.join(LOCK_FILE))?);
+        fs::remove_dir_all(store_dir)?;
+    }
+    fs::rename(&temp_dir, store_dir)?;
+
+    println!(
+        "restored {} files from {} to: {}",
+        file_count,
+        backup_path.display(),
+        store_dir.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_then_restore_returns_same_files() {
+        let dir = std::env::temp_dir().join(format!("vega-test-backup-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store_dir = dir.join("data-en");
+        fs::create_dir_all(store_dir.join("json")).unwrap();
+        fs::write(store_dir.join("json/packs.json"), b"{}").unwrap();
+        fs::write(store_dir.join("vega.meta.toml"), b"mode = \"All\"").unwrap();
+
+        backup_store(&store_dir, &dir.join("backups")).unwrap();
+        let backup_path = list_files(&dir.join("backups")).unwrap().remove(0);
+
+        assert!(restore_store(&backup_path, &store_dir, false).is_err());
+
+        fs::write(store_dir.join("json/packs.json"), b"changed").unwrap();
+        restore_store(&backup_path, &store_dir, true).unwrap();
+        assert_eq!(fs::read(store_dir.join("json/packs.json")).unwrap(), b"{}");
+        assert!(!store_dir.join(BACKUP_MANIFEST).exists());
+        assert!(!store_dir.join(LOCK_FILE).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn verify_backup_tampered_file_returns_err() {
+        let dir = std::env::temp_dir().join(format!("vega-test-verify-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("packs.json"), b"{}").unwrap();
+        let manifest = BTreeMap::from([(String::from("packs.json"), DataStore::sha256_hex(b"[]"))]);
+        fs::write(dir.join(BACKUP_MANIFEST), checksums::format(&manifest)).unwrap();
+
+        assert!(verify_backup(&dir).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
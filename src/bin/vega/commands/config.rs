@@ -0,0 +1,260 @@
+use anyhow::{bail, ensure, Context, Result};
+use log::warn;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use vegapull::{
+    config,
+    localizer::{Localizer, BUILTIN_LOCALES, CANONICAL_KEYS},
+};
+
+pub fn show_config() -> Result<()> {
+    let config_dir = config::get_config_dir()?;
+    ensure!(
+        config_dir.exists(),
+        format!("config directory not found: {}", config_dir.display())
+    );
+
+    let entries = fs::read_dir(&config_dir)?;
+    println!("config directory: {}", config_dir.display());
+
+    for entry in entries {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        println!("- {}", file_name.to_string_lossy());
+    }
+
+    Ok(())
+}
+
+/// Locale files of the config directory (every `.toml` file except the settings and
+/// `.local.toml` overlays), sorted
+fn list_locale_files(config_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut locale_paths = Vec::new();
+    for entry in fs::read_dir(config_dir)? {
+        let path = entry?.path();
+        let is_locale = path.extension().is_some_and(|ext| ext == "toml")
+            && path.file_name().is_some_and(|name| {
+                let name = name.to_string_lossy();
+                name != config::SETTINGS_FILE && !name.ends_with(".local.toml")
+            });
+        if is_locale {
+            locale_paths.push(path);
+        }
+    }
+    locale_paths.sort();
+
+    Ok(locale_paths)
+}
+
+/// Load every locale file of the config directory and report the problems found in each
+pub fn validate_configs() -> Result<()> {
+    let config_dir = config::get_config_dir()?;
+    ensure!(
+        config_dir.exists(),
+        format!("config directory not found: {}", config_dir.display())
+    );
+
+    let locale_paths = list_locale_files(&config_dir)?;
+    let mut invalid = 0;
+    for path in &locale_paths {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let problems = match Localizer::load_from_path(path) {
+            Ok(localizer) => localizer.validate(),
+            Err(e) => vec![format!("{:#}", e)],
+        };
+
+        if problems.is_empty() {
+            println!("{}: ok", file_name);
+            continue;
+        }
+
+        invalid += 1;
+        println!("{}:", file_name);
+        for problem in problems {
+            println!("  - {}", problem);
+        }
+    }
+
+    if invalid > 0 {
+        bail!(
+            "{} of {} locale files are invalid",
+            invalid,
+            locale_paths.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Report the canonical keys each locale file of the config directory does not map
+pub fn show_coverage() -> Result<()> {
+    let config_dir = config::get_config_dir()?;
+    ensure!(
+        config_dir.exists(),
+        format!("config directory not found: {}", config_dir.display())
+    );
+
+    let mut locales = Vec::new();
+    for path in list_locale_files(&config_dir)? {
+        let locale = path.file_stem().unwrap_or_default().to_string_lossy();
+        match Localizer::load_from_path(&path) {
+            Ok(localizer) => locales.push((locale.to_string(), localizer)),
+            Err(e) => warn!("skipping {}: {:#}", path.display(), e),
+        }
+    }
+
+    let mut key_count = 0;
+    let mut gap_count = 0;
+    for (table, keys) in CANONICAL_KEYS {
+        for key in keys {
+            key_count += 1;
+            let missing: Vec<String> = locales
+                .iter()
+                .filter(|(_, localizer)| !localizer.maps_key(table, key))
+                .map(|(locale, localizer)| match &localizer.fallback {
+                    Some(fallback) => format!("{} (falls back to {})", locale, fallback),
+                    None => locale.clone(),
+                })
+                .collect();
+
+            if !missing.is_empty() {
+                gap_count += 1;
+                println!("{}.{}: missing in {}", table, key, missing.join(", "));
+            }
+        }
+    }
+
+    println!(
+        "{} of {} canonical keys are mapped in all {} locales",
+        key_count - gap_count,
+        key_count,
+        locales.len()
+    );
+
+    Ok(())
+}
+
+/// Update the locale files of the config directory to the built-in ones, keeping the
+/// aliases users added to them
+pub fn sync_configs() -> Result<()> {
+    let config_dir = config::get_config_dir()?;
+    fs::create_dir_all(&config_dir)?;
+
+    for (locale, builtin) in BUILTIN_LOCALES {
+        let file_name = format!("{}.toml", locale);
+        let locale_path = config_dir.join(&file_name);
+        if !locale_path.exists() {
+            fs::write(&locale_path, builtin)?;
+            println!("{}: created", file_name);
+            continue;
+        }
+
+        let installed = fs::read_to_string(&locale_path)
+            .with_context(|| format!("Failed to open file: {}", locale_path.display()))?;
+        if installed == builtin {
+            println!("{}: up to date", file_name);
+            continue;
+        }
+
+        let (synced, kept) = match merge_user_aliases(builtin, &installed) {
+            Ok(merged) => merged,
+            Err(e) => {
+                warn!("{}: {:#}, replacing it without its aliases", file_name, e);
+                (builtin.to_string(), 0)
+            }
+        };
+
+        let backup_path = config_dir.join(format!("{}.bak", file_name));
+        fs::copy(&locale_path, &backup_path)?;
+        fs::write(&locale_path, synced)?;
+        match kept {
+            0 => println!("{}: updated", file_name),
+            _ => println!("{}: updated, kept {} user aliases", file_name, kept),
+        }
+    }
+
+    Ok(())
+}
+
+/// Built-in locale with the `[aliases]` of the installed one added to it, and the number
+/// of aliases added (the built-in file is kept verbatim when there is none)
+fn merge_user_aliases(builtin: &str, installed: &str) -> Result<(String, usize)> {
+    let installed: toml::Table = toml::from_str(installed).context("invalid installed locale")?;
+    let Some(toml::Value::Table(user_aliases)) = installed.get("aliases") else {
+        return Ok((builtin.to_string(), 0));
+    };
+
+    let mut merged: toml::Table = toml::from_str(builtin)?;
+    let aliases = merged
+        .entry("aliases")
+        .or_insert_with(|| toml::Table::new().into());
+    let kept = match aliases {
+        toml::Value::Table(aliases) => merge_tables(aliases, user_aliases),
+        _ => 0,
+    };
+
+    match kept {
+        0 => Ok((builtin.to_string(), 0)),
+        _ => Ok((toml::to_string(&merged)?, kept)),
+    }
+}
+
+/// Add the tables and array items of `src` missing from `dst`, returning how many were added
+fn merge_tables(dst: &mut toml::Table, src: &toml::Table) -> usize {
+    let mut added = 0;
+    for (key, value) in src {
+        match (dst.get_mut(key), value) {
+            (Some(toml::Value::Table(dst)), toml::Value::Table(src)) => {
+                added += merge_tables(dst, src);
+            }
+            (Some(toml::Value::Array(dst)), toml::Value::Array(src)) => {
+                for item in src {
+                    if !dst.contains(item) {
+                        dst.push(item.clone());
+                        added += 1;
+                    }
+                }
+            }
+            (Some(_), _) => {}
+            (None, value) => {
+                dst.insert(key.clone(), value.clone());
+                added += 1;
+            }
+        }
+    }
+
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vegapull::localizer::FR_LOCALE_RAW;
+
+    #[test]
+    fn merge_user_aliases_keeps_added_aliases() {
+        let (synced, kept) = merge_user_aliases(FR_LOCALE_RAW, FR_LOCALE_RAW).unwrap();
+        assert_eq!((synced.as_str(), kept), (FR_LOCALE_RAW, 0));
+
+        let installed = r#"
+            format_version = 1
+            hostname = "https://fr.onepiece-cardgame.com"
+            [colors]
+            red = "Rouge (old)"
+            [aliases.colors]
+            red = ["Écarlate"]
+            [aliases.patterns.rarities]
+            special = ['^SP']
+        "#;
+        let (synced, kept) = merge_user_aliases(FR_LOCALE_RAW, installed).unwrap();
+        assert_eq!(kept, 2);
+
+        let localizer: Localizer = toml::from_str(&synced).unwrap();
+        assert_eq!(localizer.colors["red"], "Rouge");
+        assert_eq!(localizer.match_color("écarlate").as_deref(), Some("red"));
+        assert_eq!(localizer.match_rarity("SP").as_deref(), Some("special"));
+    }
+}
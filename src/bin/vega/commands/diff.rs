@@ -6,8 +6,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::pack::Pack;
+use vegapull::pack::Pack;
 
+#[allow(dead_code)]
 pub fn show_diffs(pack_files: Option<Vec<PathBuf>>) -> Result<()> {
     if let Some(pack_files) = pack_files {
         ensure!(pack_files.len() == 2, "exactly two packs must be provided");
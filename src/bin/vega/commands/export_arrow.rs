@@ -0,0 +1,278 @@
+use anyhow::Result;
+use arrow_array::{
+    builder::{ListBuilder, StringBuilder},
+    ArrayRef, Int32Array, RecordBatch, StringArray,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema};
+use log::{debug, info};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use crate::cli::ReadStoreArgs;
+use vegapull::{
+    card::Card,
+    language::LanguageCode,
+    pack::{Pack, PackId},
+    storage::{DataStore, FsBackend, StoreBackend},
+    utils::{variant_name, variant_names},
+};
+
+const PACKS_FILE: &str = "packs.arrow";
+const CARDS_FILE: &str = "cards.arrow";
+
+fn list_field(name: &str) -> Field {
+    let item = Field::new("item", DataType::Utf8, true);
+    Field::new(name, DataType::List(Arc::new(item)), false)
+}
+
+fn string_lists(lists: Vec<Vec<String>>) -> ArrayRef {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for list in lists {
+        for value in list {
+            builder.values().append_value(value);
+        }
+        builder.append(true);
+    }
+
+    Arc::new(builder.finish())
+}
+
+fn strings<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(values))
+}
+
+fn optional_strings<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.collect::<StringArray>())
+}
+
+fn optional_ints(values: impl Iterator<Item = Option<i32>>) -> ArrayRef {
+    Arc::new(values.collect::<Int32Array>())
+}
+
+fn packs_batch(language: &str, packs: &BTreeMap<PackId, Pack>) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("language", DataType::Utf8, false),
+        Field::new("id", DataType::Utf8, false),
+        Field::new("raw_title", DataType::Utf8, false),
+        Field::new("prefix", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("label", DataType::Utf8, true),
+    ]);
+
+    let packs: Vec<&Pack> = packs.values().collect();
+    let columns = vec![
+        strings(packs.iter().map(|_| language)),
+        strings(packs.iter().map(|pack| pack.id.as_str())),
+        strings(packs.iter().map(|pack| pack.raw_title.as_str())),
+        optional_strings(packs.iter().map(|pack| pack.title_parts.prefix.as_deref())),
+        strings(packs.iter().map(|pack| pack.title_parts.title.as_str())),
+        optional_strings(packs.iter().map(|pack| pack.title_parts.label.as_deref())),
+    ];
+
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+fn cards_batch(language: &str, cards: &[&Card]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("language", DataType::Utf8, false),
+        Field::new("pack_id", DataType::Utf8, false),
+        Field::new("id", DataType::Utf8, false),
+        Field::new("set_code", DataType::Utf8, true),
+        Field::new("card_number", DataType::Int32, true),
+        Field::new("variant_suffix", DataType::Utf8, true),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("rarity", DataType::Utf8, false),
+        Field::new("category", DataType::Utf8, false),
+        Field::new("img_url", DataType::Utf8, false),
+        Field::new("img_full_url", DataType::Utf8, true),
+        Field::new("cost", DataType::Int32, true),
+        Field::new("life", DataType::Int32, true),
+        list_field("attributes"),
+        Field::new("power", DataType::Int32, true),
+        Field::new("counter", DataType::Int32, true),
+        list_field("colors"),
+        Field::new("block_number", DataType::Int32, true),
+        list_field("types"),
+        list_field("types_canonical"),
+        Field::new("effect", DataType::Utf8, false),
+        Field::new("don_cost", DataType::Int32, true),
+        Field::new("don_attached", DataType::Int32, true),
+        Field::new("trigger", DataType::Utf8, true),
+        list_field("trigger_keywords"),
+        list_field("products"),
+        Field::new("source_url", DataType::Utf8, true),
+    ]);
+
+    let rarities = cards
+        .iter()
+        .map(|card| variant_name(&card.rarity))
+        .collect::<Result<Vec<_>, _>>()?;
+    let categories = cards
+        .iter()
+        .map(|card| variant_name(&card.category))
+        .collect::<Result<Vec<_>, _>>()?;
+    let attributes = cards
+        .iter()
+        .map(|card| variant_names(&card.attributes))
+        .collect::<Result<Vec<_>, _>>()?;
+    let colors = cards
+        .iter()
+        .map(|card| variant_names(&card.colors))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let columns = vec![
+        strings(cards.iter().map(|_| language)),
+        strings(cards.iter().map(|card| card.pack_id.as_str())),
+        strings(cards.iter().map(|card| card.id.as_str())),
+        optional_strings(cards.iter().map(|card| card.set_code.as_deref())),
+        optional_ints(cards.iter().map(|card| card.card_number)),
+        optional_strings(cards.iter().map(|card| card.variant_suffix.as_deref())),
+        strings(cards.iter().map(|card| card.name.as_str())),
+        strings(rarities.iter().map(String::as_str)),
+        strings(categories.iter().map(String::as_str)),
+        strings(cards.iter().map(|card| card.img_url.as_str())),
+        optional_strings(cards.iter().map(|card| card.img_full_url.as_deref())),
+        optional_ints(cards.iter().map(|card| card.cost)),
+        optional_ints(cards.iter().map(|card| card.life)),
+        string_lists(attributes),
+        optional_ints(cards.iter().map(|card| card.power)),
+        optional_ints(cards.iter().map(|card| card.counter)),
+        string_lists(colors),
+        optional_ints(cards.iter().map(|card| card.block_number)),
+        string_lists(cards.iter().map(|card| card.types.clone()).collect()),
+        string_lists(
+            cards
+                .iter()
+                .map(|card| card.types_canonical.clone())
+                .collect(),
+        ),
+        strings(cards.iter().map(|card| card.effect.as_str())),
+        optional_ints(cards.iter().map(|card| card.don_cost)),
+        optional_ints(cards.iter().map(|card| card.don_attached)),
+        optional_strings(cards.iter().map(|card| card.trigger.as_deref())),
+        string_lists(
+            cards
+                .iter()
+                .map(|card| card.trigger_keywords.clone())
+                .collect(),
+        ),
+        string_lists(cards.iter().map(|card| card.products.clone()).collect()),
+        optional_strings(cards.iter().map(|card| card.source_url.as_deref())),
+    ];
+
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+/// Arrow IPC file (a.k.a. Feather v2), readable with memory mapping
+fn to_ipc_file(batch: &RecordBatch) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut writer = FileWriter::try_new(&mut data, &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    drop(writer);
+
+    Ok(data)
+}
+
+pub fn export_arrow(
+    language: LanguageCode,
+    input_dir: &Path,
+    store_args: &ReadStoreArgs,
+    output_dir: &Path,
+) -> Result<()> {
+    let store = DataStore::open(input_dir, language, &store_args.store_options())?;
+    let packs: BTreeMap<PackId, Pack> = store.read_packs()?.into_iter().collect();
+    let all_cards = store.read_all_cards()?;
+    let cards: Vec<&Card> = all_cards.values().flatten().collect();
+    info!(
+        "read {} packs and {} cards from: {}",
+        packs.len(),
+        cards.len(),
+        input_dir.display()
+    );
+
+    let language = language.to_string();
+    let files = [
+        (PACKS_FILE, packs_batch(&language, &packs)?),
+        (CARDS_FILE, cards_batch(&language, &cards)?),
+    ];
+
+    for (file_name, batch) in &files {
+        let path = output_dir.join(file_name);
+        FsBackend.write(&path, &to_ipc_file(batch)?)?;
+        debug!("wrote {} rows to: {}", batch.num_rows(), path.display());
+    }
+
+    println!(
+        "exported {} packs and {} cards to: {}",
+        packs.len(),
+        cards.len(),
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{cast::AsArray, Array};
+    use arrow_ipc::reader::FileReader;
+    use std::io::Cursor;
+    use vegapull::card::{CardCategory, CardColor, CardRarity};
+
+    #[test]
+    fn cards_batch_to_ipc_file_returns_same() {
+        let card = Card {
+            id: "OP01-001".parse().unwrap(),
+            pack_id: "569101".parse().unwrap(),
+            set_code: None,
+            card_number: None,
+            variant_suffix: None,
+            name: String::from("Roronoa Zoro"),
+            rarity: CardRarity::Leader,
+            category: CardCategory::Leader,
+            raw_rarity: None,
+            raw_category: None,
+            raw_colors: None,
+            raw_attributes: None,
+            img_url: String::from("../images/cardlist/card/OP01-001.png"),
+            img_full_url: None,
+            img_url_canonical: None,
+            img_back_url: None,
+            img_back_full_url: None,
+            cost: None,
+            life: None,
+            attributes: Vec::new(),
+            power: Some(5000),
+            counter: None,
+            colors: vec![CardColor::Red],
+            block_number: Some(1),
+            types: vec![String::from("Supernovas"), String::from("Straw Hat Crew")],
+            types_canonical: Vec::new(),
+            effect: String::new(),
+            effect_segments: Vec::new(),
+            don_cost: None,
+            don_attached: None,
+            trigger: None,
+            trigger_keywords: Vec::new(),
+            reprint_of: None,
+            appears_in: Vec::new(),
+            products: Vec::new(),
+            source_url: None,
+            scraped_at: None,
+            variants: Vec::new(),
+        };
+
+        let batch = cards_batch("english", &[&card]).unwrap();
+        let data = to_ipc_file(&batch).unwrap();
+
+        let mut reader = FileReader::try_new(Cursor::new(data), None).unwrap();
+        let read_batch = reader.next().unwrap().unwrap();
+        assert_eq!(read_batch, batch);
+
+        let types = read_batch.column_by_name("types").unwrap().as_list::<i32>();
+        assert_eq!(types.value(0).len(), 2);
+        assert!(read_batch.column_by_name("cost").unwrap().is_null(0));
+    }
+}
@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use log::{debug, info};
+use postgres::{types::Json, Client, NoTls};
+use std::path::Path;
+
+use crate::cli::ReadStoreArgs;
+use vegapull::{
+    language::LanguageCode,
+    storage::DataStore,
+    utils::{variant_name, variant_names},
+};
+
+const CREATE_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS vega_packs (
+    language TEXT NOT NULL,
+    id TEXT NOT NULL,
+    raw_title TEXT NOT NULL,
+    prefix TEXT,
+    title TEXT NOT NULL,
+    label TEXT,
+    PRIMARY KEY (language, id)
+);
+
+CREATE TABLE IF NOT EXISTS vega_cards (
+    language TEXT NOT NULL,
+    pack_id TEXT NOT NULL,
+    id TEXT NOT NULL,
+    set_code TEXT,
+    card_number INTEGER,
+    variant_suffix TEXT,
+    name TEXT NOT NULL,
+    rarity TEXT NOT NULL,
+    category TEXT NOT NULL,
+    img_url TEXT NOT NULL,
+    img_full_url TEXT,
+    img_back_url TEXT,
+    cost INTEGER,
+    life INTEGER,
+    attributes TEXT[] NOT NULL,
+    power INTEGER,
+    counter INTEGER,
+    colors TEXT[] NOT NULL,
+    block_number INTEGER,
+    types TEXT[] NOT NULL,
+    types_canonical TEXT[] NOT NULL DEFAULT '{}',
+    effect TEXT NOT NULL,
+    don_cost INTEGER,
+    don_attached INTEGER,
+    trigger TEXT,
+    trigger_keywords TEXT[] NOT NULL DEFAULT '{}',
+    products TEXT[] NOT NULL DEFAULT '{}',
+    variants JSONB NOT NULL DEFAULT '[]',
+    source_url TEXT,
+    PRIMARY KEY (language, pack_id, id)
+);
+";
+
+/// Columns of `vega_cards` added after its first version, missing from tables created before
+const ADD_CARD_COLUMNS: &str = "
+ALTER TABLE vega_cards
+    ADD COLUMN IF NOT EXISTS set_code TEXT,
+    ADD COLUMN IF NOT EXISTS card_number INTEGER,
+    ADD COLUMN IF NOT EXISTS variant_suffix TEXT,
+    ADD COLUMN IF NOT EXISTS img_back_url TEXT,
+    ADD COLUMN IF NOT EXISTS life INTEGER,
+    ADD COLUMN IF NOT EXISTS types_canonical TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS don_cost INTEGER,
+    ADD COLUMN IF NOT EXISTS don_attached INTEGER,
+    ADD COLUMN IF NOT EXISTS trigger_keywords TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS products TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS variants JSONB NOT NULL DEFAULT '[]';
+";
+
+const UPSERT_PACK: &str = "
+INSERT INTO vega_packs (language, id, raw_title, prefix, title, label)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (language, id) DO UPDATE SET
+    raw_title = EXCLUDED.raw_title,
+    prefix = EXCLUDED.prefix,
+    title = EXCLUDED.title,
+    label = EXCLUDED.label
+";
+
+const UPSERT_CARD: &str = "
+INSERT INTO vega_cards (
+    language, pack_id, id, set_code, card_number, variant_suffix, name, rarity, category,
+    img_url, img_full_url, img_back_url, cost, life, attributes, power, counter, colors,
+    block_number, types, types_canonical, effect, don_cost, don_attached, trigger,
+    trigger_keywords, products, variants, source_url
+)
+VALUES (
+    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20,
+    $21, $22, $23, $24, $25, $26, $27, $28, $29
+)
+ON CONFLICT (language, pack_id, id) DO UPDATE SET
+    set_code = EXCLUDED.set_code,
+    card_number = EXCLUDED.card_number,
+    variant_suffix = EXCLUDED.variant_suffix,
+    name = EXCLUDED.name,
+    rarity = EXCLUDED.rarity,
+    category = EXCLUDED.category,
+    img_url = EXCLUDED.img_url,
+    img_full_url = EXCLUDED.img_full_url,
+    img_back_url = EXCLUDED.img_back_url,
+    cost = EXCLUDED.cost,
+    life = EXCLUDED.life,
+    attributes = EXCLUDED.attributes,
+    power = EXCLUDED.power,
+    counter = EXCLUDED.counter,
+    colors = EXCLUDED.colors,
+    block_number = EXCLUDED.block_number,
+    types = EXCLUDED.types,
+    types_canonical = EXCLUDED.types_canonical,
+    effect = EXCLUDED.effect,
+    don_cost = EXCLUDED.don_cost,
+    don_attached = EXCLUDED.don_attached,
+    trigger = EXCLUDED.trigger,
+    trigger_keywords = EXCLUDED.trigger_keywords,
+    products = EXCLUDED.products,
+    variants = EXCLUDED.variants,
+    source_url = EXCLUDED.source_url
+";
+
+pub fn export_postgres(
+    language: LanguageCode,
+    input_dir: &Path,
+    store_args: &ReadStoreArgs,
+    dsn: &str,
+) -> Result<()> {
+    let store = DataStore::open(input_dir, language, &store_args.store_options())?;
+    let packs = store.read_packs()?;
+    let all_cards = store.read_all_cards()?;
+    info!(
+        "read {} packs and cards of {} packs from: {}",
+        packs.len(),
+        all_cards.len(),
+        input_dir.display()
+    );
+
+    let mut client = Client::connect(dsn, NoTls).context("failed to connect to PostgreSQL")?;
+    let mut transaction = client.transaction()?;
+    transaction.batch_execute(CREATE_TABLES)?;
+    transaction.batch_execute(ADD_CARD_COLUMNS)?;
+    debug!("ensured vega tables exist with every column");
+
+    let language = language.to_string();
+
+    let upsert_pack = transaction.prepare(UPSERT_PACK)?;
+    for pack in packs.values() {
+        let title_parts = &pack.title_parts;
+        transaction
+            .execute(
+                &upsert_pack,
+                &[
+                    &language,
+                    &pack.id.as_str(),
+                    &pack.raw_title,
+                    &title_parts.prefix,
+                    &title_parts.title,
+                    &title_parts.label,
+                ],
+            )
+            .with_context(|| format!("failed to upsert pack `{}`", pack.id))?;
+    }
+
+    let upsert_card = transaction.prepare(UPSERT_CARD)?;
+    let mut card_count = 0;
+    for (pack_id, cards) in &all_cards {
+        for card in cards {
+            transaction
+                .execute(
+                    &upsert_card,
+                    &[
+                        &language,
+                        &pack_id.as_str(),
+                        &card.id.as_str(),
+                        &card.set_code,
+                        &card.card_number,
+                        &card.variant_suffix,
+                        &card.name,
+                        &variant_name(&card.rarity)?,
+                        &variant_name(&card.category)?,
+                        &card.img_url,
+                        &card.img_full_url,
+                        &card.img_back_url,
+                        &card.cost,
+                        &card.life,
+                        &variant_names(&card.attributes)?,
+                        &card.power,
+                        &card.counter,
+                        &variant_names(&card.colors)?,
+                        &card.block_number,
+                        &card.types,
+                        &card.types_canonical,
+                        &card.effect,
+                        &card.don_cost,
+                        &card.don_attached,
+                        &card.trigger,
+                        &card.trigger_keywords,
+                        &card.products,
+                        &Json(&card.variants),
+                        &card.source_url,
+                    ],
+                )
+                .with_context(|| format!("failed to upsert card `{}`", card.id))?;
+            card_count += 1;
+        }
+        debug!("upserted cards of `{}`", pack_id);
+    }
+
+    transaction.commit()?;
+    println!(
+        "exported {} packs and {} cards to PostgreSQL",
+        packs.len(),
+        card_count
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upserted_and_added_card_columns_are_created() {
+        let (insert, values) = UPSERT_CARD.split_once("VALUES").unwrap();
+        let columns: Vec<&str> = insert
+            .split_once('(')
+            .unwrap()
+            .1
+            .trim_end()
+            .trim_end_matches(')')
+            .split(',')
+            .map(str::trim)
+            .collect();
+        assert_eq!(values.matches('$').count(), columns.len());
+        for column in columns {
+            assert!(
+                CREATE_TABLES.contains(&format!("\n    {} ", column)),
+                "{}",
+                column
+            );
+        }
+
+        // Added columns have the same definition as in new tables
+        for definition in ADD_CARD_COLUMNS.split("ADD COLUMN IF NOT EXISTS ").skip(1) {
+            let definition = definition.trim().trim_end_matches([',', ';']);
+            assert!(
+                CREATE_TABLES.contains(&format!("    {},\n", definition)),
+                "{}",
+                definition
+            );
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{ensure, Result};
+use image::{imageops::FilterType, DynamicImage, RgbaImage};
+use log::{info, warn};
+use serde::Serialize;
+use std::{collections::BTreeMap, io::Cursor, path::Path};
+
+use vegapull::{
+    card::{Card, CardId},
+    language::LanguageCode,
+    pack::PackId,
+    storage::{DataStore, FsBackend, StoreBackend, StoreOptions},
+};
+
+/// Where each card is on a sheet, written next to it as `<pack_id>.json`
+#[derive(Debug, Serialize)]
+struct SheetMap {
+    pack_id: PackId,
+    image: String,
+    columns: u32,
+    cell_width: u32,
+    cell_height: u32,
+    cards: BTreeMap<CardId, SheetCell>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct SheetCell {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Paste `images` left to right, top to bottom on a grid of `columns` cells `cell_width` wide,
+/// as tall as the first image's aspect ratio makes them
+fn compose_sheet(
+    images: &[(CardId, DynamicImage)],
+    columns: u32,
+    cell_width: u32,
+) -> Result<(RgbaImage, BTreeMap<CardId, SheetCell>, u32)> {
+    ensure!(!images.is_empty(), "no card images to put on the sheet");
+    ensure!(
+        columns > 0 && cell_width > 0,
+        "columns and cell width must be positive"
+    );
+
+    let (_, first) = &images[0];
+    let cell_height = (u64::from(cell_width) * u64::from(first.height()) / u64::from(first.width()))
+        .max(1) as u32;
+    let columns = columns.min(images.len() as u32);
+    let rows = (images.len() as u32).div_ceil(columns);
+
+    let mut sheet = RgbaImage::new(columns * cell_width, rows * cell_height);
+    let mut cells = BTreeMap::new();
+    for (i, (card_id, img)) in images.iter().enumerate() {
+        let cell = SheetCell {
+            x: (i as u32 % columns) * cell_width,
+            y: (i as u32 / columns) * cell_height,
+            width: cell_width,
+            height: cell_height,
+        };
+        let resized = img.resize_exact(cell_width, cell_height, FilterType::Triangle);
+        image::imageops::replace(
+            &mut sheet,
+            &resized.into_rgba8(),
+            cell.x.into(),
+            cell.y.into(),
+        );
+        cells.insert(card_id.clone(), cell);
+    }
+
+    Ok((sheet, cells, cell_height))
+}
+
+/// Composite the card images of a pack into `<pack_id>.png` along with a `<pack_id>.json`
+/// coordinate map
+pub fn export_sheet(
+    language: LanguageCode,
+    input_dir: &Path,
+    options: &StoreOptions,
+    pack_id: &PackId,
+    output_dir: &Path,
+    columns: u32,
+    cell_width: u32,
+) -> Result<()> {
+    let store = DataStore::open(input_dir, language, options)?;
+    let cards = store.read_cards(pack_id)?;
+    let cards: Vec<&Card> = cards.iter().collect();
+    let mut stored = store.read_card_images(&cards)?;
+
+    let mut images = Vec::new();
+    for card in &cards {
+        let Some(img_data) = stored.remove(&card.id) else {
+            warn!("no image stored for `{}`, leaving it out", card.id);
+            continue;
+        };
+        match image::load_from_memory(&img_data) {
+            Ok(img) => images.push((card.id.clone(), img)),
+            Err(e) => warn!("invalid image for `{}`, leaving it out: {}", card.id, e),
+        }
+    }
+    info!("read {} images of pack {}", images.len(), pack_id);
+
+    let (sheet, cells, cell_height) = compose_sheet(&images, columns, cell_width)?;
+
+    let image_name = format!("{}.png", pack_id);
+    let mut png = Cursor::new(Vec::new());
+    sheet.write_to(&mut png, image::ImageFormat::Png)?;
+    FsBackend.write(&output_dir.join(&image_name), &png.into_inner())?;
+
+    let map = SheetMap {
+        pack_id: pack_id.clone(),
+        image: image_name,
+        columns: columns.min(images.len() as u32),
+        cell_width,
+        cell_height,
+        cards: cells,
+    };
+    let map_path = output_dir.join(format!("{}.json", pack_id));
+    FsBackend.write(&map_path, serde_json::to_string_pretty(&map)?.as_bytes())?;
+
+    println!(
+        "exported a sheet of {} cards to: {}",
+        map.cards.len(),
+        output_dir.join(&map.image).display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose_sheet_places_cards_on_grid() {
+        let card = |id: &str, shade: u8| {
+            let img = RgbaImage::from_pixel(60, 84, image::Rgba([shade, 0, 0, 255]));
+            (id.parse().unwrap(), DynamicImage::ImageRgba8(img))
+        };
+        let images = vec![
+            card("OP01-001", 10),
+            card("OP01-002", 20),
+            card("OP01-003", 30),
+        ];
+
+        let (sheet, cells, cell_height) = compose_sheet(&images, 2, 30).unwrap();
+
+        assert_eq!(cell_height, 42);
+        assert_eq!(sheet.dimensions(), (60, 84));
+        let third = cells[&"OP01-003".parse::<CardId>().unwrap()];
+        assert_eq!(
+            third,
+            SheetCell {
+                x: 0,
+                y: 42,
+                width: 30,
+                height: 42
+            }
+        );
+        assert_eq!(sheet.get_pixel(third.x + 5, third.y + 5)[0], 30);
+        assert_eq!(sheet.get_pixel(45, 5)[0], 20);
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{ensure, Result};
+use log::{info, warn};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
+use vegapull::{
+    card::scraper::RawLabels,
+    config,
+    language::LanguageArg,
+    localizer::{
+        Localizer, ATTRIBUTE_KEYS, CATEGORY_KEYS, COLOR_KEYS, EN_LOCALE_RAW, LOCALE_FORMAT_VERSION,
+        RARITY_KEYS,
+    },
+    scraper::OpTcgScraper,
+    user_agent::UserAgentPool,
+    utils,
+};
+
+type LabelMatcher = fn(&Localizer, &str) -> Option<String>;
+
+/// Distinct labels found on the site, by locale table
+#[derive(Debug, Default)]
+struct SampledLabels {
+    colors: BTreeSet<String>,
+    attributes: BTreeSet<String>,
+    categories: BTreeSet<String>,
+    rarities: BTreeSet<String>,
+}
+
+impl SampledLabels {
+    fn add(&mut self, labels: RawLabels) {
+        let split = |value: Option<String>| -> Vec<String> {
+            value
+                .iter()
+                .flat_map(|value| value.split('/'))
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        self.colors.extend(split(labels.colors));
+        self.attributes.extend(split(labels.attributes));
+        self.categories
+            .extend(labels.category.map(|c| c.trim().to_string()));
+        self.rarities
+            .extend(labels.rarity.map(|r| r.trim().to_string()));
+    }
+}
+
+/// Scrape the first packs of a site and write a draft locale file mapping the labels found
+pub fn init_locale(
+    code: &str,
+    hostname: &str,
+    sample_packs: usize,
+    output: Option<PathBuf>,
+    user_agents: UserAgentPool,
+) -> Result<()> {
+    utils::validate_id("locale code", code)?;
+    let output = match output {
+        Some(output) => output,
+        None => config::get_config_dir()?.join(format!("{}.toml", code)),
+    };
+    ensure!(
+        !output.exists(),
+        "locale file already exists: {}",
+        output.display()
+    );
+
+    let mut draft = toml::Table::new();
+    draft.insert(String::from("hostname"), hostname.into());
+    for table in ["colors", "attributes", "categories", "rarities"] {
+        draft.insert(table.to_string(), toml::Table::new().into());
+    }
+    let scraper = OpTcgScraper::builder()
+        .localizer(draft.try_into()?)
+        .user_agents(user_agents)
+        .build()?;
+
+    eprintln!("fetching list of packs from {}...", hostname);
+    let pack_ids: BTreeSet<_> = scraper.fetch_packs()?.into_keys().collect();
+    ensure!(!pack_ids.is_empty(), "no packs found on: {}", hostname);
+
+    let mut labels = SampledLabels::default();
+    let mut card_count = 0;
+    for pack_id in pack_ids.iter().take(sample_packs) {
+        eprintln!("sampling labels of pack {}...", pack_id);
+        match scraper.fetch_raw_labels(pack_id) {
+            Ok(pack_labels) => {
+                card_count += pack_labels.len();
+                pack_labels.into_iter().for_each(|l| labels.add(l));
+            }
+            Err(e) => warn!("failed to sample pack {}: {}", pack_id, e),
+        }
+    }
+    info!("sampled labels of {} cards", card_count);
+
+    let reference: Localizer = toml::from_str(EN_LOCALE_RAW)?;
+    let locale = draft_locale(hostname, &labels, &reference)?;
+    fs::write(&output, locale)?;
+
+    println!(
+        "wrote draft locale from {} cards to: {}",
+        card_count,
+        output.display()
+    );
+    println!("replace its TODOs, then check it with `vega config validate`");
+
+    Ok(())
+}
+
+/// Labels of a locale by table and canonical key, as written by `vega locale export`
+#[derive(Debug, Serialize)]
+struct LocaleExport {
+    language: String,
+    #[serde(flatten)]
+    labels: BTreeMap<&'static str, BTreeMap<String, String>>,
+}
+
+/// Write the labels of a language as JSON, to display localized filters without the TOML files
+pub fn export_locale(language: LanguageArg, output: Option<PathBuf>) -> Result<()> {
+    let name = match &language {
+        LanguageArg::Known(language) => language.to_string(),
+        LanguageArg::Custom(code) => code.clone(),
+    };
+    let (language, locale_file) = language.resolve(None)?;
+    let localizer = Localizer::load_or_file(language, locale_file.as_deref())?;
+
+    let export = LocaleExport {
+        language: name,
+        labels: localizer.label_maps(),
+    };
+    let json = serde_json::to_string_pretty(&export)?;
+
+    match output {
+        Some(output) => {
+            fs::write(&output, json)?;
+            eprintln!("wrote {} labels to: {}", export.language, output.display());
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// Locale file mapping every label `reference` knows, with a TODO for the others
+fn draft_locale(hostname: &str, labels: &SampledLabels, reference: &Localizer) -> Result<String> {
+    let tables: [(&str, &[&str], &BTreeSet<String>, LabelMatcher); 4] = [
+        ("colors", COLOR_KEYS, &labels.colors, Localizer::match_color),
+        (
+            "attributes",
+            ATTRIBUTE_KEYS,
+            &labels.attributes,
+            Localizer::match_attribute,
+        ),
+        (
+            "categories",
+            CATEGORY_KEYS,
+            &labels.categories,
+            Localizer::match_category,
+        ),
+        (
+            "rarities",
+            RARITY_KEYS,
+            &labels.rarities,
+            Localizer::match_rarity,
+        ),
+    ];
+
+    let mut locale = String::new();
+    writeln!(locale, "# Draft generated by `vega locale init`")?;
+    writeln!(locale, "format_version = {}", LOCALE_FORMAT_VERSION)?;
+    writeln!(locale, "hostname = {}", toml::Value::from(hostname))?;
+    writeln!(locale, "fallback = \"en\"")?;
+
+    for (table, keys, labels, matcher) in tables {
+        let mut mapped = BTreeMap::new();
+        let mut unmapped = Vec::new();
+        for label in labels {
+            match matcher(reference, label) {
+                Some(key) if !mapped.contains_key(&key) => {
+                    mapped.insert(key, label);
+                }
+                _ => unmapped.push(label),
+            }
+        }
+
+        writeln!(locale, "\n[{}]", table)?;
+        for key in keys.iter().copied() {
+            match mapped.get(key) {
+                Some(label) => writeln!(locale, "{} = {}", key, toml::Value::from(label.as_str()))?,
+                None => writeln!(locale, "# TODO: {} = \"<label>\"", key)?,
+            }
+        }
+        for label in unmapped {
+            writeln!(
+                locale,
+                "# TODO: found {}, map it to one of the keys above",
+                toml::Value::from(label.as_str())
+            )?;
+        }
+    }
+
+    Ok(locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft_locale_maps_known_labels_and_marks_the_others() {
+        let reference: Localizer = toml::from_str(EN_LOCALE_RAW).unwrap();
+        let mut labels = SampledLabels::default();
+        labels.add(RawLabels {
+            rarity: Some(String::from("SR")),
+            category: Some(String::from("CHARACTER")),
+            colors: Some(String::from("Red/Rouge")),
+            attributes: Some(String::from("Slash")),
+        });
+
+        let locale = draft_locale("https://xx.onepiece-cardgame.com", &labels, &reference).unwrap();
+
+        assert!(locale.contains("hostname = \"https://xx.onepiece-cardgame.com\"\n"));
+        assert!(locale.contains("\n[colors]\nred = \"Red\"\n# TODO: green = \"<label>\"\n"));
+        assert!(locale.contains("# TODO: found \"Rouge\", map it to one of the keys above\n"));
+        assert!(locale.contains("super_rare = \"SR\"\n"));
+        assert!(locale.contains("character = \"CHARACTER\"\n"));
+
+        let draft: Localizer = toml::from_str(&locale).unwrap();
+        assert_eq!(draft.match_attribute("Slash").as_deref(), Some("slash"));
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::{ensure, Result};
+use std::path::Path;
+
+use crate::cli::ReadStoreArgs;
+use vegapull::{
+    language::LanguageCode,
+    storage::{DataStore, StoreOptions},
+};
+
+pub fn migrate_store(
+    language: LanguageCode,
+    store_dir: &Path,
+    store_args: &ReadStoreArgs,
+    pretty: bool,
+) -> Result<()> {
+    ensure!(
+        store_dir.is_dir(),
+        "store not found: {}",
+        store_dir.display()
+    );
+
+    let options = StoreOptions {
+        pretty,
+        ..store_args.store_options()
+    };
+    let store = DataStore::create(store_dir, language, &options)?;
+    let migrated = store.migrate()?;
+
+    println!(
+        "migrated {} data files in: {}",
+        migrated,
+        store_dir.display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+pub mod backup;
+pub mod config;
+pub mod diff;
+pub mod export_arrow;
+pub mod export_postgres;
+pub mod export_sheet;
+pub mod locale;
+pub mod migrate;
+pub mod pull_all;
+pub mod pull_cards;
+pub mod pull_packs;
+
+pub use self::backup::{backup_store, restore_store};
+pub use self::config::{show_config, show_coverage, sync_configs, validate_configs};
+pub use self::export_arrow::export_arrow;
+pub use self::export_postgres::export_postgres;
+pub use self::export_sheet::export_sheet;
+pub use self::locale::{export_locale, init_locale};
+pub use self::migrate::migrate_store;
+pub use self::pull_all::pull_all;
+pub use self::pull_cards::pull_cards;
+pub use self::pull_packs::pull_packs;
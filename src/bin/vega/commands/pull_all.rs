@@ -0,0 +1,420 @@
+use anyhow::{bail, ensure, Context, Result};
+use inquire::{Confirm, Select, Text};
+use log::info;
+use rayon::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+use yansi::Paint;
+
+use crate::cli::StoreArgs;
+use crate::commands::{pull_cards, pull_packs};
+use vegapull::{
+    card::{validation, Card, CardId},
+    config,
+    language::LanguageCode,
+    localizer::Localizer,
+    pack::PackId,
+    progress::TerminalProgress,
+    rate_limit::RateLimiter,
+    scraper::{FetchedImages, OpTcgScraper},
+    storage::{
+        DataStore, ImageLayout, KeywordTaxonomy, LanguageIndex, PackStats, PullMode, Reprints,
+        SharedImages, VegaMetaStats,
+    },
+    user_agent::UserAgentPool,
+    utils,
+};
+
+fn print_banner() {
+    let version = env!("CARGO_PKG_VERSION");
+
+    println!("{}", "+-----------------------------------+".yellow());
+    println!(
+        "{} {} {}",
+        "|".yellow(),
+        "vega - One Piece TCG Data Scraper".blue().bold(),
+        "|".yellow()
+    );
+    println!(
+        "{} {} {}",
+        "|".yellow(),
+        format!("version: {version}                   ")
+            .white()
+            .bold(),
+        "|".yellow()
+    );
+    println!("{}", "+-----------------------------------+\n".yellow());
+}
+
+struct InteractiveInputs {
+    language: LanguageCode,
+    data_dir: PathBuf,
+    download_images: bool,
+}
+
+fn get_inputs_from_user() -> Result<InteractiveInputs> {
+    let language = Select::new("Choose a language:", Localizer::supported_languages())
+        .prompt()?
+        .code;
+
+    info!("using language: {:?}", language);
+
+    let download_dir = Text::new("Enter location to save data:")
+        .with_default(&utils::get_default_data_dirname(language))
+        .prompt()?;
+
+    let download_dir = PathBuf::from(&download_dir);
+    if download_dir.exists() {
+        handle_existing_dir(&download_dir)?;
+    }
+
+    info!("prompting user whether to download images");
+    let download_images = Confirm::new("Download images as well?")
+        .with_default(false)
+        .with_help_message("Downlading images might take some time")
+        .prompt()?;
+
+    Ok(InteractiveInputs {
+        language,
+        data_dir: download_dir,
+        download_images,
+    })
+}
+
+fn handle_existing_dir(data_dir: &PathBuf) -> Result<()> {
+    info!(
+        "directory `{}` exists, prompting user for removal",
+        data_dir.display()
+    );
+
+    let replace_existing_dir = Confirm::new(&format!(
+        "Directory '{}' already exists. Overwrite?",
+        data_dir.display()
+    ))
+    .with_default(false)
+    .with_help_message("This will delete all existing data in this directory")
+    .prompt()?;
+
+    info!("user input: {}", replace_existing_dir);
+
+    if replace_existing_dir {
+        fs::remove_dir_all(data_dir)?;
+        eprintln!("Cleared directory: {}", data_dir.display());
+    } else {
+        bail!("Aborted, directory has been kept: `{}`", data_dir.display());
+    }
+
+    Ok(())
+}
+
+pub fn pull_all(
+    languages: Vec<LanguageCode>,
+    output_dir: Option<PathBuf>,
+    locale_file: Option<&Path>,
+    with_images: bool,
+    store_args: &StoreArgs,
+    user_agents: UserAgentPool,
+) -> Result<()> {
+    if languages.is_empty() {
+        return pull_all_interactive(locale_file, store_args, user_agents);
+    }
+
+    ensure!(
+        locale_file.is_none() || languages.len() == 1,
+        "--locale-file can only be used when pulling a single language"
+    );
+    pull_all_languages(
+        &languages,
+        output_dir,
+        locale_file,
+        with_images,
+        store_args,
+        user_agents,
+    )
+}
+
+fn pull_all_interactive(
+    locale_file: Option<&Path>,
+    store_args: &StoreArgs,
+    user_agents: UserAgentPool,
+) -> Result<()> {
+    print_banner();
+
+    let inputs = get_inputs_from_user()?;
+
+    let progress = TerminalProgress::default();
+    let localizer = Localizer::load_or_file(inputs.language, locale_file)?;
+    let scraper = OpTcgScraper::builder()
+        .localizer(localizer)
+        .user_agents(user_agents)
+        .progress(progress.for_language(inputs.language))
+        .image_jobs(store_args.image_jobs)
+        .partial_dir(config::get_partial_downloads_dir().ok())
+        .rate_limit(store_args.page_rate, store_args.image_rate)
+        .image_quality(store_args.image_quality)
+        .build()?;
+    let store = DataStore::create(
+        &inputs.data_dir,
+        inputs.language,
+        &store_args.store_options(),
+    )?;
+
+    pull_dataset(
+        &scraper,
+        &store,
+        inputs.language,
+        &progress.for_language(inputs.language),
+        inputs.download_images,
+        store_args.with_pack_images,
+        store_args.images_only,
+    )?;
+
+    eprintln!(
+        "\nFinal data is available in: {}",
+        inputs.data_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Pull several languages in one run, each into its own sub-store under a common root
+fn pull_all_languages(
+    languages: &[LanguageCode],
+    output_dir: Option<PathBuf>,
+    locale_file: Option<&Path>,
+    with_images: bool,
+    store_args: &StoreArgs,
+    user_agents: UserAgentPool,
+) -> Result<()> {
+    let root_dir = match output_dir {
+        Some(output_dir) => output_dir,
+        None => utils::get_default_multi_data_dir()?,
+    };
+
+    let mut seen = HashSet::new();
+    let languages: Vec<LanguageCode> = languages
+        .iter()
+        .copied()
+        .filter(|language| seen.insert(*language))
+        .collect();
+    info!(
+        "pulling {} languages into: {}",
+        languages.len(),
+        root_dir.display()
+    );
+
+    let shared_images = Arc::new(SharedImages::default());
+    let link_images =
+        !store_args.no_image_links && store_args.images.image_layout == ImageLayout::Flat;
+
+    // Languages are pulled at the same time: they share connections, rate limits and the
+    // terminal
+    let client = OpTcgScraper::build_client()?;
+    let image_client = OpTcgScraper::build_client()?;
+    let page_limiter = Arc::new(RateLimiter::new(store_args.page_rate));
+    let image_limiter = Arc::new(RateLimiter::new(store_args.image_rate));
+    let progress = TerminalProgress::default();
+    let user_agents = Arc::new(user_agents);
+
+    let scrapers = languages
+        .iter()
+        .map(|&language| {
+            let localizer = Localizer::load_or_file(language, locale_file)?;
+            let scraper = OpTcgScraper::builder()
+                .localizer(localizer)
+                .user_agents(user_agents.clone())
+                .progress(progress.for_language(language))
+                .client(client.clone())
+                .image_client(image_client.clone())
+                .image_jobs(store_args.image_jobs)
+                .partial_dir(config::get_partial_downloads_dir().ok())
+                .rate_limiters(page_limiter.clone(), image_limiter.clone())
+                .image_quality(store_args.image_quality)
+                .build()?;
+            let mut store =
+                DataStore::for_language(&root_dir, language, &store_args.store_options())?;
+            if link_images {
+                store = store.with_shared_images(shared_images.clone());
+            }
+            Ok((language, scraper, store, progress.for_language(language)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let start = SystemTime::now();
+
+    let pulled = scrapers
+        .par_iter()
+        .map(|(language, scraper, store, progress)| {
+            pull_dataset(
+                scraper,
+                store,
+                *language,
+                progress,
+                with_images,
+                store_args.with_pack_images,
+                store_args.images_only,
+            )
+            .with_context(|| format!("failed to pull {} dataset", language))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut index = LanguageIndex::default();
+    for ((language, _, _, _), cards) in scrapers.iter().zip(&pulled) {
+        index.add_cards(*language, cards.values().flatten());
+    }
+    index.write(&root_dir, store_args.pretty)?;
+
+    eprintln!(
+        "\nFinal data for {} languages is available in: {}",
+        languages.len(),
+        root_dir.display()
+    );
+    eprintln!("Full download took: {:?}", start.elapsed()?);
+
+    Ok(())
+}
+
+fn pull_dataset(
+    scraper: &OpTcgScraper,
+    store: &DataStore,
+    language: LanguageCode,
+    progress: &TerminalProgress,
+    download_images: bool,
+    download_pack_images: bool,
+    images_only: bool,
+) -> Result<HashMap<PackId, Vec<Card>>> {
+    if images_only {
+        progress.println("Reading stored cards...");
+        let all_cards: HashMap<PackId, Vec<Card>> = store.read_all_cards()?.into_iter().collect();
+        pull_cards::backfill_images(
+            scraper,
+            store,
+            all_cards.values().flatten().cloned().collect(),
+        )?;
+        return Ok(all_cards);
+    }
+
+    progress.println("Fetching list of packs...");
+
+    let start = SystemTime::now();
+
+    let mut packs = scraper.fetch_packs()?;
+
+    progress.println(&format!("Found {} packs!", packs.len()));
+
+    let pack_ids: HashSet<PackId> = packs.keys().cloned().collect();
+    if download_pack_images {
+        pull_packs::download_pack_images(scraper, store, &mut packs);
+    }
+
+    progress.println("Now fetching all the cards for each pack...");
+    let fetched = scraper.fetch_all_cards(&pack_ids)?;
+
+    let mut pack_results = BTreeMap::new();
+    let mut all_cards = HashMap::new();
+    for (pack_id, mut fetched) in fetched {
+        let anomalies = validation::validate_cards(&mut fetched.cards, packs.get(&pack_id));
+        let stats = PackStats {
+            cards: fetched.cards.len(),
+            duration_ms: fetched.duration.as_millis().try_into()?,
+            errors: fetched.errors,
+            warnings: fetched.warnings,
+            anomalies,
+            ..Default::default()
+        };
+        pack_results.insert(pack_id.clone(), stats);
+        all_cards.insert(pack_id, fetched.cards);
+    }
+
+    let reprints = Reprints::link(&packs, &mut all_cards);
+    store.write_all(&packs, &all_cards)?;
+    store.write_reprints(&reprints)?;
+    store.write_keywords(&KeywordTaxonomy::collect(
+        scraper.localizer(),
+        all_cards.values().flatten(),
+    ))?;
+
+    let cards_by_id: HashMap<CardId, Card> = all_cards
+        .values()
+        .flatten()
+        .map(|card| (card.id.to_owned(), card.clone()))
+        .collect();
+
+    progress.println(&format!("Wrote data for all {} packs", pack_ids.len()));
+
+    let mut card_back = None;
+    if download_images {
+        progress.println("Downloading all images for every single card...");
+
+        let FetchedImages {
+            images,
+            failed,
+            changed,
+            small,
+        } = pull_cards::download_card_images(scraper, store, &cards_by_id)?;
+
+        for (card_id, count) in &images {
+            let pack_id = &cards_by_id[card_id].pack_id;
+            if let Some(stats) = pack_results.get_mut(pack_id) {
+                stats.images += count;
+            }
+        }
+        for (card_id, e) in &failed {
+            let pack_id = &cards_by_id[card_id].pack_id;
+            if let Some(stats) = pack_results.get_mut(pack_id) {
+                stats.image_errors.push(format!("{}: {}", card_id, e));
+            }
+        }
+        for card_id in &changed {
+            let pack_id = &cards_by_id[card_id].pack_id;
+            if let Some(stats) = pack_results.get_mut(pack_id) {
+                stats.changed_images.push(card_id.to_string());
+            }
+        }
+        for (card_id, size) in &small {
+            let pack_id = &cards_by_id[card_id].pack_id;
+            if let Some(stats) = pack_results.get_mut(pack_id) {
+                stats.small_images.push(format!("{}: {}", card_id, size));
+            }
+        }
+        if !small.is_empty() {
+            progress.println(&format!(
+                "images of {} cards look like placeholders, see vega.meta.toml",
+                small.len()
+            ));
+        }
+        if !failed.is_empty() {
+            progress.println(&format!(
+                "failed to download images of {} cards, see vega.meta.toml",
+                failed.len()
+            ));
+        }
+        card_back = pull_cards::download_card_back(scraper, store);
+    }
+
+    let duration = start.elapsed()?;
+    progress.println(&format!("Full download took: {:?}", duration));
+
+    store.write_checksums()?;
+    store.write_vega_stats(
+        VegaMetaStats::new(
+            language,
+            start.into(),
+            duration.as_millis().try_into()?,
+            download_images,
+            PullMode::All,
+            pack_ids,
+        )
+        .with_locale(scraper.localizer())
+        .with_pack_results(pack_results)
+        .with_card_back(card_back),
+    )?;
+    store.finish()?;
+
+    Ok(all_cards)
+}
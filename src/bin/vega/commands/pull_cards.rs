@@ -0,0 +1,276 @@
+use anyhow::{bail, Result};
+use log::{debug, error, info, warn};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::Path,
+    time::SystemTime,
+};
+
+use crate::cli::StoreArgs;
+use vegapull::{
+    card::{validation, Card, CardId},
+    config,
+    language::LanguageCode,
+    localizer::Localizer,
+    pack::PackId,
+    progress::TerminalProgress,
+    scraper::{FetchedImages, ImageCheck, OpTcgScraper, PackImage},
+    storage::{DataStore, PackStats, PullMode, VegaMetaStats},
+    user_agent::UserAgentPool,
+    utils,
+};
+
+pub fn pull_cards(
+    language: LanguageCode,
+    pack_id: &PackId,
+    output_dir: Option<&Path>,
+    locale_file: Option<&Path>,
+    with_images: bool,
+    store_args: &StoreArgs,
+    user_agents: UserAgentPool,
+) -> Result<()> {
+    let default_data_path = utils::get_default_data_dir(language)?;
+    let output_dir = output_dir.unwrap_or(&default_data_path);
+
+    let localizer = Localizer::load_or_file(language, locale_file)?;
+    let scraper = OpTcgScraper::builder()
+        .localizer(localizer)
+        .user_agents(user_agents)
+        .progress(TerminalProgress::default())
+        .image_jobs(store_args.image_jobs)
+        .partial_dir(config::get_partial_downloads_dir().ok())
+        .rate_limit(store_args.page_rate, store_args.image_rate)
+        .image_quality(store_args.image_quality)
+        .build()?;
+    let store = DataStore::create(output_dir, language, &store_args.store_options())?;
+
+    if store_args.images_only {
+        return backfill_images(&scraper, &store, store.read_cards(pack_id)?);
+    }
+
+    eprintln!("fetching all cards for pack {pack_id}...");
+    let start = SystemTime::now();
+
+    let fetched = scraper.fetch_cards(pack_id)?;
+    let mut cards = fetched.cards;
+    if cards.is_empty() {
+        error!("No cards available for pack {}", pack_id);
+        bail!("No cards found");
+    }
+
+    let anomalies = validation::validate_cards(&mut cards, None);
+    store.write_cards(pack_id, &cards)?;
+
+    let mut stats = PackStats {
+        cards: cards.len(),
+        duration_ms: fetched.duration.as_millis().try_into()?,
+        errors: fetched.errors,
+        anomalies,
+        ..Default::default()
+    };
+
+    eprintln!("successfully fetched {} cards!", cards.len());
+
+    let mut card_back = None;
+    if with_images {
+        eprintln!("Downloading all images for every single card...");
+
+        let cards_by_id: HashMap<CardId, Card> = cards
+            .into_iter()
+            .map(|card| (card.id.to_owned(), card))
+            .collect();
+
+        let FetchedImages {
+            images,
+            failed,
+            changed,
+            small,
+        } = download_card_images(&scraper, &store, &cards_by_id)?;
+        stats.images = images.values().sum();
+        stats.image_errors = failed
+            .iter()
+            .map(|(card_id, e)| format!("{}: {}", card_id, e))
+            .collect();
+        stats.changed_images = changed.iter().map(CardId::to_string).collect();
+        stats.small_images = small
+            .iter()
+            .map(|(card_id, size)| format!("{}: {}", card_id, size))
+            .collect();
+        if !small.is_empty() {
+            eprintln!(
+                "images of {} cards look like placeholders, see vega.meta.toml",
+                small.len()
+            );
+        }
+        if !failed.is_empty() {
+            eprintln!(
+                "failed to download images of {} cards, see vega.meta.toml",
+                failed.len()
+            );
+        }
+        card_back = download_card_back(&scraper, &store);
+    }
+
+    println!(
+        "downloaded cards for pack {} to: {}",
+        pack_id,
+        output_dir.display()
+    );
+
+    let duration = start.elapsed()?;
+
+    info!("list_cards took: {:?}", duration);
+
+    store.write_checksums()?;
+    store.write_vega_stats(
+        VegaMetaStats::new(
+            language,
+            start.into(),
+            duration.as_millis().try_into()?,
+            with_images,
+            PullMode::SinglePack,
+            HashSet::from([pack_id.clone()]),
+        )
+        .with_locale(scraper.localizer())
+        .with_pack_results(BTreeMap::from([(pack_id.clone(), stats)]))
+        .with_card_back(card_back),
+    )?;
+    store.finish()?;
+
+    Ok(())
+}
+
+/// Download the images of `cards_by_id` that are missing from the store, or that the site
+/// reports as changed with `--check-images`, writing each card's images as soon as they are
+/// downloaded. Returns how many were written by card along with the cards whose images failed
+pub fn download_card_images(
+    scraper: &OpTcgScraper,
+    store: &DataStore,
+    cards_by_id: &HashMap<CardId, Card>,
+) -> Result<FetchedImages> {
+    let total = cards_by_id.len();
+    let mut cards = store.missing_images(cards_by_id.values().collect())?;
+    let missing: HashSet<&CardId> = cards.iter().map(|card| &card.id).collect();
+    let stored: Vec<&Card> = cards_by_id
+        .values()
+        .filter(|card| !missing.contains(&card.id))
+        .collect();
+
+    let mut changed = BTreeSet::new();
+    let to_check = store.images_to_check(stored)?;
+    if !to_check.is_empty() {
+        eprintln!(
+            "checking whether the {} stored images changed on the site...",
+            to_check.len()
+        );
+        for (card, check) in scraper.check_all_card_images(&to_check)? {
+            match check {
+                ImageCheck::Unchanged => continue,
+                ImageCheck::Changed => {
+                    changed.insert(card.id.clone());
+                }
+                ImageCheck::Unknown => debug!("no validators stored for {}", card.id),
+            }
+            cards.push(card);
+        }
+        eprintln!(
+            "images of {} cards changed, {} cards stored without validators are downloaded again",
+            changed.len(),
+            cards.len() - missing.len() - changed.len()
+        );
+    } else if cards.len() < total {
+        eprintln!(
+            "skipping {} cards whose images are already downloaded (--force-images to download them again)",
+            total - cards.len()
+        );
+    }
+    let mut fetched = scraper.fetch_all_card_images(&cards, |card, card_images| {
+        store
+            .write_image(card, card_images.front)
+            .map_err(|e| e.context(format!("failed to write image of {}", card.id)))?;
+        if let Some(back) = card_images.back {
+            store
+                .write_back_image(card, back)
+                .map_err(|e| e.context(format!("failed to write back image of {}", card.id)))?;
+        }
+        store.record_image_variants(
+            card,
+            card_images.front_variant.as_deref(),
+            card_images.back_variant.as_deref(),
+        );
+        store.record_image_validators(
+            card,
+            &card_images.front_validators,
+            &card_images.back_validators,
+        );
+        debug!("wrote image_data for: {}", card.id);
+        Ok(())
+    })?;
+    let failed: Vec<(&Card, &str)> = fetched
+        .failed
+        .iter()
+        .map(|(card_id, e)| (&cards_by_id[card_id], e.as_str()))
+        .collect();
+    store.write_image_placeholders(&failed)?;
+    changed.retain(|card_id| fetched.images.contains_key(card_id));
+    fetched.changed = changed;
+
+    store.write_image_index()?;
+    Ok(fetched)
+}
+
+/// Download the card back and write it to the store, returning its path there. A missing card back
+/// does not fail the pull
+pub fn download_card_back(scraper: &OpTcgScraper, store: &DataStore) -> Option<String> {
+    let written = scraper
+        .fetch_card_back()
+        .and_then(|PackImage { url, data }| store.write_card_back(&url, data));
+
+    match written {
+        Ok(path) => Some(path),
+        Err(e) => {
+            warn!("failed to download the card back: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Download the images missing for cards already in the store, without scraping them again
+pub fn backfill_images(scraper: &OpTcgScraper, store: &DataStore, cards: Vec<Card>) -> Result<()> {
+    eprintln!(
+        "downloading missing images of {} stored cards...",
+        cards.len()
+    );
+
+    let cards_by_id: HashMap<CardId, Card> = cards
+        .into_iter()
+        .map(|card| (card.id.to_owned(), card))
+        .collect();
+    let FetchedImages {
+        images,
+        failed,
+        changed,
+        small,
+    } = download_card_images(scraper, store, &cards_by_id)?;
+
+    let downloaded: usize = images.values().sum();
+    println!("downloaded {} images", downloaded);
+    for card_id in &changed {
+        info!("image of {} changed on the site", card_id);
+    }
+    if !small.is_empty() {
+        eprintln!("images of {} cards look like placeholders", small.len());
+    }
+    for (card_id, e) in &failed {
+        error!("no image for {}: {}", card_id, e);
+    }
+
+    store.write_checksums()?;
+    store.finish()?;
+
+    if !failed.is_empty() {
+        bail!("failed to download images of {} cards", failed.len());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use log::{debug, warn};
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    time::SystemTime,
+};
+
+use crate::cli::StoreArgs;
+use vegapull::{
+    config,
+    language::LanguageCode,
+    localizer::Localizer,
+    pack::{Pack, PackId},
+    scraper::{OpTcgScraper, PackImage},
+    storage::{DataStore, PullMode, VegaMetaStats},
+    user_agent::UserAgentPool,
+    utils,
+};
+
+pub fn pull_packs(
+    language: LanguageCode,
+    output_dir: Option<&Path>,
+    locale_file: Option<&Path>,
+    store_args: &StoreArgs,
+    user_agents: UserAgentPool,
+) -> Result<()> {
+    let default_data_path = utils::get_default_data_dir(language)?;
+    let output_dir = output_dir.unwrap_or(&default_data_path);
+
+    let localizer = Localizer::load_or_file(language, locale_file)?;
+    let scraper = OpTcgScraper::builder()
+        .localizer(localizer)
+        .user_agents(user_agents)
+        .image_jobs(store_args.image_jobs)
+        .partial_dir(config::get_partial_downloads_dir().ok())
+        .rate_limit(store_args.page_rate, store_args.image_rate)
+        .build()?;
+    let store = DataStore::create(output_dir, language, &store_args.store_options())?;
+
+    eprintln!("fetching list of packs...");
+    let start = SystemTime::now();
+
+    let mut packs = scraper.fetch_packs()?;
+    let pack_ids: HashSet<PackId> = packs.keys().cloned().collect();
+    if store_args.with_pack_images {
+        download_pack_images(&scraper, &store, &mut packs);
+    }
+    store.write_packs(&packs)?;
+
+    println!(
+        "downloaded {} packs to: {}",
+        packs.len(),
+        output_dir.display()
+    );
+
+    let duration = start.elapsed()?;
+
+    debug!("pull_packs took: {:?}", duration);
+
+    store.write_checksums()?;
+    store.write_vega_stats(
+        VegaMetaStats::new(
+            language,
+            start.into(),
+            duration.as_millis().try_into()?,
+            false,
+            PullMode::PackListOnly,
+            pack_ids,
+        )
+        .with_locale(scraper.localizer()),
+    )?;
+    store.finish()?;
+
+    Ok(())
+}
+
+/// Download the artwork of every pack and reference it from the packs, packs whose artwork fails
+/// to download are left without
+pub fn download_pack_images(
+    scraper: &OpTcgScraper,
+    store: &DataStore,
+    packs: &mut HashMap<PackId, Pack>,
+) {
+    eprintln!("downloading artwork of {} packs...", packs.len());
+
+    packs.par_iter_mut().for_each(|(pack_id, pack)| {
+        let write = |kind: &str, image| -> vegapull::error::Result<Option<String>> {
+            match image {
+                Some(PackImage { url, data }) => {
+                    Ok(Some(store.write_pack_image(pack_id, kind, &url, data)?))
+                }
+                None => Ok(None),
+            }
+        };
+
+        let written = scraper
+            .fetch_pack_images(pack_id)
+            .and_then(|images| Ok((write("cover", images.cover)?, write("logo", images.logo)?)));
+        match written {
+            Ok((cover_image, logo_image)) => {
+                pack.cover_image = cover_image;
+                pack.logo_image = logo_image;
+            }
+            Err(e) => warn!("failed to download artwork of pack {}: {:#}", pack_id, e),
+        }
+    });
+}
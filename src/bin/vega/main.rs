@@ -0,0 +1,163 @@
+use anyhow::Result;
+use clap::Parser;
+use log::{error, info, warn, LevelFilter};
+use std::{env, process::ExitCode};
+
+use cli::Cli;
+use vegapull::config::{self, initialize_configs};
+use vegapull::user_agent::UserAgentPool;
+
+mod cli;
+mod commands;
+
+fn main() -> ExitCode {
+    let args = Cli::parse();
+    env_logger::Builder::new()
+        .filter_module("vegapull", LevelFilter::Debug)
+        .filter_module("html5ever", LevelFilter::Warn)
+        .filter_module("selectors", LevelFilter::Warn)
+        .filter_level(args.verbose.log_level_filter())
+        .init();
+
+    match process_args(args) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn process_args(args: Cli) -> Result<()> {
+    // Locales and settings of a pull are all read from its `--config-dir`
+    if let cli::Commands::Pull {
+        config_path: Some(config_dir),
+        ..
+    } = &args.command
+    {
+        env::set_var(config::CONFIG_VAR, config_dir);
+    }
+
+    info!("initialize config");
+    if let Err(e) = initialize_configs() {
+        warn!(
+            "could not initialize config directory, using built-in locales: {}",
+            e
+        );
+    }
+
+    match args.command {
+        cli::Commands::Pull {
+            command,
+            language,
+            output_dir,
+            config_path: _,
+            locale_file,
+            user_agent,
+            user_agent_strategy,
+            store_args,
+        } => {
+            let (language, locale_file) = language.resolve(locale_file)?;
+            let settings = config::load_settings()?;
+            let user_agents = UserAgentPool::resolve(&settings, user_agent, user_agent_strategy)?;
+
+            match command {
+                cli::PullSubCommands::All {
+                    languages,
+                    with_images,
+                } => commands::pull_all(
+                    languages,
+                    output_dir,
+                    locale_file.as_deref(),
+                    with_images,
+                    &store_args,
+                    user_agents,
+                ),
+                cli::PullSubCommands::Packs => commands::pull_packs(
+                    language,
+                    output_dir.as_deref(),
+                    locale_file.as_deref(),
+                    &store_args,
+                    user_agents,
+                ),
+                cli::PullSubCommands::Cards {
+                    pack_id,
+                    with_images,
+                } => commands::pull_cards(
+                    language,
+                    &pack_id,
+                    output_dir.as_deref(),
+                    locale_file.as_deref(),
+                    with_images,
+                    &store_args,
+                    user_agents,
+                ),
+            }
+        }
+        cli::Commands::Export {
+            command,
+            language,
+            input_dir,
+            store_args,
+        } => match command {
+            cli::ExportSubCommands::Arrow { output_dir } => {
+                commands::export_arrow(language, &input_dir, &store_args, &output_dir)
+            }
+            cli::ExportSubCommands::Postgres { dsn } => {
+                commands::export_postgres(language, &input_dir, &store_args, &dsn)
+            }
+            cli::ExportSubCommands::Sheet {
+                pack_id,
+                output_dir,
+                columns,
+                cell_width,
+                image_args,
+            } => commands::export_sheet(
+                language,
+                &input_dir,
+                &image_args.store_options(&store_args),
+                &pack_id,
+                &output_dir,
+                columns,
+                cell_width,
+            ),
+        },
+        cli::Commands::Backup {
+            store_dir,
+            dest_dir,
+        } => commands::backup_store(&store_dir, &dest_dir),
+        cli::Commands::Restore {
+            backup_file,
+            store_dir,
+            force,
+        } => commands::restore_store(&backup_file, &store_dir, force),
+        cli::Commands::Migrate {
+            store_dir,
+            language,
+            store_args,
+            pretty,
+        } => commands::migrate_store(language, &store_dir, &store_args, pretty),
+        cli::Commands::Locale { command } => match command {
+            cli::LocaleSubCommands::Init {
+                code,
+                hostname,
+                sample_packs,
+                output,
+            } => {
+                let settings = config::load_settings()?;
+                let user_agents = UserAgentPool::resolve(&settings, None, None)?;
+                commands::init_locale(&code, &hostname, sample_packs, output, user_agents)
+            }
+            cli::LocaleSubCommands::Export { language, output } => {
+                commands::export_locale(language, output)
+            }
+        },
+        // cli::Commands::Diff { pack_files } => show_diffs(pack_files),
+        cli::Commands::Config { command } => match command {
+            Some(cli::ConfigSubCommands::Validate) => commands::validate_configs(),
+            Some(cli::ConfigSubCommands::Coverage) => commands::show_coverage(),
+            Some(cli::ConfigSubCommands::Sync) => commands::sync_configs(),
+            None => commands::show_config(),
+        },
+    }
+}
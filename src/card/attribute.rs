@@ -1,7 +1,10 @@
-use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-use crate::localizer::Localizer;
+use crate::{
+    error::{Result, VegaError},
+    localizer::Localizer,
+};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub enum CardAttribute {
@@ -13,55 +16,70 @@ pub enum CardAttribute {
     Unknown, // For Imu Leader
 }
 
+/// Built-in attribute icon numbers, used for icons the locale does not map
+const DEFAULT_ICONS: &[(&str, &[CardAttribute])] = &[
+    ("01", &[CardAttribute::Strike]),
+    ("02", &[CardAttribute::Slash]),
+    ("03", &[CardAttribute::Special]),
+    ("04", &[CardAttribute::Ranged]),
+    ("05", &[CardAttribute::Wisdom]),
+    ("06", &[CardAttribute::Slash, CardAttribute::Strike]),
+    ("07", &[CardAttribute::Slash, CardAttribute::Special]),
+    ("08", &[CardAttribute::Strike, CardAttribute::Ranged]),
+    ("09", &[CardAttribute::Strike, CardAttribute::Special]),
+    ("10", &[CardAttribute::Strike, CardAttribute::Wisdom]),
+    ("11", &[CardAttribute::Slash, CardAttribute::Wisdom]),
+    ("12", &[CardAttribute::Unknown]), // Unknown for Imu
+];
+
 impl CardAttribute {
     pub fn parse(localizer: &Localizer, value: &str) -> Result<CardAttribute> {
         match localizer.match_attribute(value.trim()) {
             Some(key) => Ok(Self::from_str(&key)?),
-            None => bail!("Failed to match attribute `{}`", value),
-        }
-    }
-
-    pub fn from_str(value: &str) -> Result<CardAttribute> {
-        match value.to_lowercase().as_str() {
-            "slash" => Ok(Self::Slash),
-            "strike" => Ok(Self::Strike),
-            "ranged" => Ok(Self::Ranged),
-            "special" => Ok(Self::Special),
-            "wisdom" => Ok(Self::Wisdom),
-            "unknown" => Ok(Self::Unknown),
-            _ => bail!("Unsupported attribute `{}`", value),
+            None => Err(VegaError::unparsable("attributes", value)),
         }
     }
 
-    pub fn from_icon_url(url: &str) -> Result<Vec<CardAttribute>> {
+    /// Map an `ico_typeNN.png` icon to its attributes, using the locale's `[attribute_icons]`
+    /// table first and the built-in one otherwise
+    pub fn from_icon_url(localizer: &Localizer, url: &str) -> Result<Vec<CardAttribute>> {
         let file = url
             .rsplit('/')
             .next()
-            .ok_or_else(|| anyhow!("Invalid URL: {}", url))?;
+            .ok_or_else(|| VegaError::unparsable("attributes", url))?;
 
         let stem = file
             .strip_prefix("ico_type")
-            .ok_or_else(|| anyhow!("attribute icon: missing ico_type prefix in: {}", file))?;
+            .ok_or_else(|| VegaError::unparsable("attributes", file))?;
 
         let value = stem
             .split('.')
             .next()
-            .ok_or_else(|| anyhow!("attribute icon: missing file extension in: {}", file))?;
-
-        match value {
-            "01" => Ok(vec![Self::Strike]),
-            "02" => Ok(vec![Self::Slash]),
-            "03" => Ok(vec![Self::Special]),
-            "04" => Ok(vec![Self::Ranged]),
-            "05" => Ok(vec![Self::Wisdom]),
-            "06" => Ok(vec![Self::Slash, Self::Strike]),
-            "07" => Ok(vec![Self::Slash, Self::Special]),
-            "08" => Ok(vec![Self::Strike, Self::Ranged]),
-            "09" => Ok(vec![Self::Strike, Self::Special]),
-            "10" => Ok(vec![Self::Strike, Self::Wisdom]),
-            "11" => Ok(vec![Self::Slash, Self::Wisdom]),
-            "12" => Ok(vec![Self::Unknown]), // Unknown for Imu
-            _ => bail!("Unsupported attribute `{}`", value),
+            .ok_or_else(|| VegaError::unparsable("attributes", file))?;
+
+        if let Some(keys) = localizer.attribute_icons.get(value) {
+            return keys.iter().map(|key| Self::from_str(key)).collect();
+        }
+
+        match DEFAULT_ICONS.iter().find(|(number, _)| *number == value) {
+            Some((_, attributes)) => Ok(attributes.to_vec()),
+            None => Err(VegaError::unparsable("attributes", value)),
+        }
+    }
+}
+
+impl FromStr for CardAttribute {
+    type Err = VegaError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "slash" => Ok(Self::Slash),
+            "strike" => Ok(Self::Strike),
+            "ranged" => Ok(Self::Ranged),
+            "special" => Ok(Self::Special),
+            "wisdom" => Ok(Self::Wisdom),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(VegaError::unparsable("attributes", value)),
         }
     }
 }
@@ -69,6 +87,11 @@ impl CardAttribute {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::localizer::EN_LOCALE_RAW;
+
+    fn test_localizer() -> Localizer {
+        toml::from_str(EN_LOCALE_RAW).unwrap()
+    }
 
     #[test]
     fn from_str_slash_returns_ok() {
@@ -118,7 +141,11 @@ mod tests {
     #[test]
     fn from_icon_url_single() {
         assert_eq!(
-            CardAttribute::from_icon_url("/images/cardlist/attribute/ico_type02.png").unwrap(),
+            CardAttribute::from_icon_url(
+                &test_localizer(),
+                "/images/cardlist/attribute/ico_type02.png"
+            )
+            .unwrap(),
             vec![CardAttribute::Slash]
         );
     }
@@ -126,7 +153,11 @@ mod tests {
     #[test]
     fn from_icon_url_combo() {
         assert_eq!(
-            CardAttribute::from_icon_url("/images/cardlist/attribute/ico_type07.png").unwrap(),
+            CardAttribute::from_icon_url(
+                &test_localizer(),
+                "/images/cardlist/attribute/ico_type07.png"
+            )
+            .unwrap(),
             vec![CardAttribute::Slash, CardAttribute::Special]
         );
     }
@@ -134,8 +165,32 @@ mod tests {
     #[test]
     fn from_icon_url_unknown() {
         assert_eq!(
-            CardAttribute::from_icon_url("/images/cardlist/attribute/ico_type12.png").unwrap(),
+            CardAttribute::from_icon_url(
+                &test_localizer(),
+                "/images/cardlist/attribute/ico_type12.png"
+            )
+            .unwrap(),
             vec![CardAttribute::Unknown]
         );
     }
+
+    #[test]
+    fn from_icon_url_uses_locale_table() {
+        let mut localizer = test_localizer();
+        localizer.attribute_icons.insert(
+            String::from("13"),
+            vec![String::from("ranged"), String::from("wisdom")],
+        );
+
+        assert_eq!(
+            CardAttribute::from_icon_url(&localizer, "/images/cardlist/attribute/ico_type13.png")
+                .unwrap(),
+            vec![CardAttribute::Ranged, CardAttribute::Wisdom]
+        );
+        assert!(CardAttribute::from_icon_url(
+            &test_localizer(),
+            "/images/cardlist/attribute/ico_type14.png"
+        )
+        .is_err());
+    }
 }
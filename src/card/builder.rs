@@ -0,0 +1,218 @@
+use super::{Card, CardAttribute, CardCategory, CardColor, CardIdParts, CardRarity};
+use crate::error::Result;
+
+/// Build a card from its ID and whichever fields matter, the others are left empty
+/// (`Common` `Character` without stats, effect or trigger)
+#[derive(Debug, Clone)]
+pub struct CardBuilder {
+    id: String,
+    pack_id: String,
+    name: String,
+    rarity: CardRarity,
+    category: CardCategory,
+    img_url: Option<String>,
+    cost: Option<i32>,
+    life: Option<i32>,
+    attributes: Vec<CardAttribute>,
+    power: Option<i32>,
+    counter: Option<i32>,
+    colors: Vec<CardColor>,
+    block_number: Option<i32>,
+    types: Vec<String>,
+    effect: String,
+    trigger: Option<String>,
+}
+
+impl Default for CardBuilder {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            pack_id: String::from("000000"),
+            name: String::new(),
+            rarity: CardRarity::Common,
+            category: CardCategory::Character,
+            img_url: None,
+            cost: None,
+            life: None,
+            attributes: Vec::new(),
+            power: None,
+            counter: None,
+            colors: Vec::new(),
+            block_number: None,
+            types: Vec::new(),
+            effect: String::new(),
+            trigger: None,
+        }
+    }
+}
+
+impl Card {
+    pub fn builder() -> CardBuilder {
+        CardBuilder::default()
+    }
+}
+
+impl CardBuilder {
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    pub fn pack_id(mut self, pack_id: &str) -> Self {
+        self.pack_id = pack_id.to_string();
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn rarity(mut self, rarity: CardRarity) -> Self {
+        self.rarity = rarity;
+        self
+    }
+
+    pub fn category(mut self, category: CardCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Defaults to the site's URL for the ID (`../images/cardlist/card/<id>.png`)
+    pub fn img_url(mut self, img_url: &str) -> Self {
+        self.img_url = Some(img_url.to_string());
+        self
+    }
+
+    pub fn cost(mut self, cost: i32) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+
+    pub fn life(mut self, life: i32) -> Self {
+        self.life = Some(life);
+        self
+    }
+
+    pub fn attributes(mut self, attributes: Vec<CardAttribute>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn power(mut self, power: i32) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    pub fn counter(mut self, counter: i32) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    pub fn colors(mut self, colors: Vec<CardColor>) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    pub fn block_number(mut self, block_number: i32) -> Self {
+        self.block_number = Some(block_number);
+        self
+    }
+
+    pub fn types(mut self, types: &[&str]) -> Self {
+        self.types = types
+            .iter()
+            .map(|card_type| card_type.to_string())
+            .collect();
+        self
+    }
+
+    pub fn effect(mut self, effect: &str) -> Self {
+        self.effect = effect.to_string();
+        self
+    }
+
+    pub fn trigger(mut self, trigger: &str) -> Self {
+        self.trigger = Some(trigger.to_string());
+        self
+    }
+
+    /// Fails if the card or pack ID is not a valid ID
+    pub fn build(self) -> Result<Card> {
+        let id_parts = CardIdParts::parse(&self.id).ok();
+        let img_url = self
+            .img_url
+            .unwrap_or_else(|| format!("../images/cardlist/card/{}.png", self.id));
+
+        Ok(Card {
+            id: self.id.parse()?,
+            pack_id: self.pack_id.parse()?,
+            set_code: id_parts.as_ref().map(|parts| parts.set_code.clone()),
+            card_number: id_parts.as_ref().map(|parts| parts.card_number),
+            variant_suffix: id_parts.and_then(|parts| parts.variant_suffix),
+            name: self.name,
+            rarity: self.rarity,
+            category: self.category,
+            raw_rarity: None,
+            raw_category: None,
+            raw_colors: None,
+            raw_attributes: None,
+            img_url,
+            img_full_url: None,
+            img_url_canonical: None,
+            img_back_url: None,
+            img_back_full_url: None,
+            cost: self.cost,
+            life: self.life,
+            attributes: self.attributes,
+            power: self.power,
+            counter: self.counter,
+            colors: self.colors,
+            block_number: self.block_number,
+            types: self.types,
+            types_canonical: Vec::new(),
+            effect: self.effect,
+            effect_segments: Vec::new(),
+            don_cost: None,
+            don_attached: None,
+            trigger: self.trigger,
+            trigger_keywords: Vec::new(),
+            reprint_of: None,
+            appears_in: Vec::new(),
+            products: Vec::new(),
+            source_url: None,
+            scraped_at: None,
+            variants: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_fills_defaults_and_id_parts() {
+        let card = Card::builder()
+            .id("OP01-025_p1")
+            .name("Roronoa Zoro")
+            .rarity(CardRarity::SuperRare)
+            .power(5000)
+            .build()
+            .unwrap();
+
+        assert_eq!(card.pack_id, "000000");
+        assert_eq!(card.set_code.as_deref(), Some("OP01"));
+        assert_eq!(card.card_number, Some(25));
+        assert_eq!(card.variant_suffix.as_deref(), Some("p1"));
+        assert_eq!(card.img_url, "../images/cardlist/card/OP01-025_p1.png");
+        assert_eq!(card.category, CardCategory::Character);
+        assert_eq!(card.cost, None);
+    }
+
+    #[test]
+    fn build_rejects_invalid_ids() {
+        assert!(Card::builder().build().is_err());
+        assert!(Card::builder().id("OP01/025").build().is_err());
+    }
+}
@@ -1,7 +1,10 @@
-use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-use crate::localizer::Localizer;
+use crate::{
+    error::{Result, VegaError},
+    localizer::Localizer,
+};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub enum CardCategory {
@@ -16,18 +19,22 @@ impl CardCategory {
     pub fn parse(localizer: &Localizer, value: &str) -> Result<CardCategory> {
         match localizer.match_category(value.trim()) {
             Some(key) => Ok(Self::from_str(&key)?),
-            None => bail!("Failed to match category `{}`", value),
+            None => Err(VegaError::unparsable("category", value)),
         }
     }
+}
+
+impl FromStr for CardCategory {
+    type Err = VegaError;
 
-    pub fn from_str(value: &str) -> Result<CardCategory> {
+    fn from_str(value: &str) -> Result<Self> {
         match value.to_lowercase().as_str() {
             "leader" => Ok(Self::Leader),
             "character" => Ok(Self::Character),
             "event" => Ok(Self::Event),
             "stage" => Ok(Self::Stage),
             "don" => Ok(Self::Don),
-            _ => bail!("Unsupported category `{}`", value),
+            _ => Err(VegaError::unparsable("category", value)),
         }
     }
 }
@@ -1,9 +1,13 @@
-use anyhow::{bail, Result};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-use crate::localizer::Localizer;
+use crate::{
+    error::{Result, VegaError},
+    localizer::Localizer,
+};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum CardColor {
     Red,
     Green,
@@ -11,17 +15,27 @@ pub enum CardColor {
     Purple,
     Black,
     Yellow,
+    /// Label missing from the locale file (e.g. a color introduced by a new set), kept as is
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl CardColor {
     pub fn parse(localizer: &Localizer, value: &str) -> Result<CardColor> {
         match localizer.match_color(value.trim()) {
             Some(key) => Ok(Self::from_str(&key)?),
-            None => bail!("Failed to match color `{}`", value),
+            None => {
+                warn!("unknown color `{}`, keeping it as is", value);
+                Ok(Self::Unknown(value.trim().to_string()))
+            }
         }
     }
+}
+
+impl FromStr for CardColor {
+    type Err = VegaError;
 
-    pub fn from_str(value: &str) -> Result<CardColor> {
+    fn from_str(value: &str) -> Result<Self> {
         match value.to_lowercase().as_str() {
             "red" => Ok(Self::Red),
             "green" => Ok(Self::Green),
@@ -29,7 +43,7 @@ impl CardColor {
             "purple" => Ok(Self::Purple),
             "black" => Ok(Self::Black),
             "yellow" => Ok(Self::Yellow),
-            _ => bail!("Unsupported color `{}`", value),
+            _ => Err(VegaError::unparsable("colors", value)),
         }
     }
 }
@@ -72,4 +86,18 @@ mod tests {
     fn from_str_invalid_returns_err() {
         assert!(CardColor::from_str("not a valid color").is_err());
     }
+
+    #[test]
+    fn parse_unknown_returns_unknown() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+
+        assert_eq!(
+            CardColor::parse(&localizer, " Red ").unwrap(),
+            CardColor::Red
+        );
+        assert_eq!(
+            CardColor::parse(&localizer, "Orange").unwrap(),
+            CardColor::Unknown(String::from("Orange"))
+        );
+    }
 }
@@ -0,0 +1,67 @@
+use regex::Regex;
+use std::sync::LazyLock;
+use unicode_normalization::UnicodeNormalization;
+
+/// DON!! cards to attach, e.g. `DON!! x2`
+static ATTACHED_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:DON|ドン)!!\s*[xX×]\s*(\d+)").unwrap());
+/// DON!! cards to return, e.g. `DON!! −1`
+static COST_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:DON|ドン)!!\s*[-−]\s*(\d+)").unwrap());
+
+/// DON!! requirements written in a card effect
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DonRequirements {
+    /// DON!! cards to return to activate the effect (`DON!! −1`)
+    pub cost: Option<i32>,
+    /// DON!! cards that must be attached to the card (`[DON!! x2]`)
+    pub attached: Option<i32>,
+}
+
+impl DonRequirements {
+    /// Find the first DON!! cost and attachment requirement of an effect
+    pub fn parse(effect: &str) -> Self {
+        // NFKC turns full-width `！` and `－` into their ASCII equivalents
+        let effect = effect.nfkc().collect::<String>();
+
+        let first_number = |reg: &Regex| -> Option<i32> {
+            reg.captures(&effect)
+                .and_then(|captures| captures[1].parse().ok())
+        };
+
+        Self {
+            cost: first_number(&COST_REG),
+            attached: first_number(&ATTACHED_REG),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_returns_cost_and_attached() {
+        let don = DonRequirements::parse(
+            "[DON!! x2] [When Attacking] DON!! −1 (You may return the specified number of DON!! cards): K.O. up to 1 of your opponent's Characters.",
+        );
+
+        assert_eq!(don.attached, Some(2));
+        assert_eq!(don.cost, Some(1));
+    }
+
+    #[test]
+    fn parse_japanese_returns_cost_and_attached() {
+        let don = DonRequirements::parse("【ドン!!×1】【アタック時】ドン!!－2：カード1枚を引く。");
+
+        assert_eq!(don.attached, Some(1));
+        assert_eq!(don.cost, Some(2));
+    }
+
+    #[test]
+    fn parse_without_don_returns_none() {
+        let don = DonRequirements::parse("[On Play] Draw 1 card.");
+
+        assert_eq!(don, DonRequirements::default());
+    }
+}
@@ -0,0 +1,114 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use crate::localizer::Localizer;
+
+/// Bracketed part of an effect, e.g. `[On Play]` or `【登場時】`
+static BRACKET_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]|【([^】]+)】").unwrap());
+/// DON!! attachment condition, e.g. `DON!! x1` or `ドン!!×1`
+static DON_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:DON|ドン)\s*(?:!!|！！)\s*[xX×]").unwrap());
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    /// Bracketed ability keyword (e.g. `[Rush]`, `[Blocker]`)
+    Keyword,
+    /// Bracketed timing (e.g. `[On Play]`, `[Activate: Main]`)
+    Timing,
+    /// Bracketed condition (e.g. `[DON!! x1]`, `[Once Per Turn]`)
+    Condition,
+    Text,
+    LineBreak,
+}
+
+/// Piece of an effect, in the order they appear in the flat effect text
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EffectSegment {
+    pub kind: SegmentKind,
+    pub text: String,
+}
+
+impl EffectSegment {
+    fn new(kind: SegmentKind, text: &str) -> Self {
+        Self {
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    /// Split an effect into bracketed keywords, timings and conditions, plain text and line breaks
+    pub fn parse_all(localizer: &Localizer, effect: &str) -> Vec<Self> {
+        let mut segments = Vec::new();
+        for (index, line) in effect.lines().enumerate() {
+            if index > 0 {
+                segments.push(Self::new(SegmentKind::LineBreak, "\n"));
+            }
+
+            let mut last_end = 0;
+            for captures in BRACKET_REG.captures_iter(line) {
+                let bracket = captures.get(0).unwrap();
+                Self::push_text(&mut segments, &line[last_end..bracket.start()]);
+
+                let inner = captures.get(1).or(captures.get(2)).unwrap().as_str().trim();
+                let kind = if DON_REG.is_match(inner) || localizer.match_condition(inner).is_some()
+                {
+                    SegmentKind::Condition
+                } else if localizer.match_timing(inner).is_some() {
+                    SegmentKind::Timing
+                } else {
+                    SegmentKind::Keyword
+                };
+                segments.push(Self::new(kind, inner));
+                last_end = bracket.end();
+            }
+            Self::push_text(&mut segments, &line[last_end..]);
+        }
+
+        segments
+    }
+
+    fn push_text(segments: &mut Vec<Self>, text: &str) {
+        let text = text.trim();
+        if !text.is_empty() {
+            segments.push(Self::new(SegmentKind::Text, text));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localizer::EN_LOCALE_RAW;
+
+    #[test]
+    fn parse_all_returns_segments() {
+        let localizer: Localizer = toml::from_str(EN_LOCALE_RAW).unwrap();
+        let effect = "[Rush]\n[DON!! x1] [When Attacking] [Once Per Turn] Draw 1 card.";
+
+        let segments = EffectSegment::parse_all(&localizer, effect);
+        let kinds: Vec<SegmentKind> = segments.iter().map(|segment| segment.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SegmentKind::Keyword,
+                SegmentKind::LineBreak,
+                SegmentKind::Condition,
+                SegmentKind::Timing,
+                SegmentKind::Condition,
+                SegmentKind::Text,
+            ]
+        );
+        assert_eq!(segments[0].text, "Rush");
+        assert_eq!(segments[5].text, "Draw 1 card.");
+    }
+
+    #[test]
+    fn parse_all_empty_effect_returns_empty() {
+        let localizer: Localizer = toml::from_str(EN_LOCALE_RAW).unwrap();
+
+        assert!(EffectSegment::parse_all(&localizer, "").is_empty());
+    }
+}
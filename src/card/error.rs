@@ -0,0 +1,69 @@
+use std::fmt;
+
+/// Why a card could not be scraped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardScrapeError {
+    /// The site shows no value for the field on this card
+    MissingField {
+        field: &'static str,
+        selector: String,
+    },
+    /// The site shows a value vegapull does not understand
+    UnparsableValue { field: &'static str, raw: String },
+    /// A selector matches several elements: the page layout changed and vegapull needs an update
+    SelectorChanged {
+        field: &'static str,
+        selector: String,
+    },
+}
+
+impl CardScrapeError {
+    /// Field of the card the error is about
+    pub fn field(&self) -> &'static str {
+        match self {
+            CardScrapeError::MissingField { field, .. }
+            | CardScrapeError::UnparsableValue { field, .. }
+            | CardScrapeError::SelectorChanged { field, .. } => field,
+        }
+    }
+
+    /// Whether the error comes from the data of the card rather than from vegapull
+    pub fn is_site_data_issue(&self) -> bool {
+        !matches!(self, CardScrapeError::SelectorChanged { .. })
+    }
+}
+
+impl fmt::Display for CardScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardScrapeError::MissingField { field, selector } => {
+                write!(
+                    f,
+                    "missing card.{}: expected `{}` but got nothing",
+                    field, selector
+                )
+            }
+            CardScrapeError::UnparsableValue { field, raw } => {
+                write!(f, "failed to parse card.{} value `{}`", field, raw)
+            }
+            CardScrapeError::SelectorChanged { selector, .. } => {
+                write!(f, "expected single `{}` but got many", selector)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardScrapeError {}
+
+/// Non-critical field left empty on a card that could still be scraped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrapeWarning {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for ScrapeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "card.{} left empty: {}", self.field, self.message)
+    }
+}
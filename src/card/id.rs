@@ -0,0 +1,133 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Borrow, fmt, ops::Deref, str::FromStr, sync::LazyLock};
+
+use crate::{
+    error::{Result, VegaError},
+    utils,
+};
+
+/// Set code, card number and optional variant suffix of a card ID, e.g. `OP15-096_p1`
+static CARD_ID_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Z]+[0-9]*)-([0-9]{3})(?:_([a-z][a-z0-9]*))?$").unwrap());
+
+/// ID of a card as shown on the site (e.g. `OP15-096_p1`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CardId(String);
+
+impl CardId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for CardId {
+    type Error = VegaError;
+
+    fn try_from(value: String) -> Result<Self> {
+        utils::validate_id("card ID", &value).map_err(|_| VegaError::unparsable("id", &value))?;
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for CardId {
+    type Err = VegaError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::try_from(value.to_string())
+    }
+}
+
+impl From<CardId> for String {
+    fn from(id: CardId) -> Self {
+        id.0
+    }
+}
+
+impl Deref for CardId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for CardId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<&str> for CardId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for CardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Structured form of a card ID (e.g. `OP15-096_p1`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardIdParts {
+    pub set_code: String,
+    pub card_number: i32,
+    pub variant_suffix: Option<String>,
+}
+
+impl CardIdParts {
+    pub fn parse(id: &str) -> Result<Self> {
+        let captures = CARD_ID_REG
+            .captures(id)
+            .ok_or_else(|| VegaError::unparsable("id", id))?;
+
+        Ok(Self {
+            set_code: captures[1].to_string(),
+            card_number: captures[2]
+                .parse()
+                .map_err(|_| VegaError::unparsable("id", id))?,
+            variant_suffix: captures.get(3).map(|suffix| suffix.as_str().to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_id_from_str_validates() {
+        assert_eq!("OP01-001".parse::<CardId>().unwrap(), "OP01-001");
+        assert!("OP01/001".parse::<CardId>().is_err());
+        assert!(serde_json::from_str::<CardId>("\"\"").is_err());
+    }
+
+    #[test]
+    fn parse_returns_parts() {
+        assert_eq!(
+            CardIdParts::parse("OP15-096_p1").unwrap(),
+            CardIdParts {
+                set_code: String::from("OP15"),
+                card_number: 96,
+                variant_suffix: Some(String::from("p1")),
+            }
+        );
+
+        let promo = CardIdParts::parse("P-001").unwrap();
+        assert_eq!(promo.set_code, "P");
+        assert_eq!(promo.card_number, 1);
+        assert_eq!(promo.variant_suffix, None);
+    }
+
+    #[test]
+    fn parse_invalid_id_returns_err() {
+        assert!(CardIdParts::parse("OP15096").is_err());
+        assert!(CardIdParts::parse("OP15-96").is_err());
+        assert!(CardIdParts::parse("op15-096").is_err());
+        assert!(CardIdParts::parse("OP15-096_").is_err());
+    }
+}
@@ -1,13 +1,32 @@
 pub mod attribute;
+pub mod builder;
 pub mod category;
 pub mod color;
+pub mod don;
+pub mod effect;
+pub mod error;
+pub mod id;
 pub mod model;
+pub mod ordering;
 pub mod rarity;
+pub mod sanitizer;
 pub mod scraper;
+pub mod text;
+pub mod trigger;
+pub mod validation;
+pub mod variant;
 
 pub use self::attribute::CardAttribute;
 pub use self::category::CardCategory;
 pub use self::color::CardColor;
+pub use self::don::DonRequirements;
+pub use self::effect::EffectSegment;
+pub use self::error::CardScrapeError;
+pub use self::id::{CardId, CardIdParts};
 pub use self::model::Card;
+pub use self::ordering::CardOrder;
 pub use self::rarity::CardRarity;
 pub use self::scraper::CardScraper;
+pub use self::text::TextNormalization;
+pub use self::trigger::CardTrigger;
+pub use self::variant::CardVariant;
@@ -1,16 +1,37 @@
 use std::fmt;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{CardAttribute, CardCategory, CardColor, CardRarity};
+use crate::pack::PackId;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+use super::{
+    CardAttribute, CardCategory, CardColor, CardId, CardRarity, CardVariant, EffectSegment,
+};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Card {
-    pub id: String,
-    pub pack_id: String,
+    pub id: CardId,
+    pub pack_id: PackId,
+    // Parsed from the ID (`OP15-096_p1` -> `OP15`, 96, `p1`), unset if it has an unexpected format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_number: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_suffix: Option<String>,
     pub name: String,
     pub rarity: CardRarity,
     pub category: CardCategory,
+    // Labels as shown on the site (only kept with --include-raw)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_rarity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_colors: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_attributes: Option<String>,
     // pub number: i32,
     // #[serde(skip_serializing)]
     // pub copyright: String,
@@ -18,11 +39,21 @@ pub struct Card {
     // Images
     pub img_url: String,
     pub img_full_url: Option<String>,
+    // `img_url` without its query string and with `.`/`..` resolved (e.g. `/images/cardlist/card/OP01-001.png`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img_url_canonical: Option<String>,
+    // Second face of the card (e.g. the back of some leaders), stored with a `_back` suffix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img_back_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img_back_full_url: Option<String>,
     // pub illustration: CardIllustration,
     // pub illustrator_name: String,
 
     // Gameplay
-    pub cost: Option<i32>, // Only Character, Event, and Stage (called life for Leader)
+    pub cost: Option<i32>, // Only Character, Event, and Stage (Leaders have `life` instead)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub life: Option<i32>, // Only Leader, shown by the site where other cards show their cost
     pub attributes: Vec<CardAttribute>, // Only Leader and Character
     pub power: Option<i32>, // Only Leader and Character
     pub counter: Option<i32>, // Only Character
@@ -30,9 +61,39 @@ pub struct Card {
     pub block_number: Option<i32>,
 
     pub types: Vec<String>,
+    // English names of `types`, only set when the locale maps every one of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types_canonical: Vec<String>,
     pub effect: String,
-    pub trigger: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effect_segments: Vec<EffectSegment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub don_cost: Option<i32>, // DON!! returned to activate the effect (DON!! −1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub don_attached: Option<i32>, // DON!! required on the card ([DON!! x1])
+    pub trigger: Option<String>, // Without its localized label
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trigger_keywords: Vec<String>,
     // pub notes: String,
+
+    // Reprints (only filled by a full pull): first pack printing the card and every pack holding it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reprint_of: Option<PackId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub appears_in: Vec<PackId>,
+    // Decks and boosters the site lists the card in (e.g. `-ROMANCE DAWN- [OP-01]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub products: Vec<String>,
+
+    // Provenance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scraped_at: Option<DateTime<Utc>>,
+
+    // Alternate printings (only filled with --group-variants)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<CardVariant>,
 }
 
 impl fmt::Display for Card {
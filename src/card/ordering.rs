@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+
+use super::Card;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum CardOrder {
+    /// By set code, card number and variant
+    #[default]
+    Set,
+    /// By card number across sets
+    Number,
+    /// From commons to promos
+    Rarity,
+    /// By colors
+    Color,
+}
+
+impl CardOrder {
+    pub fn compare(self, a: &Card, b: &Card) -> Ordering {
+        match self {
+            CardOrder::Set => a.cmp_by_set(b),
+            CardOrder::Number => a.cmp_by_number(b),
+            CardOrder::Rarity => a.cmp_by_rarity(b),
+            CardOrder::Color => a.cmp_by_color(b),
+        }
+    }
+}
+
+/// Comparators giving cards the same order everywhere (data files, exports, tools)
+impl Card {
+    /// By set code, card number and variant (base card first); cards with an unexpected ID
+    /// format come last, ordered by ID
+    pub fn cmp_by_set(&self, other: &Card) -> Ordering {
+        let key = |card: &Card| {
+            (
+                card.set_code.is_none(),
+                card.set_code.clone(),
+                card.card_number,
+                card.variant_suffix.clone(),
+            )
+        };
+
+        key(self)
+            .cmp(&key(other))
+            .then_with(|| self.id.cmp(&other.id))
+    }
+
+    /// By card number across sets, then by set
+    pub fn cmp_by_number(&self, other: &Card) -> Ordering {
+        self.card_number
+            .cmp(&other.card_number)
+            .then_with(|| self.cmp_by_set(other))
+    }
+
+    /// From commons to promos, then by set
+    pub fn cmp_by_rarity(&self, other: &Card) -> Ordering {
+        self.rarity
+            .cmp(&other.rarity)
+            .then_with(|| self.cmp_by_set(other))
+    }
+
+    /// By colors (red, green, blue, purple, black, yellow; multicolor after their first color),
+    /// then by set
+    pub fn cmp_by_color(&self, other: &Card) -> Ordering {
+        self.colors
+            .cmp(&other.colors)
+            .then_with(|| self.cmp_by_set(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{CardColor, CardRarity};
+
+    fn test_card(id: &str, rarity: CardRarity, colors: Vec<CardColor>) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id("569101")
+            .name("Nami")
+            .rarity(rarity)
+            .colors(colors)
+            .build()
+            .unwrap()
+    }
+
+    fn ids(cards: &[Card]) -> Vec<&str> {
+        cards.iter().map(|card| card.id.as_str()).collect()
+    }
+
+    #[test]
+    fn comparators_order_cards() {
+        let mut cards = vec![
+            test_card("OP02-001", CardRarity::Common, vec![CardColor::Green]),
+            test_card("P-001", CardRarity::Promo, vec![CardColor::Red]),
+            test_card("OP01-010_p1", CardRarity::SuperRare, vec![CardColor::Red]),
+            test_card("OP01-010", CardRarity::Rare, vec![CardColor::Blue]),
+            test_card("OP01-002", CardRarity::Common, vec![CardColor::Red]),
+        ];
+
+        cards.sort_by(|a, b| CardOrder::Set.compare(a, b));
+        assert_eq!(
+            ids(&cards),
+            vec!["OP01-002", "OP01-010", "OP01-010_p1", "OP02-001", "P-001"]
+        );
+
+        cards.sort_by(Card::cmp_by_number);
+        assert_eq!(
+            ids(&cards),
+            vec!["OP02-001", "P-001", "OP01-002", "OP01-010", "OP01-010_p1"]
+        );
+
+        cards.sort_by(Card::cmp_by_rarity);
+        assert_eq!(
+            ids(&cards),
+            vec!["OP01-002", "OP02-001", "OP01-010", "OP01-010_p1", "P-001"]
+        );
+
+        cards.sort_by(Card::cmp_by_color);
+        assert_eq!(
+            ids(&cards),
+            vec!["OP01-002", "OP01-010_p1", "P-001", "OP02-001", "OP01-010"]
+        );
+    }
+}
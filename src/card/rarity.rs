@@ -1,30 +1,45 @@
-use anyhow::{bail, Result};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-use crate::localizer::Localizer;
+use crate::{
+    error::{Result, VegaError},
+    localizer::Localizer,
+};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+/// Declared from the most to the least common, which is how rarities are ordered
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum CardRarity {
-    Common = 0,
-    Uncommon = 1,
-    Rare = 2,
-    SuperRare = 3,
-    SecretRare = 4,
-    Leader = 5,
-    Special = 6,
-    TreasureRare = 7,
-    Promo = 8,
+    Common,
+    Uncommon,
+    Rare,
+    SuperRare,
+    SecretRare,
+    Leader,
+    Special,
+    TreasureRare,
+    Promo,
+    /// Label missing from the locale file (e.g. a rarity introduced by a new set), kept as is
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 impl CardRarity {
     pub fn parse(localizer: &Localizer, value: &str) -> Result<CardRarity> {
         match localizer.match_rarity(value) {
             Some(key) => Ok(Self::from_str(&key)?),
-            None => bail!("Failed to match rarity `{}`", value),
+            None => {
+                warn!("unknown rarity `{}`, keeping it as is", value);
+                Ok(Self::Unknown(value.trim().to_string()))
+            }
         }
     }
+}
+
+impl FromStr for CardRarity {
+    type Err = VegaError;
 
-    pub fn from_str(value: &str) -> Result<CardRarity> {
+    fn from_str(value: &str) -> Result<Self> {
         match value.to_lowercase().as_str() {
             "common" => Ok(Self::Common),
             "uncommon" => Ok(Self::Uncommon),
@@ -35,7 +50,7 @@ impl CardRarity {
             "special" => Ok(Self::Special),
             "treasure_rare" => Ok(Self::TreasureRare), // Supposedly added in OP07
             "promo" => Ok(Self::Promo),                // Promo cards (Ultra rare)
-            _ => bail!("Unsupported rarity `{}`", value),
+            _ => Err(VegaError::unparsable("rarity", value)),
         }
     }
 }
@@ -108,4 +123,18 @@ mod tests {
     fn from_str_invalid_returns_err() {
         assert!(CardRarity::from_str("not a valid rarity").is_err())
     }
+
+    #[test]
+    fn unknown_serializes_as_raw_value() {
+        let rarity = CardRarity::Unknown(String::from("SP"));
+        assert_eq!(serde_json::to_string(&rarity).unwrap(), "\"SP\"");
+        assert_eq!(
+            serde_json::from_str::<CardRarity>("\"SP\"").unwrap(),
+            rarity
+        );
+        assert_eq!(
+            serde_json::from_str::<CardRarity>("\"SuperRare\"").unwrap(),
+            CardRarity::SuperRare
+        );
+    }
 }
@@ -0,0 +1,151 @@
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use super::text::{normalize_text, TextNormalization};
+
+/// `<tag>...</tag>` block, e.g. `<h3>Power</h3>`
+static HEADER_REG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>.*?</[^>]*>").unwrap());
+static BR_REG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<br\s*/?>").unwrap());
+static TAG_REG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+/// Named, decimal or hexadecimal entity, e.g. `&amp;`, `&#39;` or `&#x2212;`
+static ENTITY_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizeStep {
+    /// Drop the `<h3>Label</h3>`-like headers the site puts in front of values
+    StripHeaders,
+    /// Turn `<br>` into newlines and drop any other tag
+    StripTags,
+    /// Decode `&amp;`, `&#39;`, `&#x2212;`... entities
+    DecodeEntities,
+    /// Unicode NFKC (full-width letters and digits, compatibility characters...)
+    Nfkc,
+    /// Trim leading and trailing whitespace
+    Trim,
+}
+
+/// Steps turning the HTML of a text field into plain text, set per locale in `[sanitizer]`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Sanitizer {
+    #[serde(default = "Sanitizer::default_steps")]
+    pub steps: Vec<SanitizeStep>,
+    /// Locale-specific fixups, replaced literally once all steps are applied
+    #[serde(default)]
+    pub replacements: BTreeMap<String, String>,
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self {
+            steps: Self::default_steps(),
+            replacements: BTreeMap::new(),
+        }
+    }
+}
+
+impl Sanitizer {
+    fn default_steps() -> Vec<SanitizeStep> {
+        vec![
+            SanitizeStep::StripHeaders,
+            SanitizeStep::StripTags,
+            SanitizeStep::DecodeEntities,
+            SanitizeStep::Trim,
+        ]
+    }
+
+    /// Run every step in order, then the replacements
+    pub fn sanitize(&self, html: &str) -> String {
+        let mut text = html.to_string();
+        for step in &self.steps {
+            text = Self::apply(*step, &text);
+        }
+
+        for (from, to) in &self.replacements {
+            text = text.replace(from.as_str(), to);
+        }
+
+        text
+    }
+
+    fn apply(step: SanitizeStep, value: &str) -> String {
+        match step {
+            SanitizeStep::StripHeaders => strip_headers(value),
+            SanitizeStep::StripTags => {
+                let text = BR_REG.replace_all(value, "\n");
+                TAG_REG.replace_all(&text, "").to_string()
+            }
+            SanitizeStep::DecodeEntities => ENTITY_REG
+                .replace_all(value, |captures: &Captures| {
+                    decode_entity(&captures[1]).unwrap_or_else(|| captures[0].to_string())
+                })
+                .to_string(),
+            SanitizeStep::Nfkc => normalize_text(value, &[TextNormalization::Nfkc]),
+            SanitizeStep::Trim => value.trim().to_string(),
+        }
+    }
+}
+
+/// Remove `<tag>...</tag>` blocks (the labels shown before a value) and trim the rest
+pub fn strip_headers(value: &str) -> String {
+    HEADER_REG.replace_all(value, "").trim().to_string()
+}
+
+fn decode_entity(entity: &str) -> Option<String> {
+    let code = match entity.strip_prefix('#') {
+        Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok()?,
+        Some(decimal) => decimal.parse().ok()?,
+        None => {
+            let decoded = match entity {
+                "amp" => "&",
+                "lt" => "<",
+                "gt" => ">",
+                "quot" => "\"",
+                "apos" => "'",
+                "nbsp" => " ",
+                _ => return None,
+            };
+            return Some(decoded.to_string());
+        }
+    };
+
+    char::from_u32(code).map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_decodes_entities_and_line_breaks() {
+        let html = "[On Play] K.O. up to 1 &lt;Straw Hat Crew&gt; type<br>Draw 1 card.<BR />Tom &amp; Jerry&#39;s &#x2212;1 &unknown;";
+
+        assert_eq!(
+            Sanitizer::default().sanitize(html),
+            "[On Play] K.O. up to 1 <Straw Hat Crew> type\nDraw 1 card.\nTom & Jerry's \u{2212}1 &unknown;"
+        );
+    }
+
+    #[test]
+    fn sanitize_decodes_once() {
+        assert_eq!(Sanitizer::default().sanitize("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn sanitize_uses_configured_steps_and_replacements() {
+        let sanitizer: Sanitizer = toml::from_str(
+            r#"
+            steps = ["strip_headers", "strip_tags", "nfkc", "trim"]
+            replacements = { "K.O." = "KO" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sanitizer.sanitize("<h3>Effect</h3> ［Ｏｎ Ｐｌａｙ］ K.O. 1 &lt;Navy&gt;"),
+            "[On Play] KO 1 &lt;Navy&gt;"
+        );
+    }
+}
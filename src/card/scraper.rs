@@ -1,19 +1,38 @@
-use anyhow::{bail, Context, Result};
-use log::trace;
-use regex::Regex;
+use log::{trace, warn};
 use scraper::{ElementRef, Html};
 use unicode_normalization::UnicodeNormalization;
 
 use crate::{
-    card::{Card, CardAttribute, CardCategory, CardColor, CardRarity},
-    localizer::Localizer,
+    card::{
+        error::{CardScrapeError, ScrapeWarning},
+        sanitizer, Card, CardAttribute, CardCategory, CardColor, CardId, CardIdParts, CardRarity,
+        CardTrigger, DonRequirements, EffectSegment,
+    },
+    error::{Result, VegaError},
+    localizer::{AttributeParsing, Localizer},
+    pack::PackId,
+    utils,
 };
 
+const RARITY_SELECTOR: &str = "dt>div.infoCol>span:nth-child(2)";
+const CATEGORY_SELECTOR: &str = "dt>div.infoCol>span:nth-child(3)";
+const COLORS_SELECTOR: &str = "dd>div.backCol div.color";
+const ATTRIBUTES_SELECTOR: &str = "dd>div.backCol>div.col2>div.attribute>img";
+
 fn normalize_ascii(s: &str) -> String {
     // NFKC converts full-width digits/letters to ASCII equivalents
     s.nfkc().collect::<String>()
 }
 
+/// Labels of a card as shown on the site, to draft locale files from
+#[derive(Debug, Default, Clone)]
+pub struct RawLabels {
+    pub rarity: Option<String>,
+    pub category: Option<String>,
+    pub colors: Option<String>,
+    pub attributes: Option<String>,
+}
+
 pub struct CardScraper {}
 
 impl CardScraper {
@@ -21,51 +40,230 @@ impl CardScraper {
         localizer: &Localizer,
         document: &Html,
         card_id: &str,
-        pack_id: &str,
-    ) -> Result<Card> {
+        pack_id: &PackId,
+    ) -> Result<(Card, Vec<ScrapeWarning>)> {
+        Self::scrape_card(localizer, document, card_id, pack_id).map_err(|e| e.for_card(card_id))
+    }
+
+    fn scrape_card(
+        localizer: &Localizer,
+        document: &Html,
+        card_id: &str,
+        pack_id: &PackId,
+    ) -> Result<(Card, Vec<ScrapeWarning>)> {
         trace!("start create card: `{}`", card_id);
         let dl_elem = Self::get_dl_node(document, card_id.to_string())?;
 
-        let id = Self::fetch_id(dl_elem)?;
-        let pack_id = pack_id.to_string();
+        let id: CardId = Self::fetch_id(dl_elem)?.parse()?;
+        let pack_id = pack_id.clone();
+        let id_parts = match CardIdParts::parse(&id) {
+            Ok(id_parts) => Some(id_parts),
+            Err(e) => {
+                warn!("{}", e);
+                None
+            }
+        };
 
-        let name = Self::fetch_name(dl_elem)?;
+        let name = Self::fetch_name(localizer, dl_elem)?;
+        let mut warnings = Vec::new();
         let rarity = Self::fetch_rarity(localizer, dl_elem)?;
         let category = Self::fetch_category(localizer, dl_elem)?;
-        let img_url = Self::fetch_img_url(dl_elem)?;
+        let img_url = Self::fetch_img_url(localizer, dl_elem)?;
         let img_full_url = None;
-
-        let colors = Self::fetch_colors(localizer, dl_elem)?;
-        let cost = Self::fetch_cost(dl_elem)?;
-        let attributes = Self::fetch_attributes(localizer, dl_elem)?;
-        let power = Self::fetch_power(dl_elem)?;
-        let counter = Self::fetch_counter(dl_elem)?;
-        let block_number = Self::fetch_block_number(dl_elem)?;
-        let types = Self::fetch_types(dl_elem)?;
-        let effect = Self::fetch_effect(dl_elem)?;
-        let trigger = Self::fetch_trigger(dl_elem)?;
+        let img_url_canonical = Some(Self::canonical_img_url(&img_url));
+        let img_back_url = Self::fetch_img_back_url(localizer, dl_elem);
+
+        let colors =
+            Self::tolerate_missing(&category, "colors", Self::fetch_colors(localizer, dl_elem))?;
+        // The cost shown on Leaders is their life
+        let (cost, life) = match category {
+            CardCategory::Leader => (None, Self::fetch_life(localizer, &category, dl_elem)?),
+            _ => (
+                Self::tolerate_missing(&category, "cost", Self::fetch_cost(localizer, dl_elem))?,
+                None,
+            ),
+        };
+        let attributes = Self::tolerate_missing(
+            &category,
+            "attributes",
+            Self::fetch_attributes(localizer, dl_elem),
+        )?;
+        let power =
+            Self::tolerate_missing(&category, "power", Self::fetch_power(localizer, dl_elem))?;
+        let counter = Self::degrade(
+            &mut warnings,
+            "counter",
+            Self::tolerate_missing(
+                &category,
+                "counter",
+                Self::fetch_counter(localizer, dl_elem),
+            ),
+        );
+        let block_number = Self::degrade(
+            &mut warnings,
+            "block_number",
+            Self::tolerate_missing(
+                &category,
+                "block_number",
+                Self::fetch_block_number(localizer, dl_elem),
+            ),
+        );
+        let types =
+            Self::tolerate_missing(&category, "types", Self::fetch_types(localizer, dl_elem))?;
+        let types_canonical = Self::canonical_types(localizer, &types);
+        let effect =
+            Self::tolerate_missing(&category, "effect", Self::fetch_effect(localizer, dl_elem))?;
+        let effect_segments = EffectSegment::parse_all(localizer, &effect);
+        let don = DonRequirements::parse(&effect);
+        let products = Self::fetch_products(localizer, dl_elem)?;
+        let trigger = Self::degrade(
+            &mut warnings,
+            "trigger",
+            Self::fetch_trigger(localizer, dl_elem),
+        );
 
         let card = Card {
             id,
             pack_id,
+            set_code: id_parts.as_ref().map(|parts| parts.set_code.clone()),
+            card_number: id_parts.as_ref().map(|parts| parts.card_number),
+            variant_suffix: id_parts.and_then(|parts| parts.variant_suffix),
             name,
             rarity,
             category,
+            raw_rarity: Self::fetch_raw_text(
+                localizer,
+                dl_elem,
+                "rarity",
+                localizer.selector("rarity", RARITY_SELECTOR),
+            ),
+            raw_category: Self::fetch_raw_text(
+                localizer,
+                dl_elem,
+                "category",
+                localizer.selector("category", CATEGORY_SELECTOR),
+            ),
+            raw_colors: Self::fetch_raw_text(
+                localizer,
+                dl_elem,
+                "colors",
+                localizer.selector("colors", COLORS_SELECTOR),
+            ),
+            raw_attributes: Self::get_child_node(
+                dl_elem,
+                "attributes",
+                localizer.selector("attributes", ATTRIBUTES_SELECTOR),
+            )
+            .ok()
+            .and_then(|img| img.attr("alt"))
+            .map(str::to_string),
             img_url,
             img_full_url,
+            img_url_canonical,
+            img_back_url,
+            img_back_full_url: None,
             colors,
             cost,
+            life,
             attributes,
             power,
             counter,
             block_number,
             types,
+            types_canonical,
             effect,
-            trigger,
+            effect_segments,
+            don_cost: don.cost,
+            don_attached: don.attached,
+            trigger: trigger.as_ref().map(|trigger| trigger.text.clone()),
+            trigger_keywords: trigger.map(|trigger| trigger.keywords).unwrap_or_default(),
+            reprint_of: None,
+            appears_in: Vec::new(),
+            products,
+            source_url: None,
+            scraped_at: None,
+            variants: Vec::new(),
         };
 
         trace!("processed card: `{}`", card);
-        Ok(card)
+        Ok((card, warnings))
+    }
+
+    /// Non-critical fields are left empty with a warning instead of failing the whole card
+    fn degrade<T: Default>(
+        warnings: &mut Vec<ScrapeWarning>,
+        field: &'static str,
+        result: Result<T>,
+    ) -> T {
+        result.unwrap_or_else(|e| {
+            warnings.push(ScrapeWarning {
+                field,
+                message: e.to_string(),
+            });
+            T::default()
+        })
+    }
+
+    pub fn fetch_raw_labels(
+        localizer: &Localizer,
+        document: &Html,
+        card_id: &str,
+    ) -> Result<RawLabels> {
+        let dl_elem = Self::get_dl_node(document, card_id.to_string())?;
+        let raw_colors = Self::get_child_node(
+            dl_elem,
+            "colors",
+            localizer.selector("colors", COLORS_SELECTOR),
+        )
+        .map(|colors| sanitizer::strip_headers(&colors.inner_html()));
+
+        Ok(RawLabels {
+            rarity: Self::fetch_raw_text(
+                localizer,
+                dl_elem,
+                "rarity",
+                localizer.selector("rarity", RARITY_SELECTOR),
+            ),
+            category: Self::fetch_raw_text(
+                localizer,
+                dl_elem,
+                "category",
+                localizer.selector("category", CATEGORY_SELECTOR),
+            ),
+            colors: raw_colors.ok(),
+            attributes: Self::get_child_node(
+                dl_elem,
+                "attributes",
+                localizer.selector("attributes", ATTRIBUTES_SELECTOR),
+            )
+            .ok()
+            .and_then(|img| img.attr("alt"))
+            .map(str::to_string),
+        })
+    }
+
+    /// DON!! cards only show a few fields: leave the missing ones empty instead of failing the card
+    fn tolerate_missing<T: Default>(
+        category: &CardCategory,
+        field: &str,
+        result: Result<T>,
+    ) -> Result<T> {
+        match result {
+            Err(e)
+                if *category == CardCategory::Don
+                    && matches!(
+                        e,
+                        VegaError::Parse {
+                            reason: CardScrapeError::MissingField { .. },
+                            ..
+                        }
+                    ) =>
+            {
+                trace!("card.{} skipped for DON!! card: {}", field, e);
+                Ok(T::default())
+            }
+            result => result,
+        }
     }
 
     // element is top level <dl> tag
@@ -73,28 +271,32 @@ impl CardScraper {
         trace!("fetching card.id...");
         let id = element
             .attr("id")
-            .context("expected to find id attr on <dl>")?
+            .ok_or_else(|| CardScrapeError::MissingField {
+                field: "id",
+                selector: String::from("dl[id]"),
+            })?
             .to_string();
 
         trace!("fetched card.id: {}", id);
         Ok(id)
     }
 
-    pub fn fetch_name(element: ElementRef) -> Result<String> {
-        let sel = "dt>div.cardName";
+    pub fn fetch_name(localizer: &Localizer, element: ElementRef) -> Result<String> {
+        let sel = localizer.selector("name", "dt>div.cardName");
         trace!("fetching card.name ({})...", sel);
 
-        let name = Self::get_child_node(element, sel.to_string())?.inner_html();
+        let name = Self::get_child_node(element, "name", sel)?.inner_html();
+        let name = localizer.sanitizer.sanitize(&name);
 
         trace!("fetched card.name: {}", name);
         Ok(name)
     }
 
     pub fn fetch_rarity(localizer: &Localizer, element: ElementRef) -> Result<CardRarity> {
-        let sel = "dt>div.infoCol>span:nth-child(2)";
+        let sel = localizer.selector("rarity", RARITY_SELECTOR);
         trace!("fetching card.rarity ({})...", sel);
 
-        let raw_rarity = Self::get_child_node(element, sel.to_string())?.inner_html();
+        let raw_rarity = Self::get_child_node(element, "rarity", sel)?.inner_html();
 
         trace!("fetched card.rarity: {}", raw_rarity);
         let rarity = CardRarity::parse(localizer, &raw_rarity)?;
@@ -104,10 +306,10 @@ impl CardScraper {
     }
 
     pub fn fetch_category(localizer: &Localizer, element: ElementRef) -> Result<CardCategory> {
-        let sel = "dt>div.infoCol>span:nth-child(3)";
+        let sel = localizer.selector("category", CATEGORY_SELECTOR);
         trace!("fetching card.category ({})...", sel);
 
-        let raw_category = Self::get_child_node(element, sel.to_string())?.inner_html();
+        let raw_category = Self::get_child_node(element, "category", sel)?.inner_html();
 
         trace!("fetched card.category: {}", raw_category);
         let category = CardCategory::parse(localizer, &raw_category)?;
@@ -116,74 +318,130 @@ impl CardScraper {
         Ok(category)
     }
 
-    pub fn fetch_img_url(element: ElementRef) -> Result<String> {
-        let sel = "dd>div.frontCol>img";
+    /// Strip the query string and fragment of an image URL and resolve its `.` and `..` segments
+    fn canonical_img_url(img_url: &str) -> String {
+        let path = img_url.split(['?', '#']).next().unwrap_or_default();
+
+        let mut segments = Vec::new();
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                _ => segments.push(segment),
+            }
+        }
+
+        format!("/{}", segments.join("/"))
+    }
+
+    pub fn fetch_img_url(localizer: &Localizer, element: ElementRef) -> Result<String> {
+        let sel = localizer.selector("img_url", "dd>div.frontCol>img");
         trace!("fetching card.img_url ({})...", sel);
 
-        let img_elem = Self::get_child_node(element, sel.to_string())?;
+        let img_elem = Self::get_child_node(element, "img_url", sel)?;
         let img_url = img_elem
             .attr("data-src")
-            .context("no data-src attr")?
+            .ok_or_else(|| CardScrapeError::MissingField {
+                field: "img_url",
+                selector: format!("{}[data-src]", sel),
+            })?
             .to_string();
 
         trace!("fetched card.img_url: {}", img_url);
         Ok(img_url)
     }
 
-    pub fn fetch_colors(localizer: &Localizer, element: ElementRef) -> Result<Vec<CardColor>> {
-        let sel = "dd>div.backCol div.color";
-        trace!("fetching card.colors ({})...", sel);
+    /// Image of the second face, shown in `backCol` for double-faced cards only
+    pub fn fetch_img_back_url(localizer: &Localizer, element: ElementRef) -> Option<String> {
+        let sel = localizer.selector("img_back_url", "dd>div.backCol img[data-src]");
+        trace!("fetching card.img_back_url ({})...", sel);
 
-        let raw_colors = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let raw_colors = Self::strip_html_tags(&raw_colors)?;
-        trace!("fetched card.colors: {}", raw_colors);
+        let img_back_url = Self::get_child_node(element, "img_back_url", sel)
+            .ok()?
+            .attr("data-src")
+            .map(str::to_string);
 
-        let raw_colors: Vec<&str> = raw_colors.split('/').collect();
+        trace!("fetched card.img_back_url: {:?}", img_back_url);
+        img_back_url
+    }
 
-        let mut colors = Vec::new();
+    /// Canonical keys of a label combining several labels without separators
+    /// (e.g. `赤緑` or `แดงเขียว`), matching the longest known label at each position
+    fn match_composite(raw: &str, match_label: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let chars: Vec<(usize, char)> = raw.char_indices().collect();
+        let mut keys = Vec::new();
+        let mut start = 0;
+
+        while start < chars.len() {
+            let (from, ch) = chars[start];
+            if ch.is_whitespace() {
+                start += 1;
+                continue;
+            }
 
-        if raw_colors.len() == 1 && localizer.match_color(raw_colors[0]).is_none() {
-            trace!("raw color is unseparatble, trying to parse character by character");
-            let mut any = false;
-            for ch in raw_colors[0].chars() {
-                let char_color = ch.to_string();
-                if let Some(raw_color) = localizer.match_color(&char_color) {
-                    trace!("found color char: {} -> {}", char_color, raw_color);
-                    let color = CardColor::from_str(&raw_color)?;
-                    colors.push(color);
-                    any = true;
+            let longest = (start + 1..=chars.len()).rev().find_map(|end| {
+                let to = chars.get(end).map_or(raw.len(), |(index, _)| *index);
+                match_label(&raw[from..to]).map(|key| (end, key))
+            });
+            match longest {
+                Some((end, key)) => {
+                    keys.push(key);
+                    start = end;
                 }
-            }
-            if any {
-                colors.dedup();
-                trace!("processed card.colors by character");
-                return Ok(colors);
+                None => start += 1,
             }
         }
 
-        for (index, raw_color) in raw_colors.iter().enumerate() {
+        keys
+    }
+
+    pub fn fetch_colors(localizer: &Localizer, element: ElementRef) -> Result<Vec<CardColor>> {
+        let sel = localizer.selector("colors", COLORS_SELECTOR);
+        trace!("fetching card.colors ({})...", sel);
+
+        let raw_colors = Self::get_child_node(element, "colors", sel)?.inner_html();
+        let raw_colors = sanitizer::strip_headers(&raw_colors);
+        trace!("fetched card.colors: {}", raw_colors);
+
+        let mut colors = Vec::new();
+        for (index, raw_color) in localizer.split_colors(&raw_colors).into_iter().enumerate() {
             trace!("processing card.colors[{}]: {}", index, raw_color);
-            let color = CardColor::parse(localizer, raw_color)?;
-            colors.push(color);
+            if localizer.match_color(raw_color).is_some() {
+                colors.push(CardColor::parse(localizer, raw_color)?);
+                continue;
+            }
+
+            let composite = Self::match_composite(raw_color, |label| localizer.match_color(label));
+            if composite.is_empty() {
+                // Kept as an unknown color
+                colors.push(CardColor::parse(localizer, raw_color)?);
+            }
+            for raw_color in composite {
+                trace!("found color in composite label: {}", raw_color);
+                colors.push(raw_color.parse::<CardColor>()?);
+            }
         }
+        colors.dedup();
 
         trace!("processed card.colors");
         Ok(colors)
     }
 
-    pub fn fetch_cost(element: ElementRef) -> Result<Option<i32>> {
-        let sel = "dd>div.backCol>div.col2>div.cost";
+    pub fn fetch_cost(localizer: &Localizer, element: ElementRef) -> Result<Option<i32>> {
+        let sel = localizer.selector("cost", "dd>div.backCol>div.col2>div.cost");
         trace!("fetching card.cost ({})...", sel);
 
-        let raw_cost = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let raw_cost = Self::strip_html_tags(&raw_cost)?;
+        let raw_cost = Self::get_child_node(element, "cost", sel)?.inner_html();
+        let raw_cost = sanitizer::strip_headers(&raw_cost);
         let raw_cost = normalize_ascii(&raw_cost)
             .replace([',', ' '], "")
             .trim()
             .to_string();
         trace!("fetched card.cost: {}", raw_cost);
 
-        if raw_cost == "-" || raw_cost.is_empty() {
+        if localizer.is_null_token(&raw_cost) {
             trace!("card.cost unset");
             return Ok(None);
         }
@@ -200,20 +458,44 @@ impl CardScraper {
                 trace!("processed card.cost");
                 Ok(Some(val))
             }
-            Err(e) => bail!("failed to parse card.cost value `{}`: {}", raw_cost, e),
+            Err(e) => {
+                trace!("failed to parse card.cost: {}", e);
+                Err(CardScrapeError::UnparsableValue {
+                    field: "cost",
+                    raw: raw_cost,
+                })?
+            }
+        }
+    }
+
+    /// Leaders show their life where other cards show their cost
+    pub fn fetch_life(
+        localizer: &Localizer,
+        category: &CardCategory,
+        element: ElementRef,
+    ) -> Result<Option<i32>> {
+        if *category != CardCategory::Leader {
+            trace!("card.life unset (not a leader)");
+            return Ok(None);
         }
+
+        trace!("fetching card.life...");
+        let life = Self::fetch_cost(localizer, element)?;
+
+        trace!("processed card.life");
+        Ok(life)
     }
 
     pub fn fetch_attributes(
         localizer: &Localizer,
         element: ElementRef,
     ) -> Result<Vec<CardAttribute>> {
-        let sel = "dd>div.backCol>div.col2>div.attribute>img";
+        let sel = localizer.selector("attributes", ATTRIBUTES_SELECTOR);
         trace!("fetching card.attributes ({})...", sel);
 
-        if let Ok(attr_img) = Self::get_child_node(element, sel.to_string()) {
+        if let Ok(attr_img) = Self::get_child_node(element, "attributes", sel) {
             if let Some(url) = attr_img.attr("src") {
-                if let Ok(attributes) = CardAttribute::from_icon_url(url) {
+                if let Ok(attributes) = CardAttribute::from_icon_url(localizer, url) {
                     trace!("processed card.attributes");
                     return Ok(attributes);
                 }
@@ -221,7 +503,13 @@ impl CardScraper {
             }
             trace!("Falling back to processing alt attribute");
 
-            let raw_attributes = attr_img.attr("alt").context("no alt attr")?.to_string();
+            let raw_attributes = attr_img
+                .attr("alt")
+                .ok_or_else(|| CardScrapeError::MissingField {
+                    field: "attributes",
+                    selector: format!("{}[alt]", sel),
+                })?
+                .to_string();
             trace!("fetched card.attributes: {}", raw_attributes);
 
             if raw_attributes.is_empty() {
@@ -229,13 +517,28 @@ impl CardScraper {
                 return Ok(Vec::new());
             }
 
-            let raw_attributes: Vec<&str> = raw_attributes.split('/').collect();
+            let raw_attributes = localizer.split_attributes(&raw_attributes);
 
             let mut attributes = Vec::new();
-            for (index, raw_attribute) in raw_attributes.iter().enumerate() {
+            for (index, raw_attribute) in raw_attributes.into_iter().enumerate() {
                 trace!("processing card.attributes[{}]: {}", index, raw_attribute);
-                let attribute = CardAttribute::parse(localizer, raw_attribute)?;
-                attributes.push(attribute);
+                let composite = match localizer.attribute_parsing {
+                    AttributeParsing::Composite
+                        if localizer.match_attribute(raw_attribute).is_none() =>
+                    {
+                        Self::match_composite(raw_attribute, |label| {
+                            localizer.match_attribute(label)
+                        })
+                    }
+                    _ => Vec::new(),
+                };
+                if composite.is_empty() {
+                    attributes.push(CardAttribute::parse(localizer, raw_attribute)?);
+                }
+                for raw_attribute in composite {
+                    trace!("found attribute in composite label: {}", raw_attribute);
+                    attributes.push(raw_attribute.parse::<CardAttribute>()?);
+                }
             }
 
             trace!("processed card.attributes");
@@ -246,19 +549,19 @@ impl CardScraper {
         Ok(Vec::new())
     }
 
-    pub fn fetch_power(element: ElementRef) -> Result<Option<i32>> {
-        let sel = "dd>div.backCol>div.col2>div.power";
+    pub fn fetch_power(localizer: &Localizer, element: ElementRef) -> Result<Option<i32>> {
+        let sel = localizer.selector("power", "dd>div.backCol>div.col2>div.power");
         trace!("fetching card.power ({})...", sel);
 
-        let raw_power = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let raw_power = Self::strip_html_tags(&raw_power)?;
+        let raw_power = Self::get_child_node(element, "power", sel)?.inner_html();
+        let raw_power = sanitizer::strip_headers(&raw_power);
         let raw_power = normalize_ascii(&raw_power)
             .replace([',', ' '], "")
             .trim()
             .to_string();
         trace!("fetched card.power: {}", raw_power);
 
-        if raw_power == "-" || raw_power.is_empty() {
+        if localizer.is_null_token(&raw_power) {
             trace!("card.power unset");
             return Ok(None);
         }
@@ -275,23 +578,29 @@ impl CardScraper {
                 trace!("processed card.power");
                 Ok(Some(val))
             }
-            Err(e) => bail!("failed to parse card.power value `{}`: {}", raw_power, e),
+            Err(e) => {
+                trace!("failed to parse card.power: {}", e);
+                Err(CardScrapeError::UnparsableValue {
+                    field: "power",
+                    raw: raw_power,
+                })?
+            }
         }
     }
 
-    pub fn fetch_counter(element: ElementRef) -> Result<Option<i32>> {
-        let sel = "dd>div.backCol>div.col2>div.counter";
+    pub fn fetch_counter(localizer: &Localizer, element: ElementRef) -> Result<Option<i32>> {
+        let sel = localizer.selector("counter", "dd>div.backCol>div.col2>div.counter");
         trace!("fetching card.counter ({})...", sel);
 
-        let raw_counter = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let raw_counter = Self::strip_html_tags(&raw_counter)?;
+        let raw_counter = Self::get_child_node(element, "counter", sel)?.inner_html();
+        let raw_counter = sanitizer::strip_headers(&raw_counter);
         let raw_counter = normalize_ascii(&raw_counter)
             .replace([',', ' '], "")
             .trim()
             .to_string();
         trace!("fetched card.counter: {}", raw_counter);
 
-        if raw_counter == "-" || raw_counter.is_empty() {
+        if localizer.is_null_token(&raw_counter) {
             trace!("card.counter unset");
             return Ok(None);
         }
@@ -308,24 +617,26 @@ impl CardScraper {
                 trace!("processed card.counter");
                 Ok(Some(val))
             }
-            Err(e) => bail!(
-                "failed to parse card.counter value `{}`: {}",
-                raw_counter,
-                e
-            ),
+            Err(e) => {
+                trace!("failed to parse card.counter: {}", e);
+                Err(CardScrapeError::UnparsableValue {
+                    field: "counter",
+                    raw: raw_counter,
+                })?
+            }
         }
     }
 
-    pub fn fetch_block_number(element: ElementRef) -> Result<Option<i32>> {
-        let sel = "dd>div.backCol>div.col2>div.block";
+    pub fn fetch_block_number(localizer: &Localizer, element: ElementRef) -> Result<Option<i32>> {
+        let sel = localizer.selector("block_number", "dd>div.backCol>div.col2>div.block");
         trace!("fetching card.block_number ({})...", sel);
 
-        let raw_block_number = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let raw_block_number = Self::strip_html_tags(&raw_block_number)?;
+        let raw_block_number = Self::get_child_node(element, "block_number", sel)?.inner_html();
+        let raw_block_number = sanitizer::strip_headers(&raw_block_number);
         let raw_block_number = normalize_ascii(&raw_block_number).trim().to_string();
         trace!("fetched card.block_number: {}", raw_block_number);
 
-        if raw_block_number == "-" || raw_block_number.is_empty() {
+        if localizer.is_null_token(&raw_block_number) {
             trace!("card.block_number unset");
             return Ok(None);
         }
@@ -344,20 +655,22 @@ impl CardScraper {
                 trace!("processed card.block_number");
                 Ok(Some(val))
             }
-            Err(e) => bail!(
-                "failed to parse card.block_number value `{}`: {}",
-                raw_block_number,
-                e
-            ),
+            Err(e) => {
+                trace!("failed to parse card.block_number: {}", e);
+                Err(CardScrapeError::UnparsableValue {
+                    field: "block_number",
+                    raw: raw_block_number,
+                })?
+            }
         }
     }
 
-    pub fn fetch_types(element: ElementRef) -> Result<Vec<String>> {
-        let sel = "dd>div.backCol>div.feature";
+    pub fn fetch_types(localizer: &Localizer, element: ElementRef) -> Result<Vec<String>> {
+        let sel = localizer.selector("types", "dd>div.backCol>div.feature");
         trace!("fetching card.types ({})...", sel);
 
-        let types = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let types = Self::strip_html_tags(&types)?;
+        let types = Self::get_child_node(element, "types", sel)?.inner_html();
+        let types = localizer.sanitizer.sanitize(&types);
         trace!("fetched card.types: {}", types);
 
         let types: Vec<String> = types.split('/').map(str::to_owned).collect();
@@ -366,26 +679,40 @@ impl CardScraper {
         Ok(types)
     }
 
-    pub fn fetch_effect(element: ElementRef) -> Result<String> {
-        let sel = "dd>div.backCol>div.text";
+    /// English names of the given types, empty unless the locale maps all of them
+    fn canonical_types(localizer: &Localizer, types: &[String]) -> Vec<String> {
+        types
+            .iter()
+            .map(|card_type| localizer.match_type(card_type))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    pub fn fetch_effect(localizer: &Localizer, element: ElementRef) -> Result<String> {
+        let sel = localizer.selector("effect", "dd>div.backCol>div.text");
         trace!("fetching card.effect ({})...", sel);
 
-        let effect = Self::get_child_node(element, sel.to_string())?.inner_html();
-        let effect = Self::strip_html_tags(&effect)?;
+        let effect = Self::get_child_node(element, "effect", sel)?.inner_html();
+        let effect = localizer.sanitizer.sanitize(&effect);
         trace!("fetched card.effect: {}", effect);
 
         Ok(effect)
     }
 
-    pub fn fetch_trigger(element: ElementRef) -> Result<Option<String>> {
-        let sel = "dd>div.backCol>div.trigger";
+    pub fn fetch_trigger(
+        localizer: &Localizer,
+        element: ElementRef,
+    ) -> Result<Option<CardTrigger>> {
+        let sel = localizer.selector("trigger", "dd>div.backCol>div.trigger");
         trace!("fetching card.trigger ({})...", sel);
 
-        if let Ok(trigger_div) = Self::get_child_node(element, sel.to_string()) {
+        if let Ok(trigger_div) = Self::get_child_node(element, "trigger", sel) {
             let trigger = trigger_div.inner_html();
-            let trigger = Self::strip_html_tags(&trigger)?;
+            let trigger = localizer.sanitizer.sanitize(&trigger);
             trace!("fetched card.trigger: {}", trigger);
 
+            let trigger = CardTrigger::parse(localizer, &trigger);
+            trace!("processed card.trigger");
             return Ok(Some(trigger));
         }
 
@@ -393,27 +720,365 @@ impl CardScraper {
         Ok(None)
     }
 
-    fn strip_html_tags(value: &str) -> Result<String> {
-        let reg = Regex::new(r"<[^>]*>.*?</[^>]*>")?;
-        let result = reg.replace_all(value, "").trim().to_string();
-        Ok(result)
+    /// Products the card is included in, one per line of the "Card Set(s)" block
+    pub fn fetch_products(localizer: &Localizer, element: ElementRef) -> Result<Vec<String>> {
+        let sel = localizer.selector("products", "dd>div.backCol>div.getInfo");
+        trace!("fetching card.products ({})...", sel);
+
+        let Ok(products_div) = Self::get_child_node(element, "products", sel) else {
+            trace!("card.products no html found");
+            return Ok(Vec::new());
+        };
+
+        let products = localizer.sanitizer.sanitize(&products_div.inner_html());
+        let products: Vec<String> = products
+            .lines()
+            .map(str::trim)
+            .filter(|product| !product.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        trace!("fetched card.products: {:?}", products);
+        Ok(products)
     }
 
-    fn get_child_node(element: ElementRef, selector: String) -> Result<ElementRef> {
-        let node_sel = scraper::Selector::parse(&selector).unwrap();
+    /// Text of a field as shown on the site, before any localization
+    fn fetch_raw_text(
+        localizer: &Localizer,
+        element: ElementRef,
+        field: &'static str,
+        selector: &str,
+    ) -> Option<String> {
+        let html = Self::get_child_node(element, field, selector)
+            .ok()?
+            .inner_html();
+        Some(localizer.sanitizer.sanitize(&html))
+    }
+
+    fn get_child_node<'a>(
+        element: ElementRef<'a>,
+        field: &'static str,
+        selector: &str,
+    ) -> Result<ElementRef<'a>> {
+        let node_sel = utils::parse_selector(selector)?;
         let results: Vec<_> = element.select(&node_sel).collect();
 
         match results.len() {
-            0 => bail!("Expected `{}` but got nothing", selector),
+            0 => Err(CardScrapeError::MissingField {
+                field,
+                selector: selector.to_string(),
+            })?,
             1 => Ok(*results.first().unwrap()),
-            _ => bail!("Expected single `{}` but got many", selector),
+            _ => Err(CardScrapeError::SelectorChanged {
+                field,
+                selector: selector.to_string(),
+            })?,
         }
     }
 
+    /// `<dl>` of card `card_id`, matched on its `id` attribute as card IDs are not always valid
+    /// CSS identifiers
     pub fn get_dl_node(document: &Html, card_id: String) -> Result<ElementRef<'_>> {
-        let dl_sel = format!("dl#{}", card_id);
-        let dl_sel = scraper::Selector::parse(&dl_sel).unwrap();
-        let dl_elem = document.select(&dl_sel).next().unwrap();
+        let dl_sel = utils::parse_selector("dl[id]")?;
+        let dl_elem = document
+            .select(&dl_sel)
+            .find(|dl| dl.value().id() == Some(card_id.as_str()))
+            .ok_or_else(|| CardScrapeError::MissingField {
+                field: "id",
+                selector: format!("dl#{}", card_id),
+            })?;
         Ok(dl_elem)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_dl_node_of_card_not_on_page_returns_err() {
+        let document = Html::parse_document(
+            r#"<dl id="OP01-001"><dt></dt></dl><dl id="1st card"><dt></dt></dl>"#,
+        );
+
+        assert!(CardScraper::get_dl_node(&document, String::from("OP01-001")).is_ok());
+        assert!(CardScraper::get_dl_node(&document, String::from("1st card")).is_ok());
+        assert!(matches!(
+            CardScraper::get_dl_node(&document, String::from("OP01-002")),
+            Err(VegaError::Parse {
+                reason: CardScrapeError::MissingField { field: "id", .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn tolerate_missing_only_for_don_cards() {
+        let missing = || -> Result<Option<i32>> {
+            Err(CardScrapeError::MissingField {
+                field: "cost",
+                selector: String::from("div.cost"),
+            })?
+        };
+        let unparsable = || -> Result<Option<i32>> {
+            Err(CardScrapeError::UnparsableValue {
+                field: "cost",
+                raw: String::from("?"),
+            })?
+        };
+
+        assert_eq!(
+            CardScraper::tolerate_missing(&CardCategory::Don, "cost", missing()).unwrap(),
+            None
+        );
+        assert!(
+            CardScraper::tolerate_missing(&CardCategory::Character, "cost", missing()).is_err()
+        );
+        assert!(CardScraper::tolerate_missing(&CardCategory::Don, "cost", unparsable()).is_err());
+    }
+
+    #[test]
+    fn degrade_records_a_warning_instead_of_failing() {
+        let mut warnings = Vec::new();
+        let counter = CardScraper::degrade(
+            &mut warnings,
+            "counter",
+            Err::<Option<i32>, _>(VegaError::from(CardScrapeError::UnparsableValue {
+                field: "counter",
+                raw: String::from("-"),
+            })),
+        );
+        let block_number = CardScraper::degrade(&mut warnings, "block_number", Ok(Some(3)));
+
+        assert_eq!(counter, None);
+        assert_eq!(block_number, Some(3));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "card.counter left empty: failed to parse card.counter value `-`"
+        );
+    }
+
+    #[test]
+    fn canonical_types_requires_every_type_mapped() {
+        let localizer: Localizer = toml::from_str(crate::localizer::JP_LOCALE_RAW).unwrap();
+        let types = vec![String::from("超新星"), String::from("麦わらの一味")];
+
+        assert_eq!(
+            CardScraper::canonical_types(&localizer, &types),
+            vec!["Supernovas", "Straw Hat Crew"]
+        );
+        assert!(CardScraper::canonical_types(&localizer, &[String::from("東の海")]).is_empty());
+    }
+
+    #[test]
+    fn canonical_img_url_strips_query_and_resolves_path() {
+        assert_eq!(
+            CardScraper::canonical_img_url("../images/cardlist/card/OP01-001.png?240912"),
+            "/images/cardlist/card/OP01-001.png"
+        );
+        assert_eq!(
+            CardScraper::canonical_img_url("/images/./cardlist//card/OP01-001_p1.png#top"),
+            "/images/cardlist/card/OP01-001_p1.png"
+        );
+    }
+
+    #[test]
+    fn fetch_products_splits_lines() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let document = Html::parse_document(
+            r#"<dl id="ST01-012"><dd><div class="backCol">
+                <div class="getInfo"><h3>Card Set(s)</h3>-Straw Hat Crew- [ST-01]<br>-ROMANCE DAWN- [OP-01]</div>
+            </div></dd></dl>
+            <dl id="OP01-001"><dd><div class="backCol"></div></dd></dl>"#,
+        );
+
+        let listed = CardScraper::get_dl_node(&document, String::from("ST01-012")).unwrap();
+        assert_eq!(
+            CardScraper::fetch_products(&localizer, listed).unwrap(),
+            vec!["-Straw Hat Crew- [ST-01]", "-ROMANCE DAWN- [OP-01]"]
+        );
+        let unlisted = CardScraper::get_dl_node(&document, String::from("OP01-001")).unwrap();
+        assert!(CardScraper::fetch_products(&localizer, unlisted)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn create_card_reads_life_of_leaders_instead_of_cost() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let card_html = |id: &str, rarity: &str, category: &str, cost: &str| {
+            format!(
+                r#"<dl id="{id}"><dt>
+                <div class="infoCol"><span>{id}</span><span>{rarity}</span><span>{category}</span></div>
+                <div class="cardName">Roronoa Zoro</div>
+                </dt><dd>
+                <div class="frontCol"><img data-src="../images/cardlist/card/{id}.png"></div>
+                <div class="backCol">
+                <div class="col2">
+                <div class="cost"><h3>Cost</h3>{cost}</div>
+                <div class="attribute"><img src="../images/cardlist/attribute/ico_type02.png" alt="Slash"></div>
+                <div class="power"><h3>Power</h3>5000</div>
+                <div class="counter"><h3>Counter</h3>-</div>
+                </div>
+                <div class="color"><h3>Color</h3>Red</div>
+                <div class="block"><h3>Block icon</h3>1</div>
+                <div class="feature"><h3>Type</h3>Supernovas/Straw Hat Crew</div>
+                <div class="text"><h3>Effect</h3>-</div>
+                </div></dd></dl>"#
+            )
+        };
+        let document = Html::parse_document(&format!(
+            "{}{}",
+            card_html("OP01-001", "L", "LEADER", "5"),
+            card_html("OP01-025", "SR", "CHARACTER", "3")
+        ));
+        let pack_id: PackId = "569101".parse().unwrap();
+
+        let leader = CardScraper::create_card(&localizer, &document, "OP01-001", &pack_id)
+            .unwrap()
+            .0;
+        assert_eq!((leader.cost, leader.life), (None, Some(5)));
+        let leader_dl = CardScraper::get_dl_node(&document, String::from("OP01-001")).unwrap();
+        assert_eq!(
+            CardScraper::fetch_life(&localizer, &CardCategory::Leader, leader_dl).unwrap(),
+            Some(5)
+        );
+
+        let character = CardScraper::create_card(&localizer, &document, "OP01-025", &pack_id)
+            .unwrap()
+            .0;
+        assert_eq!((character.cost, character.life), (Some(3), None));
+        let character_dl = CardScraper::get_dl_node(&document, String::from("OP01-025")).unwrap();
+        assert_eq!(
+            CardScraper::fetch_life(&localizer, &CardCategory::Character, character_dl).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn fetch_img_back_url_only_for_double_faced_cards() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let document = Html::parse_document(
+            r#"<dl id="OP01-001"><dd>
+                <div class="frontCol"><img data-src="../images/cardlist/card/OP01-001.png"></div>
+                <div class="backCol"><div class="col2"><div class="attribute"><img src="../images/cardlist/attribute/ico_type01.png" alt="Slash"></div></div></div>
+            </dd></dl>
+            <dl id="OP01-002"><dd>
+                <div class="frontCol"><img data-src="../images/cardlist/card/OP01-002.png"></div>
+                <div class="backCol"><div class="backImg"><img data-src="../images/cardlist/card/OP01-002_b.png"></div></div>
+            </dd></dl>"#,
+        );
+
+        let single = CardScraper::get_dl_node(&document, String::from("OP01-001")).unwrap();
+        assert_eq!(CardScraper::fetch_img_back_url(&localizer, single), None);
+        let double = CardScraper::get_dl_node(&document, String::from("OP01-002")).unwrap();
+        assert_eq!(
+            CardScraper::fetch_img_back_url(&localizer, double).as_deref(),
+            Some("../images/cardlist/card/OP01-002_b.png")
+        );
+    }
+
+    #[test]
+    fn fetch_name_uses_locale_selector_override() {
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let document = Html::parse_document(
+            r#"<dl id="OP01-001"><dt><div class="wrap"><div class="cardName">Roronoa Zoro</div></div></dt></dl>"#,
+        );
+        let dl_elem = CardScraper::get_dl_node(&document, String::from("OP01-001")).unwrap();
+        assert!(CardScraper::fetch_name(&localizer, dl_elem).is_err());
+
+        localizer
+            .selectors
+            .insert(String::from("name"), String::from("dt div.cardName"));
+        assert_eq!(
+            CardScraper::fetch_name(&localizer, dl_elem).unwrap(),
+            "Roronoa Zoro"
+        );
+
+        localizer
+            .selectors
+            .insert(String::from("name"), String::from("dt>>"));
+        assert!(CardScraper::fetch_name(&localizer, dl_elem).is_err());
+    }
+
+    #[test]
+    fn fetch_colors_splits_separated_and_composite_labels() {
+        let th_localizer: Localizer = toml::from_str(crate::localizer::TH_LOCALE_RAW).unwrap();
+        let mut jp_localizer: Localizer = toml::from_str(crate::localizer::JP_LOCALE_RAW).unwrap();
+        jp_localizer.color_separators = vec![String::from("/"), String::from("・")];
+
+        let fetch = |localizer: &Localizer, raw: &str| {
+            let html = format!(
+                r#"<dl id="OP01-001"><dd><div class="backCol"><div class="col2"><div class="color"><h3>Color</h3>{}</div></div></div></dd></dl>"#,
+                raw
+            );
+            let document = Html::parse_document(&html);
+            let dl_elem = CardScraper::get_dl_node(&document, String::from("OP01-001")).unwrap();
+            CardScraper::fetch_colors(localizer, dl_elem)
+        };
+
+        assert_eq!(
+            fetch(&th_localizer, "แดงเขียว").unwrap(),
+            vec![CardColor::Red, CardColor::Green]
+        );
+        assert_eq!(
+            fetch(&th_localizer, "เหลือง").unwrap(),
+            vec![CardColor::Yellow]
+        );
+        assert_eq!(
+            fetch(&jp_localizer, "赤・緑/黒").unwrap(),
+            vec![CardColor::Red, CardColor::Green, CardColor::Black]
+        );
+        assert_eq!(
+            fetch(&jp_localizer, "青紫").unwrap(),
+            vec![CardColor::Blue, CardColor::Purple]
+        );
+        assert_eq!(
+            fetch(&jp_localizer, "桃").unwrap(),
+            vec![CardColor::Unknown(String::from("桃"))]
+        );
+    }
+
+    #[test]
+    fn fetch_attributes_parses_alt_text_of_every_region() {
+        let en_localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let fetch = |localizer: &Localizer, alt: &str| {
+            let html = format!(
+                r#"<dl id="OP01-001"><dd><div class="backCol"><div class="col2"><div class="attribute"><img src="../images/cardlist/attribute/unknown.png" alt="{}"></div></div></div></dd></dl>"#,
+                alt
+            );
+            let document = Html::parse_document(&html);
+            let dl_elem = CardScraper::get_dl_node(&document, String::from("OP01-001")).unwrap();
+            CardScraper::fetch_attributes(localizer, dl_elem)
+        };
+
+        for (locale, raw) in crate::localizer::BUILTIN_LOCALES {
+            let mut localizer: Localizer = toml::from_str(raw).unwrap();
+            if localizer.fallback.is_some() {
+                localizer.fallback_locale = Some(Box::new(en_localizer.clone()));
+            }
+
+            let labels = &localizer.label_maps()["attributes"];
+            let separator = localizer
+                .attribute_separators
+                .first()
+                .map_or("/", String::as_str);
+            let alt = format!("{}{}{}", labels["slash"], separator, labels["strike"]);
+            assert_eq!(
+                fetch(&localizer, &alt).unwrap(),
+                vec![CardAttribute::Slash, CardAttribute::Strike],
+                "{} locale with alt `{}`",
+                locale,
+                alt
+            );
+        }
+
+        let jp_localizer: Localizer = toml::from_str(crate::localizer::JP_LOCALE_RAW).unwrap();
+        assert_eq!(
+            fetch(&jp_localizer, "斬打").unwrap(),
+            vec![CardAttribute::Slash, CardAttribute::Strike]
+        );
+        assert!(fetch(&en_localizer, "SlashStrike").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+
+use super::{effect::SegmentKind, Card};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TextNormalization {
+    /// Unicode NFKC (full-width letters and digits, compatibility characters...)
+    Nfkc,
+    /// Map full-width ASCII punctuation and the ideographic space to ASCII only
+    Punctuation,
+    /// Collapse runs of spaces and trim every line
+    Whitespace,
+}
+
+impl TextNormalization {
+    fn apply(self, value: &str) -> String {
+        match self {
+            TextNormalization::Nfkc => value.nfkc().collect(),
+            TextNormalization::Punctuation => value
+                .chars()
+                .map(|c| match c {
+                    // Full-width forms of `!` to `~`
+                    '\u{FF01}'..='\u{FF5E}' if !c.is_alphanumeric() => {
+                        char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+                    }
+                    '\u{3000}' => ' ',
+                    _ => c,
+                })
+                .collect(),
+            TextNormalization::Whitespace => value
+                .lines()
+                .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string(),
+        }
+    }
+}
+
+/// Apply every normalization in `steps` to `value`, always in the same order
+pub fn normalize_text(value: &str, steps: &[TextNormalization]) -> String {
+    let mut steps = steps.to_vec();
+    steps.sort();
+    steps.dedup();
+
+    steps
+        .into_iter()
+        .fold(value.to_string(), |value, step| step.apply(&value))
+}
+
+/// Normalize the free text fields of a card (name, effect, trigger and types)
+pub fn normalize_card_text(card: &mut Card, steps: &[TextNormalization]) {
+    if steps.is_empty() {
+        return;
+    }
+
+    card.name = normalize_text(&card.name, steps);
+    card.effect = normalize_text(&card.effect, steps);
+    for segment in card.effect_segments.iter_mut() {
+        if segment.kind != SegmentKind::LineBreak {
+            segment.text = normalize_text(&segment.text, steps);
+        }
+    }
+    if let Some(trigger) = &card.trigger {
+        card.trigger = Some(normalize_text(trigger, steps));
+    }
+    for card_type in card.types.iter_mut() {
+        *card_type = normalize_text(card_type, steps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_text_applies_steps() {
+        let text = "【アタック時】　ＫＯする！\n  Draw   1 card. ";
+
+        assert_eq!(normalize_text(text, &[]), text);
+        assert_eq!(
+            normalize_text(text, &[TextNormalization::Punctuation]),
+            "【アタック時】 ＫＯする!\n  Draw   1 card. "
+        );
+        assert_eq!(
+            normalize_text(
+                text,
+                &[TextNormalization::Whitespace, TextNormalization::Nfkc]
+            ),
+            "【アタック時】 KOする!\nDraw 1 card."
+        );
+    }
+}
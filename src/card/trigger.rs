@@ -0,0 +1,63 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::localizer::Localizer;
+
+/// Bracketed keywords, e.g. `[Main]` or `【メイン】`
+static KEYWORD_REG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]|【([^】]+)】").unwrap());
+
+/// Trigger effect of a card without its localized label
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardTrigger {
+    pub text: String,
+    pub keywords: Vec<String>,
+}
+
+impl CardTrigger {
+    pub fn parse(localizer: &Localizer, raw_trigger: &str) -> Self {
+        let text = localizer.strip_trigger_label(raw_trigger).to_string();
+
+        let mut keywords: Vec<String> = Vec::new();
+        for captures in KEYWORD_REG.captures_iter(&text) {
+            let keyword = captures.get(1).or(captures.get(2)).unwrap().as_str().trim();
+            if !keywords.iter().any(|k| k == keyword) {
+                keywords.push(keyword.to_string());
+            }
+        }
+
+        Self { text, keywords }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localizer::{EN_LOCALE_RAW, JP_LOCALE_RAW};
+
+    #[test]
+    fn parse_strips_label_and_extracts_keywords() {
+        let localizer: Localizer = toml::from_str(EN_LOCALE_RAW).unwrap();
+
+        let trigger =
+            CardTrigger::parse(&localizer, "[Trigger] Activate this card's [Main] effect.");
+        assert_eq!(trigger.text, "Activate this card's [Main] effect.");
+        assert_eq!(trigger.keywords, vec!["Main"]);
+
+        let trigger = CardTrigger::parse(&localizer, "Play this card.");
+        assert_eq!(trigger.text, "Play this card.");
+        assert!(trigger.keywords.is_empty());
+    }
+
+    #[test]
+    fn parse_japanese_keywords_returns_keywords() {
+        let localizer: Localizer = toml::from_str(JP_LOCALE_RAW).unwrap();
+
+        let trigger = CardTrigger::parse(
+            &localizer,
+            "【トリガー】このカードの【メイン】効果を発動する。",
+        );
+        assert_eq!(trigger.text, "このカードの【メイン】効果を発動する。");
+        assert_eq!(trigger.keywords, vec!["メイン"]);
+    }
+}
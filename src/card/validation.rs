@@ -0,0 +1,110 @@
+use log::warn;
+use std::collections::HashSet;
+
+use super::{Card, CardId, CardIdParts};
+use crate::pack::Pack;
+
+/// Upper-case the set code and number of a card ID, lower-case its variant suffix
+fn normalize_id(id: &str) -> String {
+    let id = id.trim();
+    match id.split_once('_') {
+        Some((base_id, suffix)) => format!("{}_{}", base_id.to_uppercase(), suffix.to_lowercase()),
+        None => id.to_uppercase(),
+    }
+}
+
+/// Normalize the IDs of the cards of a pack and list anything unexpected about them:
+/// IDs with an unexpected format, duplicates and set codes not matching the pack
+pub fn validate_cards(cards: &mut [Card], pack: Option<&Pack>) -> Vec<String> {
+    let mut anomalies = Vec::new();
+    let expected_set_code = pack
+        .and_then(|pack| pack.title_parts.label.as_ref())
+        .map(|label| label.replace('-', "").to_uppercase());
+
+    let mut seen = HashSet::new();
+    for card in cards.iter_mut() {
+        let id = normalize_id(&card.id);
+        if id != card.id.as_str() {
+            anomalies.push(format!("normalized card ID `{}` to `{}`", card.id, id));
+            card.id = CardId::try_from(id).expect("normalizing keeps a valid card ID");
+        }
+
+        match CardIdParts::parse(&card.id) {
+            Ok(parts) => {
+                if let Some(expected) = &expected_set_code {
+                    if &parts.set_code != expected {
+                        anomalies.push(format!(
+                            "card `{}` has set code `{}` but its pack is `{}`",
+                            card.id, parts.set_code, expected
+                        ));
+                    }
+                }
+
+                card.set_code = Some(parts.set_code);
+                card.card_number = Some(parts.card_number);
+                card.variant_suffix = parts.variant_suffix;
+            }
+            Err(e) => anomalies.push(e.to_string()),
+        }
+
+        if !seen.insert(card.id.clone()) {
+            anomalies.push(format!("duplicate card ID `{}`", card.id));
+        }
+    }
+
+    for anomaly in &anomalies {
+        warn!("{}", anomaly);
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::CardRarity, pack::TitleParts};
+
+    fn test_card(id: &str) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id("569101")
+            .name("Roronoa Zoro")
+            .rarity(CardRarity::SuperRare)
+            .cost(3)
+            .power(5000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn validate_cards_normalizes_and_flags_anomalies() {
+        let pack = Pack {
+            id: "569101".parse().unwrap(),
+            raw_title: String::from("BOOSTER PACK -ROMANCE DAWN- [OP-01]"),
+            title_parts: TitleParts {
+                prefix: Some(String::from("BOOSTER PACK")),
+                title: String::from("ROMANCE DAWN"),
+                label: Some(String::from("OP-01")),
+            },
+            cover_image: None,
+            logo_image: None,
+        };
+        let mut cards = vec![
+            test_card("op01-001_P1"),
+            test_card("OP01-002"),
+            test_card("OP01-002"),
+            test_card("ST01-001"),
+            test_card("OP01002"),
+        ];
+
+        let anomalies = validate_cards(&mut cards, Some(&pack));
+
+        assert_eq!(cards[0].id, "OP01-001_p1");
+        assert_eq!(cards[0].card_number, Some(1));
+        assert_eq!(anomalies.len(), 4);
+        assert!(anomalies[0].starts_with("normalized card ID"));
+        assert!(anomalies[1].starts_with("duplicate card ID"));
+        assert!(anomalies[2].contains("set code `ST01`"));
+        assert!(anomalies[3].contains("OP01002"));
+    }
+}
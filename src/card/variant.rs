@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::Card;
+
+/// Alternate printing of a card (e.g. `OP01-025_p1`), listed under its base card
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CardVariant {
+    pub base_id: String,
+    pub variant_code: String,
+    pub img_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img_full_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img_url_canonical: Option<String>,
+}
+
+impl CardVariant {
+    /// Split a card ID into its base ID and variant code (`OP01-025_p1` -> `OP01-025`, `p1`)
+    pub fn split_id(id: &str) -> (&str, Option<&str>) {
+        match id.split_once('_') {
+            Some((base_id, variant_code)) => (base_id, Some(variant_code)),
+            None => (id, None),
+        }
+    }
+
+    fn from_card(card: &Card) -> Option<Self> {
+        let (base_id, variant_code) = Self::split_id(&card.id);
+        Some(Self {
+            base_id: base_id.to_string(),
+            variant_code: variant_code?.to_string(),
+            img_url: card.img_url.clone(),
+            img_full_url: card.img_full_url.clone(),
+            img_url_canonical: card.img_url_canonical.clone(),
+        })
+    }
+}
+
+/// Fold alternate printings into the `variants` of their base card.
+/// Variants whose base card is not in `cards` are kept as cards of their own.
+pub fn group_variants(cards: &[Card]) -> Vec<Card> {
+    let mut grouped: Vec<Card> = Vec::with_capacity(cards.len());
+    let mut base_positions = HashMap::new();
+    for card in cards {
+        if CardVariant::split_id(&card.id).1.is_none() {
+            base_positions.insert(card.id.as_str(), grouped.len());
+            grouped.push(card.clone());
+        }
+    }
+
+    for card in cards {
+        let Some(variant) = CardVariant::from_card(card) else {
+            continue;
+        };
+
+        match base_positions.get(variant.base_id.as_str()) {
+            Some(&pos) => {
+                let variants = &mut grouped[pos].variants;
+                variants.retain(|v| v.variant_code != variant.variant_code);
+                variants.push(variant);
+                variants.sort_by(|a, b| a.variant_code.cmp(&b.variant_code));
+            }
+            None => grouped.push(card.clone()),
+        }
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardRarity;
+
+    fn test_card(id: &str) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id("569101")
+            .name("Roronoa Zoro")
+            .rarity(CardRarity::SuperRare)
+            .cost(3)
+            .power(5000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn split_id_returns_base_and_variant() {
+        assert_eq!(CardVariant::split_id("OP01-025"), ("OP01-025", None));
+        assert_eq!(
+            CardVariant::split_id("OP01-025_p1"),
+            ("OP01-025", Some("p1"))
+        );
+    }
+
+    #[test]
+    fn group_variants_folds_alternate_printings() {
+        let cards = vec![
+            test_card("OP01-025_p2"),
+            test_card("OP01-025"),
+            test_card("OP01-025_p1"),
+            test_card("OP01-026"),
+            test_card("ST01-001_p1"),
+        ];
+
+        let grouped = group_variants(&cards);
+        let ids: Vec<&str> = grouped.iter().map(|card| card.id.as_str()).collect();
+        assert_eq!(ids, vec!["OP01-025", "OP01-026", "ST01-001_p1"]);
+
+        let codes: Vec<&str> = grouped[0]
+            .variants
+            .iter()
+            .map(|variant| variant.variant_code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["p1", "p2"]);
+        assert_eq!(
+            grouped[0].variants[0].img_url,
+            "../images/cardlist/card/OP01-025_p1.png"
+        );
+        assert!(grouped[2].variants.is_empty());
+    }
+}
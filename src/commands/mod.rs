@@ -1,10 +0,0 @@
-pub mod config;
-pub mod diff;
-pub mod pull_all;
-pub mod pull_cards;
-pub mod pull_packs;
-
-pub use self::config::show_config;
-pub use self::pull_all::pull_all;
-pub use self::pull_cards::pull_cards;
-pub use self::pull_packs::pull_packs;
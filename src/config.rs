@@ -1,15 +1,32 @@
-use anyhow::{bail, Result};
 use directories::ProjectDirs;
-use log::info;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
 use std::{env, fs, path::PathBuf};
 
-use crate::localizer::{
-    EN_ASIA_LOCALE_RAW, EN_LOCALE_RAW, FR_LOCALE_RAW, JP_LOCALE_RAW, TH_LOCALE_RAW,
-    ZH_HK_LOCALE_RAW, ZH_TW_LOCALE_RAW,
+use crate::{
+    error::{Result, VegaError},
+    localizer::BUILTIN_LOCALES,
+    user_agent::UserAgentStrategy,
 };
 
 pub const APP_NAME: &str = "vegapull";
 pub const CONFIG_VAR: &str = "VEGAPULL_CONFIG";
+pub const SETTINGS_FILE: &str = "vega.toml";
+
+/// Optional user settings, read from `vega.toml` in the config directory
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub user_agent: UserAgentSettings,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UserAgentSettings {
+    #[serde(default)]
+    pub strategy: UserAgentStrategy,
+    #[serde(default)]
+    pub list: Vec<String>,
+}
 
 pub fn get_config_dir() -> Result<PathBuf> {
     if let Ok(config_var) = env::var(CONFIG_VAR) {
@@ -29,33 +46,88 @@ pub fn get_config_dir() -> Result<PathBuf> {
         return Ok(config_dir.to_path_buf());
     }
 
-    bail!("could not get config directory")
+    Err(VegaError::config("could not get config directory"))
+}
+
+/// Where interrupted image downloads are kept as `.part` files until they are resumed
+pub fn get_partial_downloads_dir() -> Result<PathBuf> {
+    match ProjectDirs::from("", "", APP_NAME) {
+        Some(proj_dirs) => Ok(proj_dirs.cache_dir().join("partial")),
+        None => Err(VegaError::config("could not get cache directory")),
+    }
 }
 
 pub fn initialize_configs() -> Result<()> {
     let config_dir = get_config_dir()?;
     if !config_dir.exists() {
         info!("creating config dir at: {}", config_dir.display());
-        fs::create_dir_all(&config_dir)?;
+        fs::create_dir_all(&config_dir).map_err(|e| {
+            VegaError::config(format!("failed to create `{}`", config_dir.display())).with_cause(e)
+        })?;
     }
 
-    let locale_files = [
-        ("en.toml", EN_LOCALE_RAW),
-        ("en_asia.toml", EN_ASIA_LOCALE_RAW),
-        ("jp.toml", JP_LOCALE_RAW),
-        ("zh_hk.toml", ZH_HK_LOCALE_RAW),
-        ("zh_tw.toml", ZH_TW_LOCALE_RAW),
-        ("th.toml", TH_LOCALE_RAW),
-        ("fr.toml", FR_LOCALE_RAW),
-    ];
-
-    for (filename, content) in &locale_files {
-        let file_path = config_dir.join(filename);
+    for (locale, content) in BUILTIN_LOCALES {
+        let file_path = config_dir.join(format!("{}.toml", locale));
         if !file_path.exists() {
             info!("creating locale file: {}", file_path.display());
-            fs::write(file_path, content)?;
+            fs::write(&file_path, content).map_err(|e| {
+                VegaError::config(format!("failed to create: {}", file_path.display()))
+                    .with_cause(e)
+            })?;
         }
     }
 
     Ok(())
 }
+
+pub fn load_settings() -> Result<Settings> {
+    let Ok(config_dir) = get_config_dir() else {
+        debug!("no config directory, using default settings");
+        return Ok(Settings::default());
+    };
+
+    let settings_path = config_dir.join(SETTINGS_FILE);
+    if !settings_path.exists() {
+        debug!("no settings file at: {}", settings_path.display());
+        return Ok(Settings::default());
+    }
+
+    info!("load settings from: {}", settings_path.display());
+    let settings_data = fs::read_to_string(&settings_path).map_err(|e| {
+        VegaError::config(format!("Failed to open file: {}", settings_path.display())).with_cause(e)
+    })?;
+
+    let settings: Settings = toml::from_str(&settings_data).map_err(|e| {
+        VegaError::config(format!(
+            "Invalid settings file: {}",
+            settings_path.display()
+        ))
+        .with_cause(e)
+    })?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_empty_returns_defaults() {
+        let settings: Settings = toml::from_str("").unwrap();
+        assert_eq!(settings.user_agent.strategy, UserAgentStrategy::Fixed);
+        assert!(settings.user_agent.list.is_empty());
+    }
+
+    #[test]
+    fn settings_user_agent_returns_ok() {
+        let raw = r#"
+            [user_agent]
+            strategy = "rotate"
+            list = ["foo/1.0", "bar/2.0"]
+        "#;
+
+        let settings: Settings = toml::from_str(raw).unwrap();
+        assert_eq!(settings.user_agent.strategy, UserAgentStrategy::Rotate);
+        assert_eq!(settings.user_agent.list, vec!["foo/1.0", "bar/2.0"]);
+    }
+}
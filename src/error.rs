@@ -0,0 +1,295 @@
+use std::fmt::{self, Display};
+use thiserror::Error;
+
+use crate::card::CardScrapeError;
+
+/// Any error, kept as the cause of a `VegaError`
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+pub type Result<T, E = VegaError> = std::result::Result<T, E>;
+
+/// Why a vegapull operation failed, by kind so that callers can match on it
+#[derive(Debug, Error)]
+pub enum VegaError {
+    /// A request to the site failed or got an unexpected response
+    #[error("{}", Caused(message, cause))]
+    Network {
+        message: String,
+        // Error that caused it, shown after the message
+        #[source]
+        cause: Option<BoxError>,
+    },
+    /// A card or pack shown on the site could not be parsed
+    #[error("{}{}{reason}", Prefix(context), CardPrefix(card_id))]
+    Parse {
+        // What was being done, shown first
+        context: Option<String>,
+        // ID of the card being parsed, if any
+        card_id: Option<String>,
+        field: &'static str,
+        reason: CardScrapeError,
+    },
+    /// Reading or writing a dataset, its images or an archive of it failed
+    #[error("{}", Caused(message, cause))]
+    Storage {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+    /// A locale file is missing, invalid or does not match the site
+    #[error("{}", Caused(message, cause))]
+    Locale {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+    /// A setting, argument or config file is missing or invalid
+    #[error("{}", Caused(message, cause))]
+    Config {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+}
+
+impl VegaError {
+    pub fn network(message: impl Display) -> Self {
+        Self::Network {
+            message: message.to_string(),
+            cause: None,
+        }
+    }
+
+    /// Error parsing `raw` as the `field` of a card or pack
+    pub fn unparsable(field: &'static str, raw: impl Display) -> Self {
+        CardScrapeError::UnparsableValue {
+            field,
+            raw: raw.to_string(),
+        }
+        .into()
+    }
+
+    /// Error about the `field` of a card or pack missing from the site, expected at `selector`
+    pub fn missing(field: &'static str, selector: impl Display) -> Self {
+        CardScrapeError::MissingField {
+            field,
+            selector: selector.to_string(),
+        }
+        .into()
+    }
+
+    pub fn storage(message: impl Display) -> Self {
+        Self::Storage {
+            message: message.to_string(),
+            cause: None,
+        }
+    }
+
+    pub fn locale(message: impl Display) -> Self {
+        Self::Locale {
+            message: message.to_string(),
+            cause: None,
+        }
+    }
+
+    pub fn config(message: impl Display) -> Self {
+        Self::Config {
+            message: message.to_string(),
+            cause: None,
+        }
+    }
+
+    /// Same error, caused by `cause`. Parse errors are left as they are
+    pub fn with_cause(self, cause: impl Into<BoxError>) -> Self {
+        let cause = Some(cause.into());
+        match self {
+            Self::Network { message, .. } => Self::Network { message, cause },
+            Self::Storage { message, .. } => Self::Storage { message, cause },
+            Self::Locale { message, .. } => Self::Locale { message, cause },
+            Self::Config { message, .. } => Self::Config { message, cause },
+            parse @ Self::Parse { .. } => parse,
+        }
+    }
+
+    /// Same error, described by `context` first
+    pub fn context(self, context: impl Display) -> Self {
+        let prefixed = |message: String| match message.is_empty() {
+            true => context.to_string(),
+            false => format!("{}: {}", context, message),
+        };
+        match self {
+            Self::Network { message, cause } => Self::Network {
+                message: prefixed(message),
+                cause,
+            },
+            Self::Storage { message, cause } => Self::Storage {
+                message: prefixed(message),
+                cause,
+            },
+            Self::Locale { message, cause } => Self::Locale {
+                message: prefixed(message),
+                cause,
+            },
+            Self::Config { message, cause } => Self::Config {
+                message: prefixed(message),
+                cause,
+            },
+            Self::Parse {
+                context: inner,
+                card_id,
+                field,
+                reason,
+            } => Self::Parse {
+                context: Some(match inner {
+                    Some(inner) => prefixed(inner),
+                    None => context.to_string(),
+                }),
+                card_id,
+                field,
+                reason,
+            },
+        }
+    }
+
+    /// Same error, as a failure to parse card `card_id` if it is a parse error
+    pub fn for_card(self, card_id: &str) -> Self {
+        match self {
+            Self::Parse {
+                context,
+                field,
+                reason,
+                ..
+            } => Self::Parse {
+                context,
+                card_id: Some(card_id.to_string()),
+                field,
+                reason,
+            },
+            other => other,
+        }
+    }
+}
+
+impl From<CardScrapeError> for VegaError {
+    fn from(reason: CardScrapeError) -> Self {
+        Self::Parse {
+            context: None,
+            card_id: None,
+            field: reason.field(),
+            reason,
+        }
+    }
+}
+
+/// Errors of the dependencies, converted to the kind of failure they mean in vegapull
+macro_rules! impl_from {
+    ($variant:ident: $($error:ty),+ $(,)?) => {
+        $(
+            impl From<$error> for VegaError {
+                fn from(e: $error) -> Self {
+                    Self::$variant {
+                        message: String::new(),
+                        cause: Some(Box::new(e)),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(Network: reqwest::Error);
+impl_from!(Locale: toml::de::Error, toml::ser::Error, regex::Error);
+impl_from!(
+    Storage: std::io::Error,
+    std::string::FromUtf8Error,
+    std::path::StripPrefixError,
+    serde_json::Error,
+    serde_yaml::Error,
+    rmp_serde::encode::Error,
+    rmp_serde::decode::Error,
+    zip::result::ZipError,
+    image::ImageError,
+    oxipng::PngError,
+);
+
+/// Adds context to errors, like `anyhow::Context` but keeping the kind of the error
+pub(crate) trait Context<T> {
+    fn context(self, context: impl Display) -> Result<T>;
+
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<VegaError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Display) -> Result<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
+
+/// `message`, followed by `cause` if any
+struct Caused<'a>(&'a String, &'a Option<BoxError>);
+
+impl Display for Caused<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.0.is_empty(), self.1) {
+            (true, Some(cause)) => write!(f, "{}", cause),
+            (false, Some(cause)) => write!(f, "{}: {}", self.0, cause),
+            (_, None) => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// `context` followed by a colon, if any
+struct Prefix<'a>(&'a Option<String>);
+
+impl Display for Prefix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(context) => write!(f, "{}: ", context),
+            None => Ok(()),
+        }
+    }
+}
+
+struct CardPrefix<'a>(&'a Option<String>);
+
+impl Display for CardPrefix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(card_id) => write!(f, "card `{}`: ", card_id),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_keeps_kind_and_is_added_to_parse_errors() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = Err::<(), _>(io_error)
+            .context("failed to read: cards.json")
+            .unwrap_err();
+        assert!(matches!(err, VegaError::Storage { cause: Some(_), .. }));
+        assert_eq!(err.to_string(), "failed to read: cards.json: no such file");
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err = VegaError::unparsable("cost", "?")
+            .context("failed to read cost")
+            .for_card("OP01-001")
+            .context("failed to scrape pack `569101`");
+        assert!(matches!(
+            &err,
+            VegaError::Parse { card_id: Some(card_id), field: "cost", .. } if card_id == "OP01-001"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "failed to scrape pack `569101`: failed to read cost: card `OP01-001`: failed to parse card.cost value `?`"
+        );
+    }
+}
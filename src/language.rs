@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use crate::{
+    config,
+    error::{Result, VegaError},
+    utils,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum LanguageCode {
+    #[cfg_attr(feature = "cli", value(name = "english", alias = "en"))]
+    English,
+    #[cfg_attr(feature = "cli", value(name = "japanese", alias = "jp"))]
+    Japanese,
+    #[cfg_attr(feature = "cli", value(name = "french", alias = "fr"))]
+    French,
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "chinese-hongkong", alias = "zh_hk", alias = "zh_HK")
+    )]
+    ChineseHongKong,
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "chinese-simplified", alias = "zh_cn", alias = "zh_CN")
+    )]
+    ChineseSimplified,
+    #[cfg_attr(
+        feature = "cli",
+        value(name = "chinese-taiwan", alias = "zh_tw", alias = "zh_TW")
+    )]
+    ChineseTaiwan,
+    #[cfg_attr(feature = "cli", value(name = "english-asia", alias = "en-asia"))]
+    EnglishAsia,
+    #[cfg_attr(feature = "cli", value(name = "thai", alias = "th"))]
+    Thai,
+    /// Locale found in the config directory, see `LanguageArg::Custom`
+    #[cfg_attr(feature = "cli", value(skip))]
+    Custom,
+}
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LanguageCode::ChineseHongKong => write!(f, "chinese-hong-kong"),
+            LanguageCode::ChineseSimplified => write!(f, "chinese-simplified"),
+            LanguageCode::ChineseTaiwan => write!(f, "chinese-taiwan"),
+            LanguageCode::English => write!(f, "english"),
+            LanguageCode::EnglishAsia => write!(f, "english-asia"),
+            LanguageCode::Japanese => write!(f, "japanese"),
+            LanguageCode::Thai => write!(f, "thai"),
+            LanguageCode::French => write!(f, "french"),
+            LanguageCode::Custom => write!(f, "custom"),
+        }
+    }
+}
+
+impl LanguageCode {
+    /// Every language with a built-in locale
+    pub const ALL: [LanguageCode; 8] = [
+        LanguageCode::English,
+        LanguageCode::Japanese,
+        LanguageCode::French,
+        LanguageCode::ChineseHongKong,
+        LanguageCode::ChineseSimplified,
+        LanguageCode::ChineseTaiwan,
+        LanguageCode::EnglishAsia,
+        LanguageCode::Thai,
+    ];
+
+    /// Names `--language` accepts for the language, case-insensitively
+    pub fn names(self) -> &'static [&'static str] {
+        match self {
+            LanguageCode::ChineseHongKong => &["chinese-hongkong", "zh_hk"],
+            LanguageCode::ChineseSimplified => &["chinese-simplified", "zh_cn"],
+            LanguageCode::ChineseTaiwan => &["chinese-taiwan", "zh_tw"],
+            LanguageCode::English => &["english", "en"],
+            LanguageCode::EnglishAsia => &["english-asia", "en-asia"],
+            LanguageCode::Japanese => &["japanese", "jp"],
+            LanguageCode::Thai => &["thai", "th"],
+            LanguageCode::French => &["french", "fr"],
+            LanguageCode::Custom => &[],
+        }
+    }
+
+    /// Name of the built-in locale file of the language (without `.toml`)
+    pub fn locale_name(self) -> Option<&'static str> {
+        match self {
+            LanguageCode::ChineseHongKong => Some("zh_hk"),
+            LanguageCode::ChineseSimplified => Some("zh_cn"),
+            LanguageCode::ChineseTaiwan => Some("zh_tw"),
+            LanguageCode::English => Some("en"),
+            LanguageCode::EnglishAsia => Some("en_asia"),
+            LanguageCode::Japanese => Some("jp"),
+            LanguageCode::Thai => Some("th"),
+            LanguageCode::French => Some("fr"),
+            LanguageCode::Custom => None,
+        }
+    }
+
+    /// Human-readable name, for language pickers
+    pub fn display_name(self) -> &'static str {
+        match self {
+            LanguageCode::ChineseHongKong => "Chinese (Hong Kong)",
+            LanguageCode::ChineseSimplified => "Chinese (Simplified)",
+            LanguageCode::ChineseTaiwan => "Chinese (Taiwan)",
+            LanguageCode::English => "English",
+            LanguageCode::EnglishAsia => "English (Asia)",
+            LanguageCode::Japanese => "Japanese",
+            LanguageCode::Thai => "Thai",
+            LanguageCode::French => "French",
+            LanguageCode::Custom => "Custom",
+        }
+    }
+
+    pub fn to_path(self) -> PathBuf {
+        let path = self.to_string();
+        PathBuf::from(path)
+    }
+}
+
+impl FromStr for LanguageCode {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "chinese-hongkong" => Ok(LanguageCode::ChineseHongKong),
+            "chinese-simplified" => Ok(LanguageCode::ChineseSimplified),
+            "chinese-taiwan" => Ok(LanguageCode::ChineseTaiwan),
+            "english" => Ok(LanguageCode::English),
+            "english-asia" => Ok(LanguageCode::EnglishAsia),
+            "japanese" => Ok(LanguageCode::Japanese),
+            "thai" => Ok(LanguageCode::Thai),
+            "french" => Ok(LanguageCode::French),
+            "custom" => Ok(LanguageCode::Custom),
+            _ => Err(()),
+        }
+    }
+}
+
+/// `--language` of a pull: a supported language, or `custom:<code>` for any `<code>.toml`
+/// locale dropped in the config directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageArg {
+    Known(LanguageCode),
+    Custom(String),
+}
+
+impl FromStr for LanguageArg {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.strip_prefix("custom:") {
+            Some(code) => {
+                utils::validate_id("locale code", code).map_err(|e| e.to_string())?;
+                Ok(LanguageArg::Custom(code.to_string()))
+            }
+            None => LanguageCode::ALL
+                .into_iter()
+                .find(|language| {
+                    language
+                        .names()
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(input))
+                })
+                .map(LanguageArg::Known)
+                .ok_or_else(|| format!("invalid variant: {}", input)),
+        }
+    }
+}
+
+impl LanguageArg {
+    /// Language of the dataset, along with the locale file to use instead of its built-in one
+    pub fn resolve(self, locale_file: Option<PathBuf>) -> Result<(LanguageCode, Option<PathBuf>)> {
+        match self {
+            LanguageArg::Known(language) => Ok((language, locale_file)),
+            LanguageArg::Custom(code) => {
+                if locale_file.is_some() {
+                    return Err(VegaError::config(
+                        "--locale-file cannot be used with a custom language",
+                    ));
+                }
+                let locale_file = config::get_config_dir()?.join(format!("{}.toml", code));
+                Ok((LanguageCode::Custom, Some(locale_file)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_arg_parses_known_and_custom_languages() {
+        assert_eq!(
+            "jp".parse::<LanguageArg>(),
+            Ok(LanguageArg::Known(LanguageCode::Japanese))
+        );
+        assert_eq!(
+            "custom:pl".parse::<LanguageArg>(),
+            Ok(LanguageArg::Custom(String::from("pl")))
+        );
+        assert_eq!(
+            "ZH_HK".parse::<LanguageArg>(),
+            Ok(LanguageArg::Known(LanguageCode::ChineseHongKong))
+        );
+        assert!("klingon".parse::<LanguageArg>().is_err());
+        assert!("custom:../pl".parse::<LanguageArg>().is_err());
+        assert!("custom".parse::<LanguageArg>().is_err());
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn names_match_cli_values() {
+        use clap::ValueEnum;
+
+        assert_eq!(LanguageCode::value_variants(), LanguageCode::ALL);
+        for language in LanguageCode::ALL {
+            let value = language.to_possible_value().unwrap();
+            for name in language.names() {
+                assert!(value.matches(name, true), "{}", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Scraping and parsing of the official One Piece Trading Card Game card lists, as used by the
+//! `vega` command line tool.
+//!
+//! The main entry points are:
+//! - [`scraper::OpTcgScraper`] to fetch packs, cards and card images from the site of a language
+//! - [`localizer::Localizer`] for the labels and selectors of that site (built-in ones for every
+//!   [`language::LanguageCode`], or loaded from a locale file)
+//! - [`storage::DataStore`] to write and read datasets in the layout `vega pull` produces
+//! - [`progress::ProgressReporter`] to follow the progress of a scraper (packs, cards, images and
+//!   errors) as events
+//! - [`error::VegaError`], the error of every fallible function, to match on the kind of failure
+//!   (network, parsing, storage, locale or config)
+//!
+//! ```no_run
+//! use vegapull::{
+//!     language::LanguageCode, localizer::Localizer, scraper::OpTcgScraper, user_agent::UserAgentPool,
+//! };
+//!
+//! # fn main() -> vegapull::error::Result<()> {
+//! let localizer = Localizer::load(LanguageCode::English)?;
+//! let scraper = OpTcgScraper::builder()
+//!     .localizer(localizer)
+//!     .user_agents(UserAgentPool::default())
+//!     .build()?;
+//!
+//! for pack_id in scraper.fetch_packs()?.keys() {
+//!     let fetched = scraper.fetch_cards(pack_id)?;
+//!     println!("{}: {} cards", pack_id, fetched.cards.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod card;
+pub mod config;
+pub mod error;
+pub mod language;
+pub mod localizer;
+pub mod pack;
+pub mod progress;
+pub mod rate_limit;
+pub mod scraper;
+pub mod storage;
+pub mod user_agent;
+pub mod utils;
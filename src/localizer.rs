@@ -1,9 +1,21 @@
-use anyhow::{ensure, Context, Result};
-use log::{debug, info};
+use log::{debug, info, warn};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use unicode_normalization::UnicodeNormalization;
 
-use crate::{cli::LanguageCode, config};
+use crate::{
+    card::{sanitizer::Sanitizer, CardAttribute, CardCategory, CardColor, CardRarity},
+    config,
+    error::{Context, Result, VegaError},
+    language::LanguageCode,
+    utils,
+};
 
 pub const EN_LOCALE_RAW: &str = include_str!("../config/en.toml");
 pub const EN_ASIA_LOCALE_RAW: &str = include_str!("../config/en_asia.toml");
@@ -13,6 +25,17 @@ pub const ZH_TW_LOCALE_RAW: &str = include_str!("../config/zh_tw.toml");
 pub const TH_LOCALE_RAW: &str = include_str!("../config/th.toml");
 pub const FR_LOCALE_RAW: &str = include_str!("../config/fr.toml");
 
+/// Locales compiled into the binary, by name of their file in the config directory
+pub const BUILTIN_LOCALES: [(&str, &str); 7] = [
+    ("en", EN_LOCALE_RAW),
+    ("en_asia", EN_ASIA_LOCALE_RAW),
+    ("jp", JP_LOCALE_RAW),
+    ("zh_hk", ZH_HK_LOCALE_RAW),
+    ("zh_tw", ZH_TW_LOCALE_RAW),
+    ("th", TH_LOCALE_RAW),
+    ("fr", FR_LOCALE_RAW),
+];
+
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct Aliases {
     #[serde(default)]
@@ -23,10 +46,37 @@ pub struct Aliases {
     pub categories: HashMap<String, Vec<String>>,
     #[serde(default)]
     pub rarities: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub keywords: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub timings: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub conditions: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub types: HashMap<String, Vec<String>>,
+
+    // Regexes matching templated labels (e.g. `Counter +1000`), by table then canonical key
+    #[serde(default)]
+    pub patterns: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// How the parts of an attribute icon's alt text are matched against attribute labels
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeParsing {
+    /// Each part is one attribute label
+    #[default]
+    Split,
+    /// Parts may combine several labels without separator (e.g. `斬打`)
+    Composite,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Localizer {
+    // Version of the locale file format, files without one are version 1
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+
     pub hostname: String,
 
     pub colors: HashMap<String, String>,
@@ -34,9 +84,235 @@ pub struct Localizer {
     pub categories: HashMap<String, String>,
     pub rarities: HashMap<String, String>,
 
+    // Bracketed terms of effects (e.g. `[Rush]`, `[On Play]`, `[Once Per Turn]`)
+    #[serde(default)]
+    pub keywords: HashMap<String, String>,
+    #[serde(default)]
+    pub timings: HashMap<String, String>,
+    #[serde(default)]
+    pub conditions: HashMap<String, String>,
+
+    // Canonical English name of types (e.g. `"Straw Hat Crew" = "麦わらの一味"`)
+    #[serde(default)]
+    pub types: HashMap<String, String>,
+
+    // Steps turning the HTML of text fields into plain text
+    #[serde(default)]
+    pub sanitizer: Sanitizer,
+
+    // Attribute icon number (`ico_typeNN.png`) to attribute keys, checked before the built-in table
+    #[serde(default)]
+    pub attribute_icons: HashMap<String, Vec<String>>,
+
+    // CSS selectors replacing the built-in ones of the scrapers, by field (e.g. `name = "dt>div.name"`)
+    #[serde(default)]
+    pub selectors: HashMap<String, String>,
+
+    // Separators between the colors of multicolor cards (e.g. `["/", "・"]`), `/` if empty
+    #[serde(default)]
+    pub color_separators: Vec<String>,
+
+    // Separators between the attributes of an attribute icon's alt text, `/` if empty
+    #[serde(default)]
+    pub attribute_separators: Vec<String>,
+
+    // How to parse each part of an attribute icon's alt text
+    #[serde(default)]
+    pub attribute_parsing: AttributeParsing,
+
+    // Values meaning "none" in cost, power, counter and block number fields, besides dashes
+    #[serde(default)]
+    pub null_tokens: Vec<String>,
+
+    // Alternate paths of card images tried by `--image-quality best`, by variant name, built from
+    // `{dir}`, `{stem}` and `{ext}` of the image path (e.g. `large = "{dir}/large/{stem}.{ext}"`)
+    #[serde(default)]
+    pub image_variants: BTreeMap<String, String>,
+
+    // Path of the image of the back of cards, if not the usual one (e.g. `"../images/card_back.jpg"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_back: Option<String>,
+
+    // Labels the site prefixes some texts with (e.g. `trigger = "[Trigger]"`)
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
     // Optional alias lists to accept multiple labels per canonical key
     #[serde(default)]
     pub aliases: Aliases,
+
+    // Locale to take labels missing from this one from (e.g. `fallback = "en"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+
+    // Set when loaded from a file, recorded in vega.meta.toml
+    #[serde(skip)]
+    pub source: Option<LocaleSource>,
+
+    // Loaded `fallback` locale
+    #[serde(skip)]
+    pub fallback_locale: Option<Box<Localizer>>,
+}
+
+/// Locale file format this vegapull reads, bumped when locales need keys older files lack
+pub const LOCALE_FORMAT_VERSION: u32 = 1;
+
+fn default_format_version() -> u32 {
+    1
+}
+
+/// Keys every locale must map (unless it has a fallback)
+pub const COLOR_KEYS: &[&str] = &["red", "green", "blue", "purple", "black", "yellow"];
+pub const ATTRIBUTE_KEYS: &[&str] = &["slash", "strike", "ranged", "special", "wisdom"];
+pub const CATEGORY_KEYS: &[&str] = &["leader", "character", "event", "stage", "don"];
+pub const RARITY_KEYS: &[&str] = &[
+    "common",
+    "uncommon",
+    "rare",
+    "super_rare",
+    "secret_rare",
+    "leader",
+    "special",
+    "treasure_rare",
+    "promo",
+];
+
+/// Canonical keys of the tables every locale must map, by table name
+pub const CANONICAL_KEYS: [(&str, &[&str]); 4] = [
+    ("colors", COLOR_KEYS),
+    ("attributes", ATTRIBUTE_KEYS),
+    ("categories", CATEGORY_KEYS),
+    ("rarities", RARITY_KEYS),
+];
+
+/// Dashes always meaning "none" in numeric fields
+const DASH_TOKENS: &[&str] = &["-", "‐", "–", "—", "―", "−"];
+
+/// Fields whose selector can be overridden in `[selectors]`
+const SELECTOR_FIELDS: &[&str] = &[
+    "packs",
+    "card_ids",
+    "name",
+    "rarity",
+    "category",
+    "img_url",
+    "img_back_url",
+    "colors",
+    "cost",
+    "attributes",
+    "power",
+    "counter",
+    "block_number",
+    "types",
+    "effect",
+    "trigger",
+    "products",
+    "pack_cover",
+    "pack_logo",
+];
+
+/// Tables accepting `[aliases.patterns.<table>]`
+const PATTERN_TABLES: &[&str] = &[
+    "colors",
+    "attributes",
+    "categories",
+    "rarities",
+    "keywords",
+    "timings",
+    "conditions",
+    "types",
+];
+
+/// Mapping table of a locale file: its name, required keys and how to check a key
+type KeyTable<'a> = (
+    &'a str,
+    &'a HashMap<String, String>,
+    &'a HashMap<String, Vec<String>>,
+    &'a [&'a str],
+    fn(&str) -> bool,
+);
+
+/// Language vegapull can pull, and the site it is pulled from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub code: LanguageCode,
+    pub display_name: &'static str,
+    pub hostname: String,
+}
+
+impl fmt::Display for LanguageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.display_name, self.hostname)
+    }
+}
+
+/// Locale file a `Localizer` was loaded from
+#[derive(Debug, Clone)]
+pub struct LocaleSource {
+    pub file_name: String,
+    pub sha256: String,
+}
+
+/// NFKC-normalized and lowercased `label`, so that labels differing only by case
+/// or width (e.g. "ÉVÈNEMENT" and "Évènement") compare equal
+fn fold_label(label: &str) -> String {
+    label
+        .nfkc()
+        .collect::<String>()
+        .to_lowercase()
+        .nfkc()
+        .collect()
+}
+
+/// Non-empty parts of `raw`, split on every separator (or `/` if there is none)
+fn split_labels<'a>(raw: &'a str, separators: &[String]) -> Vec<&'a str> {
+    let default_separators = [String::from("/")];
+    let separators = match separators.is_empty() {
+        true => &default_separators[..],
+        false => separators,
+    };
+
+    let mut parts = vec![raw];
+    for separator in separators.iter().filter(|sep| !sep.is_empty()) {
+        parts = parts
+            .into_iter()
+            .flat_map(|part| part.split(separator.as_str()))
+            .collect();
+    }
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Merge `overlay` into `locale`: tables are merged, arrays extended and other values replaced
+fn merge_overlay(locale: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (locale.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(overlay)) => {
+                merge_overlay(table, overlay);
+            }
+            (Some(toml::Value::Array(array)), toml::Value::Array(overlay)) => {
+                for item in overlay {
+                    if !array.contains(&item) {
+                        array.push(item);
+                    }
+                }
+            }
+            (_, value) => {
+                locale.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Content of a locale file, failing with a locale error rather than a storage one
+fn read_locale_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| {
+        VegaError::locale(format!("Failed to open file: {}", path.display())).with_cause(e)
+    })
 }
 
 impl Localizer {
@@ -51,11 +327,11 @@ impl Localizer {
             return Some(k.clone());
         }
 
-        // Case-insensitive match for Latin; exact match for others
-        let v_lower = v.to_ascii_lowercase();
+        // Case-insensitive match on NFKC-normalized aliases
+        let v_folded = fold_label(v);
         for (k, list) in aliases {
             for a in list {
-                if a == v || a.to_ascii_lowercase() == v_lower {
+                if a == v || fold_label(a) == v_folded {
                     return Some(k.clone());
                 }
             }
@@ -64,56 +340,498 @@ impl Localizer {
         None
     }
 
+    /// Colors, attributes, categories and rarities tables, with their canonical keys
+    fn key_tables(&self) -> [KeyTable<'_>; 4] {
+        [
+            (
+                "colors",
+                &self.colors,
+                &self.aliases.colors,
+                COLOR_KEYS,
+                |key| key.parse::<CardColor>().is_ok(),
+            ),
+            (
+                "attributes",
+                &self.attributes,
+                &self.aliases.attributes,
+                ATTRIBUTE_KEYS,
+                |key| key.parse::<CardAttribute>().is_ok(),
+            ),
+            (
+                "categories",
+                &self.categories,
+                &self.aliases.categories,
+                CATEGORY_KEYS,
+                |key| key.parse::<CardCategory>().is_ok(),
+            ),
+            (
+                "rarities",
+                &self.rarities,
+                &self.aliases.rarities,
+                RARITY_KEYS,
+                |key| key.parse::<CardRarity>().is_ok(),
+            ),
+        ]
+    }
+
+    /// Whether the locale itself (not its fallback) has a label, alias or alias pattern
+    /// for the canonical `key` of `table`
+    pub fn maps_key(&self, table: &str, key: &str) -> bool {
+        let Some((_, primary, aliases, _, _)) = self
+            .key_tables()
+            .into_iter()
+            .find(|(name, ..)| *name == table)
+        else {
+            return false;
+        };
+
+        primary.contains_key(key)
+            || aliases.contains_key(key)
+            || self
+                .aliases
+                .patterns
+                .get(table)
+                .is_some_and(|patterns| patterns.contains_key(key))
+    }
+
+    /// Labels of every table by canonical key, including the ones taken from the fallback locale
+    pub fn label_maps(&self) -> BTreeMap<&'static str, BTreeMap<String, String>> {
+        let tables = [
+            ("colors", &self.colors),
+            ("attributes", &self.attributes),
+            ("categories", &self.categories),
+            ("rarities", &self.rarities),
+            ("keywords", &self.keywords),
+            ("timings", &self.timings),
+            ("conditions", &self.conditions),
+            ("types", &self.types),
+        ];
+
+        let mut maps = match &self.fallback_locale {
+            Some(fallback_locale) => fallback_locale.label_maps(),
+            None => BTreeMap::new(),
+        };
+        for (name, table) in tables {
+            let map = maps.entry(name).or_default();
+            map.extend(table.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        maps
+    }
+
+    /// Problems with the mappings of the locale: missing or unsupported keys,
+    /// and labels matching several keys
+    pub fn validate(&self) -> Vec<String> {
+        let tables = self.key_tables();
+
+        let mut problems = Vec::new();
+        for (name, primary, aliases, required, is_supported) in tables {
+            // Labels missing from a locale with a fallback are taken from the fallback
+            for key in required.iter().filter(|_| self.fallback.is_none()) {
+                if !primary.contains_key(*key) {
+                    problems.push(format!("{}: missing `{}`", name, key));
+                }
+            }
+
+            let keys: BTreeSet<&String> = primary.keys().chain(aliases.keys()).collect();
+            for key in keys {
+                if !is_supported(key) {
+                    problems.push(format!("{}: unsupported key `{}`", name, key));
+                }
+            }
+
+            let mut labels: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+            let primary_labels = primary.iter();
+            let alias_labels = aliases
+                .iter()
+                .flat_map(|(key, list)| list.iter().map(move |label| (key, label)));
+            for (key, label) in primary_labels.chain(alias_labels) {
+                labels
+                    .entry(fold_label(label.trim()))
+                    .or_default()
+                    .insert(key);
+            }
+            for (label, keys) in labels.iter().filter(|(_, keys)| keys.len() > 1) {
+                let keys: Vec<&str> = keys.iter().copied().collect();
+                problems.push(format!(
+                    "{}: `{}` matches several keys: {}",
+                    name,
+                    label,
+                    keys.join(", ")
+                ));
+            }
+        }
+
+        for (table, patterns) in &self.aliases.patterns {
+            if !PATTERN_TABLES.contains(&table.as_str()) {
+                problems.push(format!("aliases.patterns: unknown table `{}`", table));
+            }
+            for pattern in patterns.values().flatten() {
+                if let Err(e) = Regex::new(pattern) {
+                    problems.push(format!(
+                        "aliases.patterns.{}: invalid pattern `{}`: {}",
+                        table, pattern, e
+                    ));
+                }
+            }
+        }
+
+        let selectors: BTreeMap<&String, &String> = self.selectors.iter().collect();
+        for (field, selector) in selectors {
+            if !SELECTOR_FIELDS.contains(&field.as_str()) {
+                problems.push(format!("selectors: unknown field `{}`", field));
+            }
+            if let Err(e) = utils::parse_selector(selector) {
+                problems.push(format!("selectors.{}: {}", field, e));
+            }
+        }
+
+        if self.color_separators.iter().any(String::is_empty) {
+            problems.push(String::from("color_separators: empty separator"));
+        }
+        if self.attribute_separators.iter().any(String::is_empty) {
+            problems.push(String::from("attribute_separators: empty separator"));
+        }
+
+        let icons: BTreeMap<&String, &Vec<String>> = self.attribute_icons.iter().collect();
+        for (icon, keys) in icons {
+            for key in keys {
+                if key.parse::<CardAttribute>().is_err() {
+                    problems.push(format!(
+                        "attribute_icons: unsupported key `{}` for {}",
+                        key, icon
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+
+    /// Literal labels and aliases of a table first, then its alias patterns
+    fn match_table(
+        &self,
+        table: &str,
+        primary: &HashMap<String, String>,
+        aliases: &HashMap<String, Vec<String>>,
+        value: &str,
+    ) -> Option<String> {
+        Self::match_with_alias(primary, aliases, value)
+            .or_else(|| Self::match_pattern(self.aliases.patterns.get(table)?, value))
+    }
+
+    fn match_pattern(patterns: &BTreeMap<String, Vec<String>>, value: &str) -> Option<String> {
+        let v = value.trim();
+        for (k, list) in patterns {
+            for pattern in list {
+                match Regex::new(pattern) {
+                    Ok(reg) if reg.is_match(v) => return Some(k.clone()),
+                    Ok(_) => {}
+                    Err(e) => warn!("invalid alias pattern `{}`: {}", pattern, e),
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Parts of a multicolor label, split on every `color_separators` (or `/`)
+    pub fn split_colors<'a>(&self, raw_colors: &'a str) -> Vec<&'a str> {
+        split_labels(raw_colors, &self.color_separators)
+    }
+
+    /// Parts of an attribute icon's alt text, split on every `attribute_separators` (or `/`)
+    pub fn split_attributes<'a>(&self, raw_attributes: &'a str) -> Vec<&'a str> {
+        split_labels(raw_attributes, &self.attribute_separators)
+    }
+
+    /// Whether a numeric field value means "none": empty, a dash or one of `null_tokens`
+    pub fn is_null_token(&self, value: &str) -> bool {
+        let strip = |value: &str| -> String {
+            fold_label(value)
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect()
+        };
+
+        let value = strip(value);
+        value.is_empty()
+            || DASH_TOKENS.contains(&value.as_str())
+            || self.null_tokens.iter().any(|token| strip(token) == value)
+    }
+
+    /// CSS selector of `field`, from `[selectors]` if the locale overrides it
+    pub fn selector<'a>(&'a self, field: &str, default: &'a str) -> &'a str {
+        self.selectors.get(field).map_or(default, String::as_str)
+    }
+
+    /// Ask the fallback locale, if any, for a `kind` label this locale does not know
+    fn match_fallback(
+        &self,
+        kind: &str,
+        value: &str,
+        matcher: impl Fn(&Localizer) -> Option<String>,
+    ) -> Option<String> {
+        let fallback_locale = self.fallback_locale.as_deref()?;
+        let key = matcher(fallback_locale)?;
+        warn!(
+            "{} `{}` missing from locale, matched `{}` with fallback locale `{}`",
+            kind,
+            value,
+            key,
+            self.fallback.as_deref().unwrap_or_default()
+        );
+        Some(key)
+    }
+
     pub fn match_color(&self, value: &str) -> Option<String> {
-        Self::match_with_alias(&self.colors, &self.aliases.colors, value)
+        self.match_table("colors", &self.colors, &self.aliases.colors, value)
+            .or_else(|| self.match_fallback("color", value, |l| l.match_color(value)))
     }
 
     pub fn match_attribute(&self, value: &str) -> Option<String> {
-        Self::match_with_alias(&self.attributes, &self.aliases.attributes, value)
+        self.match_table(
+            "attributes",
+            &self.attributes,
+            &self.aliases.attributes,
+            value,
+        )
+        .or_else(|| self.match_fallback("attribute", value, |l| l.match_attribute(value)))
     }
 
     pub fn match_category(&self, value: &str) -> Option<String> {
-        Self::match_with_alias(&self.categories, &self.aliases.categories, value)
+        self.match_table(
+            "categories",
+            &self.categories,
+            &self.aliases.categories,
+            value,
+        )
+        .or_else(|| self.match_fallback("category", value, |l| l.match_category(value)))
     }
 
     pub fn match_rarity(&self, value: &str) -> Option<String> {
-        Self::match_with_alias(&self.rarities, &self.aliases.rarities, value)
+        self.match_table("rarities", &self.rarities, &self.aliases.rarities, value)
+            .or_else(|| self.match_fallback("rarity", value, |l| l.match_rarity(value)))
+    }
+
+    pub fn match_keyword(&self, value: &str) -> Option<String> {
+        self.match_table("keywords", &self.keywords, &self.aliases.keywords, value)
+    }
+
+    pub fn match_timing(&self, value: &str) -> Option<String> {
+        self.match_table("timings", &self.timings, &self.aliases.timings, value)
+    }
+
+    pub fn match_condition(&self, value: &str) -> Option<String> {
+        self.match_table(
+            "conditions",
+            &self.conditions,
+            &self.aliases.conditions,
+            value,
+        )
+    }
+
+    pub fn match_type(&self, value: &str) -> Option<String> {
+        self.match_table("types", &self.types, &self.aliases.types, value)
+    }
+
+    /// Remove the localized `[Trigger]` label in front of a trigger effect, if any
+    pub fn strip_trigger_label<'a>(&self, value: &'a str) -> &'a str {
+        let value = value.trim();
+        match self.labels.get("trigger") {
+            Some(label) => value.strip_prefix(label.as_str()).unwrap_or(value).trim(),
+            None => value,
+        }
+    }
+
+    /// Load `locale_file` if given, the locale of `language` from the config directory otherwise
+    pub fn load_or_file(language: LanguageCode, locale_file: Option<&Path>) -> Result<Localizer> {
+        match locale_file {
+            Some(locale_file) => {
+                info!("load locale from: {}", locale_file.display());
+                Self::load_from_path(locale_file)
+            }
+            None => Self::load(language),
+        }
     }
 
     pub fn load(language: LanguageCode) -> Result<Localizer> {
-        match language {
-            LanguageCode::ChineseHongKong => Self::load_from_file("zh_hk"),
-            LanguageCode::ChineseSimplified => Self::load_from_file("zh_cn"),
-            LanguageCode::ChineseTaiwan => Self::load_from_file("zh_tw"),
-            LanguageCode::English => Self::load_from_file("en"),
-            LanguageCode::EnglishAsia => Self::load_from_file("en_asia"),
-            LanguageCode::Japanese => Self::load_from_file("jp"),
-            LanguageCode::Thai => Self::load_from_file("th"),
-            LanguageCode::French => Self::load_from_file("fr"),
+        match language.locale_name() {
+            Some(locale) => Self::load_from_file(locale),
+            None => Err(VegaError::config(
+                "custom languages are pulled with `--language custom:<code>`",
+            )),
         }
     }
 
+    /// Languages with a loadable locale, for language pickers
+    pub fn supported_languages() -> Vec<LanguageInfo> {
+        LanguageCode::ALL
+            .into_iter()
+            .filter_map(|code| match Self::load(code) {
+                Ok(localizer) => Some(LanguageInfo {
+                    code,
+                    display_name: code.display_name(),
+                    hostname: localizer.hostname,
+                }),
+                Err(e) => {
+                    debug!("language {} not supported: {:#}", code, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Load `<locale>.toml` from the config directory, or the built-in locale if it is missing
     pub fn load_from_file(locale: &str) -> Result<Localizer> {
-        let config_dir = config::get_config_dir()?;
+        let (file_name, locale_data) = Self::read_config_locale(locale)?;
+        Self::load_chain(&file_name, &locale_data, &mut Vec::new())
+    }
 
-        ensure!(
-            config_dir.exists(),
-            format!("config directory not found: {}", config_dir.display())
-        );
+    /// Load the locale file at `locale_path`, for `--locale-file` or the config directory
+    pub fn load_from_path(locale_path: &Path) -> Result<Localizer> {
+        if !locale_path.exists() {
+            return Err(VegaError::locale(format!(
+                "locale file not found: {}",
+                locale_path.display()
+            )));
+        }
 
-        let locale_path = config_dir.join(format!("{}.toml", locale));
-        ensure!(
-            locale_path.exists(),
-            format!("locale file not found: {}", locale_path.display())
-        );
+        let locale_data = read_locale_file(locale_path)?;
+        let locale_data = Self::with_overlay(locale_path, locale_data)?;
+        let file_name = locale_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        Self::load_chain(&file_name, &locale_data, &mut Vec::new())
+    }
 
-        info!("load {} locale from: {}", locale, locale_path.display());
+    /// `<locale>.local.toml` next to a locale file, merged on top of it so that
+    /// user additions survive `vega config sync`
+    pub fn overlay_path(locale_path: &Path) -> PathBuf {
+        let stem = locale_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        locale_path.with_file_name(format!("{}.local.toml", stem))
+    }
 
-        let locale_data = fs::read_to_string(&locale_path)
-            .with_context(|| format!("Failed to open file: {}", locale_path.display()))?;
+    fn with_overlay(locale_path: &Path, locale_data: String) -> Result<String> {
+        let overlay_path = Self::overlay_path(locale_path);
+        if !overlay_path.exists() {
+            return Ok(locale_data);
+        }
+
+        info!("merge locale overlay: {}", overlay_path.display());
+        let overlay_data = read_locale_file(&overlay_path)?;
+        let overlay: toml::Table = toml::from_str(&overlay_data)
+            .with_context(|| format!("Invalid locale overlay: {}", overlay_path.display()))?;
+
+        let mut locale: toml::Table = toml::from_str(&locale_data)?;
+        merge_overlay(&mut locale, overlay);
+        Ok(toml::to_string(&locale)?)
+    }
+
+    /// Content of `<locale>.toml` in the config directory, falling back to the
+    /// compiled-in locale when the directory or the file does not exist
+    fn read_config_locale(locale: &str) -> Result<(String, String)> {
+        let file_name = format!("{}.toml", locale);
+        let locale_path = config::get_config_dir().map(|config_dir| config_dir.join(&file_name));
+
+        if let Ok(locale_path) = &locale_path {
+            if locale_path.exists() {
+                info!("load {} locale from: {}", locale, locale_path.display());
+                let locale_data = read_locale_file(locale_path)?;
+                return Ok((file_name, Self::with_overlay(locale_path, locale_data)?));
+            }
+        }
+
+        match BUILTIN_LOCALES.iter().find(|(name, _)| *name == locale) {
+            Some((_, raw)) => {
+                info!("load built-in {} locale", locale);
+                let locale_data = match &locale_path {
+                    Ok(locale_path) => Self::with_overlay(locale_path, raw.to_string())?,
+                    Err(_) => raw.to_string(),
+                };
+                Ok((file_name, locale_data))
+            }
+            None => match locale_path {
+                Ok(locale_path) => Err(VegaError::locale(format!(
+                    "locale file not found: {}",
+                    locale_path.display()
+                ))),
+                Err(e) => Err(e.context(format!("locale file not found: {}", file_name))),
+            },
+        }
+    }
+
+    fn check_format_version(file_name: &str, format_version: u32) -> Result<()> {
+        if format_version > LOCALE_FORMAT_VERSION {
+            return Err(VegaError::locale(format!(
+                "{} uses locale format {} but this vegapull only reads up to {}: upgrade vegapull",
+                file_name, format_version, LOCALE_FORMAT_VERSION
+            )));
+        }
+        if format_version != LOCALE_FORMAT_VERSION {
+            return Err(VegaError::locale(format!(
+                "{} uses locale format {} but this vegapull expects {}: run `vega config sync` to update it",
+                file_name, format_version, LOCALE_FORMAT_VERSION
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// `card_back` is downloaded like card images, so it must be relative to the site or absolute
+    fn check_card_back(file_name: &str, card_back: Option<&str>) -> Result<()> {
+        match card_back {
+            Some(card_back) if !card_back.starts_with("../") && !card_back.starts_with("http") => {
+                Err(VegaError::locale(format!(
+                    "{}: card_back `{}` must start with `../` or be an absolute URL",
+                    file_name, card_back
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parse a locale along with its chain of fallback locales, from the config directory
+    fn load_chain(
+        file_name: &str,
+        locale_data: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<Localizer> {
         debug!("loaded {}", locale_data);
 
-        let localizer: Localizer = toml::from_str(&locale_data)?;
+        let mut localizer: Localizer = toml::from_str(locale_data)
+            .with_context(|| format!("Invalid locale file: {}", file_name))?;
+        Self::check_format_version(file_name, localizer.format_version)?;
+        Self::check_card_back(file_name, localizer.card_back.as_deref())?;
+        localizer.source = Some(LocaleSource {
+            file_name: file_name.to_string(),
+            sha256: format!("{:x}", Sha256::digest(locale_data.as_bytes())),
+        });
+
+        if let Some(fallback) = &localizer.fallback {
+            let stem = file_name.strip_suffix(".toml").unwrap_or(file_name);
+            chain.push(stem.to_string());
+            if chain.contains(fallback) {
+                return Err(VegaError::locale(format!(
+                    "locale fallbacks loop: {} -> {}",
+                    chain.join(" -> "),
+                    fallback
+                )));
+            }
+
+            let fallback_locale = Self::read_config_locale(fallback)
+                .and_then(|(file_name, locale_data)| {
+                    Self::load_chain(&file_name, &locale_data, chain)
+                })
+                .with_context(|| format!("failed to load fallback locale `{}`", fallback))?;
+            localizer.fallback_locale = Some(Box::new(fallback_locale));
+        }
+
         Ok(localizer)
     }
 }
@@ -158,4 +876,271 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn validate_embedded_locales_returns_empty() {
+        for raw in [
+            EN_LOCALE_RAW,
+            EN_ASIA_LOCALE_RAW,
+            JP_LOCALE_RAW,
+            ZH_HK_LOCALE_RAW,
+            ZH_TW_LOCALE_RAW,
+            TH_LOCALE_RAW,
+            FR_LOCALE_RAW,
+        ] {
+            let localizer: Localizer = toml::from_str(raw).unwrap();
+            assert_eq!(localizer.validate(), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn validate_reports_missing_unsupported_and_ambiguous_keys() {
+        let mut localizer: Localizer = toml::from_str(EN_LOCALE_RAW).unwrap();
+        localizer.colors.remove("yellow");
+        localizer
+            .colors
+            .insert(String::from("pink"), String::from("Pink"));
+        localizer
+            .aliases
+            .colors
+            .insert(String::from("red"), vec![String::from("blue")]);
+
+        assert_eq!(
+            localizer.validate(),
+            vec![
+                "colors: missing `yellow`",
+                "colors: unsupported key `pink`",
+                "colors: `blue` matches several keys: blue, red",
+            ]
+        );
+    }
+
+    #[test]
+    fn reverse_search_folds_unicode_case_and_width() {
+        let map = HashMap::new();
+        let mut alias_map: HashMap<String, Vec<String>> = HashMap::new();
+        alias_map.insert(String::from("event"), vec![String::from("Évènement")]);
+        alias_map.insert(String::from("leader"), vec![String::from("LEADER")]);
+
+        for value in ["ÉVÈNEMENT", "évènement", "E\u{301}ve\u{300}nement"] {
+            assert_eq!(
+                Localizer::match_with_alias(&map, &alias_map, value).as_deref(),
+                Some("event")
+            );
+        }
+        assert_eq!(
+            Localizer::match_with_alias(&map, &alias_map, "ＬＥＡＤＥＲ").as_deref(),
+            Some("leader")
+        );
+    }
+
+    #[test]
+    fn match_uses_alias_patterns_after_literals() {
+        let localizer: Localizer = toml::from_str(&format!(
+            r#"{}
+            [aliases.patterns.keywords]
+            counter = ['^Counter \+\d+$']
+            [aliases.patterns.rarities]
+            special = ['(?i)^sp\b']
+            "#,
+            EN_LOCALE_RAW
+        ))
+        .unwrap();
+
+        assert_eq!(
+            localizer.match_keyword("Counter +1000").as_deref(),
+            Some("counter")
+        );
+        assert_eq!(localizer.match_keyword("Counter"), None);
+        assert_eq!(localizer.match_rarity("SP SEC").as_deref(), Some("special"));
+        assert_eq!(
+            localizer.match_rarity("SEC").as_deref(),
+            Some("secret_rare")
+        );
+        assert!(localizer.validate().is_empty());
+    }
+
+    #[test]
+    fn match_falls_back_to_fallback_locale() {
+        let mut localizer: Localizer = toml::from_str(FR_LOCALE_RAW).unwrap();
+        localizer.rarities.remove("treasure_rare");
+        assert_eq!(localizer.match_rarity("TR"), None);
+
+        localizer.fallback = Some(String::from("en"));
+        localizer.fallback_locale = Some(Box::new(toml::from_str(EN_LOCALE_RAW).unwrap()));
+        assert_eq!(
+            localizer.match_rarity("TR").as_deref(),
+            Some("treasure_rare")
+        );
+        assert_eq!(localizer.match_color("Rouge").as_deref(), Some("red"));
+        assert_eq!(localizer.match_color("Pink"), None);
+    }
+
+    #[test]
+    fn load_or_file_prefers_locale_file() {
+        let locale_file = std::env::temp_dir().join("vegapull-test-custom.toml");
+        fs::write(&locale_file, FR_LOCALE_RAW).unwrap();
+
+        let localizer = Localizer::load_or_file(LanguageCode::English, Some(&locale_file)).unwrap();
+        assert_eq!(localizer.hostname, "https://fr.onepiece-cardgame.com");
+        assert_eq!(
+            localizer.source.unwrap().file_name,
+            "vegapull-test-custom.toml"
+        );
+
+        fs::remove_file(&locale_file).unwrap();
+        assert!(Localizer::load_from_path(&locale_file).is_err());
+    }
+
+    #[test]
+    fn maps_key_checks_labels_aliases_and_patterns() {
+        let localizer: Localizer = toml::from_str(
+            r#"
+            hostname = "https://xx.onepiece-cardgame.com"
+            fallback = "en"
+            [colors]
+            red = "Rouge"
+            [attributes]
+            [categories]
+            [rarities]
+            [aliases.categories]
+            event = ["Évènement"]
+            [aliases.patterns.rarities]
+            special = ['^SP']
+            "#,
+        )
+        .unwrap();
+
+        assert!(localizer.maps_key("colors", "red"));
+        assert!(!localizer.maps_key("colors", "green"));
+        assert!(localizer.maps_key("categories", "event"));
+        assert!(localizer.maps_key("rarities", "special"));
+        assert!(!localizer.maps_key("keywords", "blocker"));
+    }
+
+    #[test]
+    fn label_maps_merge_the_fallback_locale() {
+        let mut localizer: Localizer = toml::from_str(
+            r#"
+            hostname = "https://xx.onepiece-cardgame.com"
+            [colors]
+            red = "Rouge"
+            [attributes]
+            [categories]
+            [rarities]
+            "#,
+        )
+        .unwrap();
+        localizer.fallback_locale = Some(Box::new(toml::from_str(EN_LOCALE_RAW).unwrap()));
+
+        let maps = localizer.label_maps();
+        assert_eq!(maps["colors"]["red"], "Rouge");
+        assert_eq!(maps["colors"]["green"], "Green");
+        assert_eq!(maps["categories"]["leader"], "LEADER");
+        assert_eq!(maps.len(), 8);
+    }
+
+    #[test]
+    fn load_chain_checks_format_version() {
+        let load = |raw: &str| Localizer::load_chain("xx.toml", raw, &mut Vec::new());
+
+        let unversioned = EN_LOCALE_RAW.replace("format_version = 1\n", "");
+        assert_eq!(load(&unversioned).unwrap().format_version, 1);
+        for raw in BUILTIN_LOCALES.iter().map(|(_, raw)| raw) {
+            assert!(raw.starts_with(&format!("format_version = {}\n", LOCALE_FORMAT_VERSION)));
+        }
+
+        let newer = format!("format_version = 99\n{}", unversioned);
+        let err = load(&newer).unwrap_err().to_string();
+        assert!(err.contains("upgrade vegapull"), "{}", err);
+        let older = format!("format_version = 0\n{}", unversioned);
+        assert!(load(&older).is_err());
+    }
+
+    #[test]
+    fn load_chain_checks_card_back() {
+        let load = |card_back: &str| {
+            let raw = format!("card_back = \"{}\"\n{}", card_back, EN_LOCALE_RAW);
+            Localizer::load_chain("xx.toml", &raw, &mut Vec::new())
+        };
+
+        assert!(load("../images/card_back.jpg").is_ok());
+        assert!(load("https://cdn.example.com/card_back.jpg").is_ok());
+        for card_back in ["", "..", "images/card_back.jpg", "裏面.jpg"] {
+            let err = load(card_back).unwrap_err().to_string();
+            assert!(err.contains("card_back"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn load_from_path_merges_local_overlay() {
+        let dir = std::env::temp_dir().join("vegapull-test-overlay");
+        fs::create_dir_all(&dir).unwrap();
+        let locale_path = dir.join("en.toml");
+        fs::write(&locale_path, EN_LOCALE_RAW).unwrap();
+        fs::write(
+            Localizer::overlay_path(&locale_path),
+            r#"
+            [rarities]
+            special = "SPECIAL"
+            [aliases.rarities]
+            treasure_rare = ["TRE"]
+            "#,
+        )
+        .unwrap();
+
+        let localizer = Localizer::load_from_path(&locale_path).unwrap();
+        assert_eq!(localizer.rarities["special"], "SPECIAL");
+        assert_eq!(
+            localizer.match_rarity("TRE").as_deref(),
+            Some("treasure_rare")
+        );
+        assert_eq!(
+            localizer.match_rarity("SEC").as_deref(),
+            Some("secret_rare")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn supported_languages_lists_languages_with_a_locale() {
+        let languages = Localizer::supported_languages();
+        let english = languages
+            .iter()
+            .find(|info| info.code == LanguageCode::English)
+            .unwrap();
+        assert_eq!(english.display_name, "English");
+        assert!(english.hostname.starts_with("https://"));
+        assert!(languages
+            .iter()
+            .all(|info| info.code != LanguageCode::Custom));
+    }
+
+    #[test]
+    fn is_null_token_matches_dashes_and_locale_tokens() {
+        let mut localizer: Localizer = toml::from_str(FR_LOCALE_RAW).unwrap();
+        for value in ["", " ", "-", "—", "－", "–"] {
+            assert!(localizer.is_null_token(value), "{:?}", value);
+        }
+        assert!(!localizer.is_null_token("Aucun"));
+        assert!(!localizer.is_null_token("0"));
+
+        localizer.null_tokens = vec![String::from("Aucun"), String::from("Sans valeur")];
+        assert!(localizer.is_null_token("AUCUN"));
+        assert!(localizer.is_null_token("Sansvaleur"));
+    }
+
+    #[test]
+    fn read_config_locale_falls_back_to_builtin_locales() {
+        let (file_name, locale_data) = Localizer::read_config_locale("th").unwrap();
+        assert_eq!(file_name, "th.toml");
+        assert!(locale_data.contains("hostname"));
+
+        // Fallback locales are read from the built-in ones as well
+        let locale_data = format!("fallback = \"en\"\n{}", locale_data);
+        let localizer = Localizer::load_chain(&file_name, &locale_data, &mut Vec::new()).unwrap();
+        assert!(localizer.fallback_locale.is_some());
+        assert!(Localizer::read_config_locale("vegapull-test-missing").is_err());
+    }
 }
@@ -1,19 +1,90 @@
-use anyhow::Result;
 use regex::Regex;
 use scraper::ElementRef;
 use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Borrow,
     fmt,
     hash::{Hash, Hasher},
+    ops::Deref,
+    str::FromStr,
 };
 
-pub type PackId = String;
+use crate::{
+    error::{Result, VegaError},
+    utils,
+};
+
+/// ID of a pack as used by the site's card list (e.g. `569101`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PackId(String);
+
+impl PackId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for PackId {
+    type Error = VegaError;
+
+    fn try_from(value: String) -> Result<Self> {
+        utils::validate_id("pack ID", &value)?;
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for PackId {
+    type Err = VegaError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::try_from(value.to_string())
+    }
+}
+
+impl From<PackId> for String {
+    fn from(id: PackId) -> Self {
+        id.0
+    }
+}
 
-#[derive(Debug, Deserialize, Serialize)]
+impl Deref for PackId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PackId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<&str> for PackId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for PackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Pack {
-    pub id: String,
+    pub id: PackId,
     pub raw_title: String,
     pub title_parts: TitleParts,
+    /// Product artwork in the store (e.g. `images/packs/569101_cover.png`), with --with-pack-images
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_image: Option<String>,
+    /// Logo of the set in the store, with --with-pack-images
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_image: Option<String>,
 }
 
 impl Eq for Pack {}
@@ -30,11 +101,11 @@ impl Hash for Pack {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TitleParts {
-    prefix: Option<String>,
-    title: String,
-    label: Option<String>,
+    pub prefix: Option<String>,
+    pub title: String,
+    pub label: Option<String>,
 }
 
 impl Pack {
@@ -42,10 +113,18 @@ impl Pack {
         let raw_title = Self::flatten_title(&element.inner_html())?;
         let title_parts = Self::process_title_parts(&raw_title)?;
 
+        let raw_id = element
+            .attr("value")
+            .ok_or_else(|| VegaError::missing("pack_id", "option[value]"))?;
+
         Ok(Self {
-            id: element.attr("value").unwrap().to_string(),
+            id: raw_id
+                .parse()
+                .map_err(|_| VegaError::unparsable("pack_id", raw_id))?,
             raw_title,
             title_parts,
+            cover_image: None,
+            logo_image: None,
         })
     }
 
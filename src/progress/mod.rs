@@ -0,0 +1,39 @@
+use crate::{card::Card, error::VegaError, pack::PackId, scraper::FetchedCards};
+
+#[cfg(feature = "cli")]
+mod terminal;
+
+#[cfg(feature = "cli")]
+pub use self::terminal::TerminalProgress;
+
+/// Receives the progress of a scraper as structured events, to show it in a terminal, a GUI or
+/// a bot. Events may come from several threads at once, every method does nothing by default
+pub trait ProgressReporter: Send + Sync {
+    /// Scraping the cards of `pack_id` started
+    fn on_pack_started(&self, _pack_id: &PackId) {}
+
+    /// All the cards of `pack_id` were scraped
+    fn on_pack_finished(&self, _pack_id: &PackId, _fetched: &FetchedCards) {}
+
+    /// `card` was parsed from the card list of its pack
+    fn on_card_parsed(&self, _card: &Card) {}
+
+    /// Downloading the images of `total` cards started
+    fn on_images_started(&self, _total: usize) {}
+
+    /// The images of `card` were downloaded and handled, `bytes` being their size
+    fn on_image_downloaded(&self, _card: &Card, _bytes: usize) {}
+
+    /// Downloading images ended, whether or not all of them succeeded
+    fn on_images_finished(&self) {}
+
+    /// Scraping a card or pack, or downloading the images of a card, failed. `subject` is the
+    /// card or pack ID
+    fn on_error(&self, _subject: &str, _error: &VegaError) {}
+}
+
+/// Reports nothing, the default of scrapers
+#[derive(Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {}
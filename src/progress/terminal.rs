@@ -0,0 +1,115 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressState, ProgressStyle};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use super::ProgressReporter;
+use crate::{card::Card, error::VegaError, pack::PackId, scraper::FetchedCards};
+
+/// Reports progress on stderr: a line per pack scraped and a progress bar for image downloads
+#[derive(Debug, Default)]
+pub struct TerminalProgress {
+    // Bars of this reporter and of the ones made with `for_language`, drawn together
+    bars: MultiProgress,
+    // Shown before the lines and bars of a reporter made with `for_language`
+    label: Option<String>,
+    image_bar: Mutex<Option<ProgressBar>>,
+    downloaded_bytes: Arc<AtomicU64>,
+}
+
+impl TerminalProgress {
+    /// Reporter for the scraper of another language pulled at the same time, its lines and bars
+    /// labelled with `language` and drawn along with the ones of this reporter
+    pub fn for_language(&self, language: impl fmt::Display) -> Self {
+        Self {
+            bars: self.bars.clone(),
+            label: Some(language.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Print `line` on stderr, above the bars being drawn
+    pub fn println(&self, line: &str) {
+        let line = match &self.label {
+            Some(label) => format!("[{}] {}", label, line),
+            None => line.to_string(),
+        };
+        // Lines are only printed above the bars when they are drawn
+        if self.bars.is_hidden() || self.bars.println(&line).is_err() {
+            eprintln!("{}", line);
+        }
+    }
+
+    fn inc_image_bar(&self) {
+        if let Some(bar) = self.image_bar.lock().unwrap().as_ref() {
+            bar.inc(1);
+        }
+    }
+}
+
+impl ProgressReporter for TerminalProgress {
+    fn on_pack_finished(&self, pack_id: &PackId, _fetched: &FetchedCards) {
+        self.println(&format!("Fetched cards for pack {pack_id}"));
+    }
+
+    fn on_images_started(&self, total: usize) {
+        self.downloaded_bytes.store(0, Ordering::Relaxed);
+        let bar = self
+            .bars
+            .add(image_progress_bar(total, self.downloaded_bytes.clone()));
+        if let Some(label) = &self.label {
+            bar.set_prefix(format!("[{}] ", label));
+        }
+        *self.image_bar.lock().unwrap() = Some(bar);
+    }
+
+    fn on_image_downloaded(&self, _card: &Card, bytes: usize) {
+        self.downloaded_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.inc_image_bar();
+    }
+
+    fn on_images_finished(&self) {
+        if let Some(bar) = self.image_bar.lock().unwrap().take() {
+            bar.finish_and_clear();
+        }
+    }
+
+    fn on_error(&self, _subject: &str, _error: &VegaError) {
+        // Errors are logged where they happen, they only move the bar of an image download on
+        self.inc_image_bar();
+    }
+}
+
+/// Progress bar of an image download, showing cards downloaded out of `total` and throughput
+fn image_progress_bar(total: usize, downloaded_bytes: Arc<AtomicU64>) -> ProgressBar {
+    let style = ProgressStyle::with_template(
+        "{spinner} {prefix}images [{bar:30}] {pos}/{len} cards, {mb} MB ({mb_per_sec} MB/s) {elapsed}",
+    )
+    .expect("valid progress template")
+    .progress_chars("=> ");
+
+    let bytes = downloaded_bytes.clone();
+    let style = style
+        .with_key("mb", move |_: &ProgressState, w: &mut dyn fmt::Write| {
+            let mb = bytes.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+            let _ = write!(w, "{:.1}", mb);
+        })
+        .with_key(
+            "mb_per_sec",
+            move |state: &ProgressState, w: &mut dyn fmt::Write| {
+                let mb = downloaded_bytes.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+                let secs = state.elapsed().as_secs_f64().max(0.001);
+                let _ = write!(w, "{:.2}", mb / secs);
+            },
+        );
+
+    let progress = ProgressBar::new(total as u64).with_style(style);
+    progress.enable_steady_tick(Duration::from_millis(200));
+    progress
+}
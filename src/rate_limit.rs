@@ -0,0 +1,98 @@
+use log::trace;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Spaces out requests to each host so that no host gets more than a set number of requests
+/// per second, whatever the number of threads sending them
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    // Earliest time the next request to a host may be sent, by host
+    next_slots: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    /// Limiter allowing `requests_per_second` requests per host, unlimited if `None` or not positive
+    pub fn new(requests_per_second: Option<f64>) -> Self {
+        let interval = requests_per_second
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(1.0 / rate));
+
+        Self {
+            interval,
+            next_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Block until a request to the host of `url` may be sent
+    pub fn wait(&self, url: &str) {
+        if let Some(delay) = self.reserve(url) {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Wait without blocking the thread until a request to the host of `url` may be sent
+    pub async fn wait_async(&self, url: &str) {
+        if let Some(delay) = self.reserve(url) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Take the next slot for a request to the host of `url`, returning how long to wait for it
+    fn reserve(&self, url: &str) -> Option<Duration> {
+        let interval = self.interval?;
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+
+        let now = Instant::now();
+        let slot = {
+            let mut next_slots = self.next_slots.lock().unwrap();
+            let slot = match next_slots.get(&host) {
+                Some(next) if *next > now => *next,
+                _ => now,
+            };
+            next_slots.insert(host.clone(), slot + interval);
+            slot
+        };
+
+        if slot > now {
+            trace!("waiting {:?} before requesting {}", slot - now, host);
+            return Some(slot - now);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_spaces_out_requests_per_host() {
+        let limiter = RateLimiter::new(Some(20.0));
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.wait("https://en.onepiece-cardgame.com/cardlist/");
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let start = Instant::now();
+        limiter.wait("https://cdn.example.com/images/OP01-001.png");
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        let unlimited = RateLimiter::new(None);
+        let start = Instant::now();
+        for _ in 0..100 {
+            unlimited.wait("https://en.onepiece-cardgame.com/cardlist/");
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
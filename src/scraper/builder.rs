@@ -0,0 +1,264 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use super::{ImageQuality, OpTcgScraper, DEFAULT_IMAGE_JOBS};
+use crate::{
+    error::{Result, VegaError},
+    localizer::Localizer,
+    progress::{NoProgress, ProgressReporter},
+    rate_limit::RateLimiter,
+    user_agent::UserAgentPool,
+};
+
+/// Timeout of requests when none is set
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Settings of the HTTP clients a scraper builds for itself
+#[derive(Debug, Clone)]
+pub(super) struct ClientOptions {
+    timeout: Duration,
+    proxy: Option<reqwest::Proxy>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            proxy: None,
+        }
+    }
+}
+
+impl ClientOptions {
+    pub(super) fn blocking_client(&self) -> Result<reqwest::blocking::Client> {
+        let mut builder = reqwest::blocking::ClientBuilder::new().timeout(self.timeout);
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        Ok(builder.build()?)
+    }
+
+    pub(super) fn async_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::ClientBuilder::new().timeout(self.timeout);
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Build a scraper for the site of a localizer, every other setting having a default
+#[derive(Default)]
+pub struct OpTcgScraperBuilder {
+    localizer: Option<Localizer>,
+    user_agents: Option<Arc<UserAgentPool>>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    page_rate: Option<f64>,
+    image_rate: Option<f64>,
+    page_limiter: Option<Arc<RateLimiter>>,
+    image_limiter: Option<Arc<RateLimiter>>,
+    client: Option<reqwest::blocking::Client>,
+    image_client: Option<reqwest::blocking::Client>,
+    async_client: Option<reqwest::Client>,
+    image_jobs: Option<usize>,
+    image_quality: ImageQuality,
+    partial_dir: Option<PathBuf>,
+    progress: Option<Arc<dyn ProgressReporter>>,
+}
+
+impl OpTcgScraper {
+    pub fn builder() -> OpTcgScraperBuilder {
+        OpTcgScraperBuilder::default()
+    }
+}
+
+impl OpTcgScraperBuilder {
+    /// Labels and selectors of the site to scrape, required
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = Some(localizer);
+        self
+    }
+
+    /// Send requests with the user agents of `user_agents`, which can be shared between scrapers
+    pub fn user_agents(mut self, user_agents: impl Into<Arc<UserAgentPool>>) -> Self {
+        self.user_agents = Some(user_agents.into());
+        self
+    }
+
+    /// Give up on requests taking longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send all requests through the proxy at `proxy`, directly if `None`
+    pub fn proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Send at most `page_rate` page requests and `image_rate` image requests per second to each
+    /// host, each limit applying independently of the other
+    pub fn rate_limit(mut self, page_rate: Option<f64>, image_rate: Option<f64>) -> Self {
+        self.page_rate = page_rate;
+        self.image_rate = image_rate;
+        self
+    }
+
+    /// Space out requests with limiters shared with other scrapers, so that their requests
+    /// together stay within the limits instead of each scraper's own (replaces `rate_limit`)
+    pub fn rate_limiters(
+        mut self,
+        page_limiter: Arc<RateLimiter>,
+        image_limiter: Arc<RateLimiter>,
+    ) -> Self {
+        self.page_limiter = Some(page_limiter);
+        self.image_limiter = Some(image_limiter);
+        self
+    }
+
+    /// Share an existing HTTP client (and its connection pool) with other scrapers. Timeout and
+    /// proxy are those of `client`
+    pub fn client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Download images with `image_client` instead of the client used for pages
+    pub fn image_client(mut self, image_client: reqwest::blocking::Client) -> Self {
+        self.image_client = Some(image_client);
+        self
+    }
+
+    /// Send the requests of the `_async` methods with `async_client`
+    pub fn async_client(mut self, async_client: reqwest::Client) -> Self {
+        self.async_client = Some(async_client);
+        self
+    }
+
+    /// Download images on a dedicated pool of `image_jobs` threads
+    pub fn image_jobs(mut self, image_jobs: usize) -> Self {
+        self.image_jobs = Some(image_jobs);
+        self
+    }
+
+    pub fn image_quality(mut self, image_quality: ImageQuality) -> Self {
+        self.image_quality = image_quality;
+        self
+    }
+
+    /// Keep interrupted image downloads in `partial_dir` to resume them, in memory only if `None`
+    /// (the default)
+    pub fn partial_dir(mut self, partial_dir: Option<PathBuf>) -> Self {
+        self.partial_dir = partial_dir;
+        self
+    }
+
+    /// Report progress to `progress` instead of reporting nothing
+    pub fn progress(mut self, progress: impl ProgressReporter + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Fails if no localizer was given or the proxy URL is invalid
+    pub fn build(self) -> Result<OpTcgScraper> {
+        let localizer = self
+            .localizer
+            .ok_or_else(|| VegaError::config("a localizer is required to build a scraper"))?;
+        let proxy = self
+            .proxy
+            .map(|proxy| {
+                reqwest::Proxy::all(&proxy).map_err(|e| {
+                    VegaError::config(format!("invalid proxy: {}", proxy)).with_cause(e)
+                })
+            })
+            .transpose()?;
+        let client_options = ClientOptions {
+            timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            proxy,
+        };
+
+        // Blocking clients given for pages are also used for images, unless given their own
+        let image_client = self.image_client.or_else(|| self.client.clone());
+        let async_client = match self.async_client {
+            Some(async_client) => async_client,
+            None => client_options.async_client()?,
+        };
+
+        Ok(OpTcgScraper {
+            base_url: localizer.hostname.clone(),
+            localizer,
+            client: self.client.map(OnceLock::from).unwrap_or_default(),
+            image_client: image_client.map(OnceLock::from).unwrap_or_default(),
+            async_client,
+            client_options,
+            page_limiter: self
+                .page_limiter
+                .unwrap_or_else(|| Arc::new(RateLimiter::new(self.page_rate))),
+            image_limiter: self
+                .image_limiter
+                .unwrap_or_else(|| Arc::new(RateLimiter::new(self.image_rate))),
+            user_agents: self.user_agents.unwrap_or_default(),
+            image_jobs: self.image_jobs.unwrap_or(DEFAULT_IMAGE_JOBS).max(1),
+            image_quality: self.image_quality,
+            partial_dir: self.partial_dir,
+            progress: self.progress.unwrap_or_else(|| Arc::new(NoProgress)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_requires_localizer_and_valid_proxy() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+
+        assert!(OpTcgScraper::builder().build().is_err());
+        assert!(OpTcgScraper::builder()
+            .localizer(localizer.clone())
+            .proxy(Some(String::from("not a proxy url")))
+            .build()
+            .is_err());
+
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .user_agents(UserAgentPool::default())
+            .timeout(Duration::from_secs(5))
+            .proxy(Some(String::from("http://127.0.0.1:3128")))
+            .rate_limit(Some(2.0), None)
+            .image_jobs(0)
+            .build()
+            .unwrap();
+
+        assert_eq!(scraper.client_options.timeout, Duration::from_secs(5));
+        assert!(scraper.client_options.proxy.is_some());
+        assert_eq!(scraper.image_jobs, 1);
+        assert_eq!(scraper.partial_dir, None);
+    }
+
+    #[test]
+    fn scrapers_share_rate_limiters_given() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let page_limiter = Arc::new(RateLimiter::new(Some(2.0)));
+        let image_limiter = Arc::new(RateLimiter::new(Some(4.0)));
+
+        let build = || {
+            OpTcgScraper::builder()
+                .localizer(localizer.clone())
+                .rate_limiters(page_limiter.clone(), image_limiter.clone())
+                .build()
+                .unwrap()
+        };
+        let (first, second) = (build(), build());
+
+        assert!(Arc::ptr_eq(&first.page_limiter, &second.page_limiter));
+        assert!(Arc::ptr_eq(&first.image_limiter, &second.image_limiter));
+        assert!(Arc::ptr_eq(&first.page_limiter, &page_limiter));
+    }
+}
@@ -0,0 +1,1554 @@
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use rayon::prelude::*;
+use reqwest::{
+    header::{
+        HeaderMap, CONTENT_RANGE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED,
+        RANGE, USER_AGENT,
+    },
+    StatusCode,
+};
+use scraper::Html;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    io::{self, Cursor, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub mod builder;
+
+use self::builder::ClientOptions;
+pub use self::builder::OpTcgScraperBuilder;
+
+use crate::{
+    card::{scraper::RawLabels, Card, CardId, CardScraper},
+    error::{Context, Result, VegaError},
+    localizer::Localizer,
+    pack::{Pack, PackId},
+    progress::ProgressReporter,
+    rate_limit::RateLimiter,
+    storage::ImageValidators,
+    user_agent::UserAgentPool,
+    utils,
+};
+
+/// Cards of a pack along with how fetching them went
+#[derive(Debug, Default)]
+pub struct FetchedCards {
+    pub cards: Vec<Card>,
+    pub duration: Duration,
+    /// Cards that failed to scrape and were skipped
+    pub errors: usize,
+    /// Fields left empty on cards that were still scraped
+    pub warnings: usize,
+}
+
+/// Cards of a pack being scraped, see `OpTcgScraper::stream_cards`
+pub struct CardStream<'a> {
+    scraper: &'a OpTcgScraper,
+    pack_id: PackId,
+    source_url: String,
+    document: Html,
+    // IDs of the listed cards not scraped yet
+    card_ids: std::vec::IntoIter<String>,
+    scraped_at: DateTime<Utc>,
+    warnings: usize,
+}
+
+impl CardStream<'_> {
+    /// Number of warnings about fields scraped partially so far
+    pub fn warnings(&self) -> usize {
+        self.warnings
+    }
+}
+
+impl Iterator for CardStream<'_> {
+    type Item = Result<Card>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let card_id = self.card_ids.next()?;
+        let card_id = card_id.as_str();
+
+        let scraped = CardScraper::create_card(
+            &self.scraper.localizer,
+            &self.document,
+            card_id,
+            &self.pack_id,
+        )
+        .and_then(|(mut card, card_warnings)| {
+            debug!("computing img_full_url for card: {}", card);
+            card.img_full_url = Some(self.scraper.get_img_full_url(&card.img_url)?);
+            card.img_back_full_url = card
+                .img_back_url
+                .as_ref()
+                .map(|img_back_url| self.scraper.get_img_full_url(img_back_url))
+                .transpose()?;
+            Ok((card, card_warnings))
+        })
+        .map_err(|e| e.for_card(card_id));
+
+        match scraped {
+            Ok((mut card, card_warnings)) => {
+                for warning in &card_warnings {
+                    warn!("card `{}` scraped partially: {}", card_id, warning);
+                }
+                self.warnings += card_warnings.len();
+                card.source_url = Some(self.source_url.clone());
+                card.scraped_at = Some(self.scraped_at);
+                self.scraper.progress.on_card_parsed(&card);
+                Some(Ok(card))
+            }
+            Err(e) => {
+                match &e {
+                    VegaError::Parse { reason, .. } if !reason.is_site_data_issue() => error!(
+                        "failed to scrape data about card `{}`: {}. The site layout changed and vegapull needs an update, the card will be skipped...",
+                        &card_id, e
+                    ),
+                    _ => error!(
+                        "failed to scrape data about card `{}`: {}. The card will be skipped...",
+                        &card_id, e
+                    ),
+                }
+                self.scraper.progress.on_error(card_id, &e);
+                Some(Err(e))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.card_ids.size_hint()
+    }
+}
+
+/// Downloaded images of a card, the back one only for double-faced cards
+#[derive(Debug, Default)]
+pub struct CardImages {
+    pub front: Vec<u8>,
+    pub back: Option<Vec<u8>>,
+    /// Variants the faces were downloaded from, with `--image-quality best`
+    pub front_variant: Option<String>,
+    pub back_variant: Option<String>,
+    /// Validators the faces were served with, to check them for changes later
+    pub front_validators: ImageValidators,
+    pub back_validators: ImageValidators,
+}
+
+impl CardImages {
+    /// Number of image files
+    pub fn count(&self) -> usize {
+        1 + usize::from(self.back.is_some())
+    }
+
+    /// Size of the image files
+    pub fn byte_count(&self) -> usize {
+        self.front.len() + self.back.as_ref().map_or(0, Vec::len)
+    }
+}
+
+/// Outcome of an image download, the images themselves being handed over as they arrive
+#[derive(Debug, Default)]
+pub struct FetchedImages {
+    /// Number of images downloaded, by card
+    pub images: HashMap<CardId, usize>,
+    pub failed: BTreeMap<CardId, String>,
+    /// Cards downloaded again because the site reported their stored images as changed
+    pub changed: BTreeSet<CardId>,
+    /// Downloaded cards whose images are too small to be card art, with their dimensions
+    pub small: BTreeMap<CardId, String>,
+}
+
+/// What the site says about the stored images of a card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageCheck {
+    Unchanged,
+    Changed,
+    /// The images were stored without validators to ask with
+    Unknown,
+}
+
+/// Artwork of a pack as downloaded from the site
+#[derive(Debug)]
+pub struct PackImage {
+    pub url: String,
+    pub data: Vec<u8>,
+}
+
+/// Product artwork and logo shown on the card list page of a pack, when it has them
+#[derive(Debug, Default)]
+pub struct PackImages {
+    pub cover: Option<PackImage>,
+    pub logo: Option<PackImage>,
+}
+
+/// Which of the images available for a card to download
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ImageQuality {
+    /// The image shown on the cardlist page
+    #[default]
+    Standard,
+    /// The largest of the image shown, its path without query string and the locale's
+    /// `image_variants`
+    Best,
+}
+
+/// Variant name of the image shown on the cardlist page
+pub const SERVED_IMAGE_VARIANT: &str = "served";
+
+/// Where the site serves the back of every card, relative like the images of cards
+pub const CARD_BACK_PATH: &str = "../images/cardlist/card/card_back.png";
+
+/// Images downloaded at the same time unless `--image-jobs` says otherwise
+pub const DEFAULT_IMAGE_JOBS: usize = 8;
+
+/// Name of the `.part` file of a download, unique per URL (e.g. `1f2e3d4c5b6a7988_OP01-001.png.part`)
+fn part_filename(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let filename = path.rsplit('/').next().unwrap_or_default();
+    let hash = Sha256::digest(url.as_bytes());
+    let hash: String = hash[..8].iter().map(|b| format!("{:02x}", b)).collect();
+
+    format!("{}_{}.part", hash, filename)
+}
+
+/// Validators of the image a `.part` file is the start of, kept next to it
+fn part_validators_path(part_path: &Path) -> PathBuf {
+    part_path.with_extension("part.json")
+}
+
+/// Validators the `.part` file at `part_path` was downloaded with, if any
+fn read_part_validators(part_path: &Path) -> Option<ImageValidators> {
+    let data = fs::read(part_validators_path(part_path)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// `If-Range` value to resume a download of an image served with `validators`, `None` if it
+/// cannot be told whether the image changed since (weak ETags cannot be used either)
+fn if_range(validators: &ImageValidators) -> Option<&str> {
+    validators
+        .etag
+        .as_deref()
+        .filter(|etag| !etag.starts_with("W/"))
+        .or(validators.last_modified.as_deref())
+}
+
+/// First byte of the body of a `206 Partial Content` response (`Content-Range: bytes 10-99/100`)
+fn content_range_start(headers: &HeaderMap) -> Option<usize> {
+    let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (start, _) = value.strip_prefix("bytes ")?.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// Append the body of `response` to `img_data`, and to the `.part` file at `part_path` as it
+/// arrives so that an interrupted download can be resumed
+fn read_body(
+    mut response: reqwest::blocking::Response,
+    img_data: &mut Vec<u8>,
+    part_path: Option<&Path>,
+    validators: &ImageValidators,
+) -> Result<()> {
+    let mut part_file = match part_path {
+        Some(part_path) => {
+            if let Some(parent) = part_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(
+                part_validators_path(part_path),
+                serde_json::to_vec(validators)?,
+            )?;
+            let mut part_file = fs::File::create(part_path)?;
+            part_file.write_all(img_data)?;
+            Some(part_file)
+        }
+        None => None,
+    };
+
+    let mut chunk = [0; 64 * 1024];
+    loop {
+        let read = response.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(());
+        }
+        img_data.extend_from_slice(&chunk[..read]);
+        if let Some(part_file) = &mut part_file {
+            part_file.write_all(&chunk[..read])?;
+        }
+    }
+}
+
+fn remove_part_file(part_path: Option<&Path>) {
+    if let Some(part_path) = part_path {
+        for path in [part_path.to_path_buf(), part_validators_path(part_path)] {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    warn!("failed to remove {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+}
+
+/// Width and height of an image, read from its header
+fn image_dimensions(img_data: &[u8]) -> Result<(u32, u32)> {
+    Ok(image::ImageReader::new(Cursor::new(img_data))
+        .with_guessed_format()?
+        .into_dimensions()?)
+}
+
+/// Number of pixels of an image, read from its header
+fn image_area(img_data: &[u8]) -> Result<u64> {
+    let (width, height) = image_dimensions(img_data)?;
+    Ok(u64::from(width) * u64::from(height))
+}
+
+/// Smallest card image accepted as card art, the site's "no image" placeholders being smaller
+/// (its card images are about 600x838)
+const MIN_CARD_IMAGE_SIZE: (u32, u32) = (200, 280);
+
+/// Why the images of a card look like placeholders rather than card art, if they do
+fn check_card_image_size(images: &CardImages) -> Option<String> {
+    let faces = std::iter::once(("front", &images.front))
+        .chain(images.back.as_ref().map(|back| ("back", back)));
+    let small: Vec<String> = faces
+        .filter_map(|(face, img_data)| {
+            let (width, height) = image_dimensions(img_data).ok()?;
+            (width < MIN_CARD_IMAGE_SIZE.0 || height < MIN_CARD_IMAGE_SIZE.1)
+                .then(|| format!("{} is {}x{}", face, width, height))
+        })
+        .collect();
+
+    (!small.is_empty()).then(|| small.join(", "))
+}
+
+/// Smallest image accepted, anything below is an error page or an empty placeholder
+const MIN_IMAGE_BYTES: usize = 512;
+
+/// Why `img_data` cannot be an image of a card, if it cannot
+fn check_image_bytes(img_data: &[u8]) -> Option<String> {
+    if image::guess_format(img_data).is_err() {
+        let start = String::from_utf8_lossy(&img_data[..img_data.len().min(16)]).to_string();
+        return Some(format!("not an image (starts with {:?})", start));
+    }
+    if img_data.len() < MIN_IMAGE_BYTES {
+        return Some(format!("only {} bytes", img_data.len()));
+    }
+
+    None
+}
+
+/// Validators of an image response, as sent back in conditional requests
+fn response_validators(headers: &HeaderMap) -> ImageValidators {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+
+    ImageValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    }
+}
+
+pub struct OpTcgScraper {
+    base_url: String,
+    localizer: Localizer,
+    // Blocking clients are built on first use: they run their own runtime, which cannot be
+    // created or dropped from within an async runtime using the `_async` methods
+    client: OnceLock<reqwest::blocking::Client>,
+    // Own connection pool for images, so that image downloads never wait on page requests
+    image_client: OnceLock<reqwest::blocking::Client>,
+    // Client of the `_async` methods, which must not block the threads of an async runtime
+    async_client: reqwest::Client,
+    // Timeout and proxy of the blocking clients built on first use
+    client_options: ClientOptions,
+    page_limiter: Arc<RateLimiter>,
+    image_limiter: Arc<RateLimiter>,
+    user_agents: Arc<UserAgentPool>,
+    image_jobs: usize,
+    image_quality: ImageQuality,
+    partial_dir: Option<PathBuf>,
+    progress: Arc<dyn ProgressReporter>,
+}
+
+impl OpTcgScraper {
+    /// Blocking client with the default timeout, to share between scrapers
+    pub fn build_client() -> Result<reqwest::blocking::Client> {
+        ClientOptions::default().blocking_client()
+    }
+
+    /// Async client with the default timeout, to share between scrapers
+    pub fn build_async_client() -> Result<reqwest::Client> {
+        ClientOptions::default().async_client()
+    }
+
+    /// Blocking client of `cell`, built with `client_options` if not yet
+    fn blocking_client<'a>(
+        &self,
+        cell: &'a OnceLock<reqwest::blocking::Client>,
+    ) -> Result<&'a reqwest::blocking::Client> {
+        if let Some(client) = cell.get() {
+            return Ok(client);
+        }
+        let client = self.client_options.blocking_client()?;
+        Ok(cell.get_or_init(|| client))
+    }
+
+    fn get(&self, url: &str) -> Result<reqwest::blocking::RequestBuilder> {
+        let client = self.blocking_client(&self.client)?;
+        self.page_limiter.wait(url);
+        Ok(client.get(url).header(USER_AGENT, self.user_agents.next()))
+    }
+
+    async fn get_async(&self, url: &str) -> reqwest::RequestBuilder {
+        self.page_limiter.wait_async(url).await;
+        self.async_client
+            .get(url)
+            .header(USER_AGENT, self.user_agents.next())
+    }
+
+    fn get_image(&self, url: &str) -> Result<reqwest::blocking::RequestBuilder> {
+        let client = self.blocking_client(&self.image_client)?;
+        self.image_limiter.wait(url);
+        Ok(client.get(url).header(USER_AGENT, self.user_agents.next()))
+    }
+
+    pub fn localizer(&self) -> &Localizer {
+        &self.localizer
+    }
+
+    fn cardlist_endpoint(&self) -> String {
+        format!("{}/{}", self.base_url, "cardlist")
+    }
+
+    /// Absolute URL of an image the site links as `../<path>`, absolute URLs being kept as they are
+    fn get_img_full_url(&self, img_url: &str) -> Result<String> {
+        let full_url = if img_url.starts_with("http") {
+            img_url.to_string()
+        } else if let Some(short_img_url) = img_url.strip_prefix("../") {
+            format!("{}/{}", self.base_url, short_img_url)
+        } else {
+            return Err(VegaError::unparsable("img_url", img_url));
+        };
+        debug!("full url: {}", full_url);
+
+        Ok(full_url)
+    }
+
+    pub fn fetch_packs(&self) -> Result<HashMap<PackId, Pack>> {
+        let url = self.cardlist_endpoint();
+        debug!("GET `{}`", url);
+
+        let start = Instant::now();
+
+        let response = self.get(&url)?.send()?.text()?;
+
+        let duration = start.elapsed();
+        debug!("fetching packs took: {:?}", duration);
+
+        self.parse_packs(&response)
+    }
+
+    /// Same as `fetch_packs`, for async runtimes
+    pub async fn fetch_packs_async(&self) -> Result<HashMap<PackId, Pack>> {
+        let url = self.cardlist_endpoint();
+        debug!("GET `{}`", url);
+
+        let response = self.get_async(&url).await.send().await?.text().await?;
+        self.parse_packs(&response)
+    }
+
+    fn parse_packs(&self, response: &str) -> Result<HashMap<PackId, Pack>> {
+        debug!("parsing HTML document");
+        let start = Instant::now();
+
+        let document = scraper::Html::parse_document(response);
+
+        let sel = self
+            .localizer
+            .selector("packs", "div.seriesCol>select#series>option");
+        debug!("fetching series (packs) ({})...", sel);
+
+        let series_selector = utils::parse_selector(sel)?;
+
+        let mut packs = HashMap::new();
+        for element in document.select(&series_selector) {
+            let pack = Pack::new(element)?;
+            if !pack.id.is_empty() {
+                packs.insert(pack.id.clone(), pack);
+            }
+        }
+
+        let duration = start.elapsed();
+        debug!("parsing packs took: {:?}", duration);
+
+        Ok(packs)
+    }
+
+    /// Download the image of the back of cards, from the locale's `card_back` path if it has one
+    pub fn fetch_card_back(&self) -> Result<PackImage> {
+        let url = self
+            .localizer
+            .card_back
+            .clone()
+            .unwrap_or_else(|| CARD_BACK_PATH.to_string());
+        let data = self
+            .fetch_image(&url)
+            .context("failed to download the card back")?;
+
+        Ok(PackImage { url, data })
+    }
+
+    /// Download the product artwork and logo of a pack from its card list page
+    pub fn fetch_pack_images(&self, pack_id: &PackId) -> Result<PackImages> {
+        let (_, document) = self.fetch_cardlist_page(pack_id)?;
+
+        let fetch = |field: &str, default_selector: &str| -> Result<Option<PackImage>> {
+            let sel = self.localizer.selector(field, default_selector);
+            let selector = utils::parse_selector(sel)?;
+            let Some(img_url) = document
+                .select(&selector)
+                .next()
+                .and_then(|img| img.attr("data-src").or_else(|| img.attr("src")))
+            else {
+                debug!("no {} for pack {} ({})", field, pack_id, sel);
+                return Ok(None);
+            };
+
+            let data = self.fetch_image(img_url)?;
+            Ok(Some(PackImage {
+                url: img_url.to_string(),
+                data,
+            }))
+        };
+
+        Ok(PackImages {
+            cover: fetch("pack_cover", "div.seriesCol img.packImg")?,
+            logo: fetch("pack_logo", "div.seriesCol img.logoImg")?,
+        })
+    }
+
+    pub fn fetch_all_cards(
+        &self,
+        pack_ids: &HashSet<PackId>,
+    ) -> Result<HashMap<PackId, FetchedCards>> {
+        pack_ids
+            .par_iter()
+            .map(|pid| {
+                info!("fetching all cards for pack {} via rayon", pid);
+                let pack_id = pid.clone();
+                self.fetch_cards(&pack_id)
+                    .inspect_err(|e| self.progress.on_error(pack_id.as_str(), e))
+                    .map(|cards| (pack_id, cards))
+            })
+            .collect()
+    }
+
+    fn parse_html(response: &str) -> Html {
+        let start = Instant::now();
+        let document = scraper::Html::parse_document(response);
+
+        let duration = start.elapsed();
+        info!("parsing HTML took: {:?}", duration);
+
+        document
+    }
+
+    /// Card list page of a pack, along with its final URL
+    fn fetch_cardlist_page(&self, pack_id: &PackId) -> Result<(String, Html)> {
+        let url = self.cardlist_endpoint();
+        info!("GET `{}`", url);
+
+        let start = Instant::now();
+
+        let response = self
+            .get(&url)?
+            .query(&[("series", pack_id.as_str())])
+            .send()?;
+        let source_url = response.url().to_string();
+        let response = response.text()?;
+
+        let duration = start.elapsed();
+        info!("fetching HTML document took: {:?}", duration);
+
+        Ok((source_url, Self::parse_html(&response)))
+    }
+
+    /// IDs of the cards listed on a card list page
+    fn select_card_ids(&self, document: &Html) -> Result<Vec<String>> {
+        let sel = self.localizer.selector("card_ids", "div.resultCol>a");
+        debug!("fetching card IDs ({})...", sel);
+
+        let card_ids_selector = utils::parse_selector(sel)?;
+        document
+            .select(&card_ids_selector)
+            .map(|element| {
+                let card_id = element
+                    .attr("data-src")
+                    .ok_or_else(|| VegaError::missing("id", format!("{}[data-src]", sel)))?;
+                Ok(card_id[1..].to_string())
+            })
+            .collect()
+    }
+
+    pub fn fetch_cards(&self, pack_id: &PackId) -> Result<FetchedCards> {
+        let pack_start = Instant::now();
+
+        let (source_url, document) = self.fetch_cardlist_page(pack_id)?;
+        self.parse_cards(pack_id, &source_url, document, pack_start)
+    }
+
+    /// Same as `fetch_cards`, for async runtimes
+    pub async fn fetch_cards_async(&self, pack_id: &PackId) -> Result<FetchedCards> {
+        let pack_start = Instant::now();
+        let url = self.cardlist_endpoint();
+        info!("GET `{}`", url);
+
+        let response = self
+            .get_async(&url)
+            .await
+            .query(&[("series", pack_id.as_str())])
+            .send()
+            .await?;
+        let source_url = response.url().to_string();
+        let response = response.text().await?;
+
+        // Parsed documents cannot be held across an await, the future would not be `Send`
+        let document = Self::parse_html(&response);
+        self.parse_cards(pack_id, &source_url, document, pack_start)
+    }
+
+    /// Cards of the card list page of a pack, `pack_start` being when fetching it started
+    fn parse_cards(
+        &self,
+        pack_id: &PackId,
+        source_url: &str,
+        document: Html,
+        pack_start: Instant,
+    ) -> Result<FetchedCards> {
+        info!("fetching cards for pack `{}`...", pack_id);
+
+        let start = Instant::now();
+
+        let mut stream = self.card_stream(pack_id, source_url, document)?;
+        let mut cards = Vec::new();
+        let mut errors = 0;
+        for card in stream.by_ref() {
+            match card {
+                Ok(card) => cards.push(card),
+                Err(_) => errors += 1,
+            }
+        }
+
+        let duration = start.elapsed();
+        info!("processed cards for pack {} in {:?}", pack_id, duration);
+
+        let fetched = FetchedCards {
+            cards,
+            duration: pack_start.elapsed(),
+            errors,
+            warnings: stream.warnings(),
+        };
+        self.progress.on_pack_finished(pack_id, &fetched);
+        Ok(fetched)
+    }
+
+    /// Cards of a pack, each yielded as soon as its `<dl>` block is parsed instead of once the
+    /// whole pack is. Cards that fail to be scraped are logged and yielded as errors, the stream
+    /// going on with the next card
+    pub fn stream_cards(&self, pack_id: &PackId) -> Result<CardStream<'_>> {
+        let (source_url, document) = self.fetch_cardlist_page(pack_id)?;
+        self.card_stream(pack_id, &source_url, document)
+    }
+
+    fn card_stream(
+        &self,
+        pack_id: &PackId,
+        source_url: &str,
+        document: Html,
+    ) -> Result<CardStream<'_>> {
+        self.progress.on_pack_started(pack_id);
+        Ok(CardStream {
+            scraper: self,
+            pack_id: pack_id.clone(),
+            source_url: source_url.to_string(),
+            card_ids: self.select_card_ids(&document)?.into_iter(),
+            document,
+            scraped_at: Utc::now(),
+            warnings: 0,
+        })
+    }
+
+    /// Labels of the cards of a pack as shown on the site, without matching them to the locale
+    pub fn fetch_raw_labels(&self, pack_id: &PackId) -> Result<Vec<RawLabels>> {
+        let (_, document) = self.fetch_cardlist_page(pack_id)?;
+        self.select_card_ids(&document)?
+            .iter()
+            .map(|card_id| CardScraper::fetch_raw_labels(&self.localizer, &document, card_id))
+            .collect()
+    }
+
+    /// Download the images of `cards`, handing the images of each card to `on_images` as soon as
+    /// they are downloaded so that a pull never holds more than a card per job in memory. Cards
+    /// whose images fail to download or to be handled are reported instead of failing the whole
+    /// download
+    pub fn fetch_all_card_images<F>(&self, cards: &[&Card], on_images: F) -> Result<FetchedImages>
+    where
+        F: Fn(&Card, CardImages) -> Result<()> + Sync,
+    {
+        let pool = self.image_pool()?;
+
+        self.progress.on_images_started(cards.len());
+        let results: Vec<_> = pool.install(|| {
+            cards
+                .par_iter()
+                .map(|card| {
+                    let handled = self.fetch_card_image(card).and_then(|images| {
+                        let bytes = images.byte_count();
+                        let count = images.count();
+                        let small = check_card_image_size(&images);
+                        on_images(card, images)?;
+                        Ok((count, bytes, small))
+                    });
+                    match &handled {
+                        Ok((_, bytes, _)) => self.progress.on_image_downloaded(card, *bytes),
+                        Err(e) => self.progress.on_error(&card.id, e),
+                    }
+                    (card.id.clone(), handled)
+                })
+                .collect()
+        });
+        self.progress.on_images_finished();
+
+        let mut fetched = FetchedImages::default();
+        for (card_id, handled) in results {
+            match handled {
+                Ok((count, _, small)) => {
+                    if let Some(size) = small {
+                        warn!("images of {} look like placeholders: {}", card_id, size);
+                        fetched.small.insert(card_id.clone(), size);
+                    }
+                    fetched.images.insert(card_id, count);
+                }
+                Err(e) => {
+                    error!("failed to download images of {}: {:#}", card_id, e);
+                    fetched.failed.insert(card_id, format!("{:#}", e));
+                }
+            }
+        }
+
+        Ok(fetched)
+    }
+
+    /// Own pool so that image downloads neither starve nor get starved by the pack scraping
+    fn image_pool(&self) -> Result<rayon::ThreadPool> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.image_jobs)
+            .thread_name(|i| format!("vega-images-{}", i))
+            .build()
+            .map_err(|e| VegaError::network("failed to start image download threads").with_cause(e))
+    }
+
+    /// Ask the site whether the stored images of `cards` changed, given the validators their
+    /// faces were served with. Cards that cannot be checked keep their stored images
+    pub fn check_all_card_images<'a>(
+        &self,
+        cards: &[(&'a Card, Vec<ImageValidators>)],
+    ) -> Result<Vec<(&'a Card, ImageCheck)>> {
+        let pool = self.image_pool()?;
+
+        Ok(pool.install(|| {
+            cards
+                .par_iter()
+                .map(|(card, validators)| {
+                    let check = self
+                        .check_card_images(card, validators)
+                        .unwrap_or_else(|e| {
+                            warn!("failed to check images of {}: {:#}", card.id, e);
+                            ImageCheck::Unchanged
+                        });
+                    (*card, check)
+                })
+                .collect()
+        }))
+    }
+
+    /// Ask the site whether any face of `card` changed since it was served with `validators`
+    /// (front first)
+    pub fn check_card_images(
+        &self,
+        card: &Card,
+        validators: &[ImageValidators],
+    ) -> Result<ImageCheck> {
+        let img_urls: Vec<&String> = std::iter::once(&card.img_url)
+            .chain(&card.img_back_url)
+            .collect();
+        if validators.len() < img_urls.len() || validators.iter().any(ImageValidators::is_empty) {
+            return Ok(ImageCheck::Unknown);
+        }
+
+        for (img_url, known) in img_urls.into_iter().zip(validators) {
+            if self.image_changed(img_url, known)? {
+                return Ok(ImageCheck::Changed);
+            }
+        }
+
+        Ok(ImageCheck::Unchanged)
+    }
+
+    /// Conditional request for `img_url`, a site ignoring it still tells a change by its validators
+    fn image_changed(&self, img_url: &str, known: &ImageValidators) -> Result<bool> {
+        let full_url = self.get_img_full_url(img_url)?;
+        let mut request = self.get_image(&full_url)?;
+        if let Some(etag) = &known.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &known.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = request.send()?;
+        match response.status() {
+            StatusCode::NOT_MODIFIED => Ok(false),
+            status if status.is_success() => Ok(response_validators(response.headers()) != *known),
+            status => Err(VegaError::network(format!("HTTP {}: {}", status, full_url))),
+        }
+    }
+
+    pub fn fetch_card_image(&self, card: &Card) -> Result<CardImages> {
+        let (front, front_variant, front_validators) =
+            self.fetch_image_of_quality(&card.img_url)?;
+        let (back, back_variant, back_validators) = match &card.img_back_url {
+            Some(img_back_url) => {
+                let (back, variant, validators) = self.fetch_image_of_quality(img_back_url)?;
+                (Some(back), variant, validators)
+            }
+            None => (None, None, ImageValidators::default()),
+        };
+
+        Ok(CardImages {
+            front,
+            back,
+            front_variant,
+            back_variant,
+            front_validators,
+            back_validators,
+        })
+    }
+
+    /// Image of `img_url` at the scraper's image quality, with the variant it was taken from
+    /// when probing for the best one and the validators of `img_url` itself
+    fn fetch_image_of_quality(
+        &self,
+        img_url: &str,
+    ) -> Result<(Vec<u8>, Option<String>, ImageValidators)> {
+        let (served, validators) = self.fetch_validated_image(img_url)?;
+        if self.image_quality == ImageQuality::Standard {
+            return Ok((served, None, validators));
+        }
+
+        let mut best = (served, SERVED_IMAGE_VARIANT.to_string());
+        let mut best_area = image_area(&best.0).unwrap_or(0);
+        for (variant, variant_url) in self.image_variant_urls(img_url) {
+            let img_data = match self.fetch_image(&variant_url) {
+                Ok(img_data) => img_data,
+                Err(e) => {
+                    debug!("no `{}` variant of {}: {}", variant, img_url, e);
+                    continue;
+                }
+            };
+            let area = image_area(&img_data).unwrap_or(0);
+            if area > best_area {
+                best_area = area;
+                best = (img_data, variant);
+            }
+        }
+
+        debug!("picked `{}` variant of {}", best.1, img_url);
+        Ok((best.0, Some(best.1), validators))
+    }
+
+    /// Other paths `img_url` may be available at, by variant name: without its query string
+    /// (`unsized`) and the locale's `image_variants`
+    fn image_variant_urls(&self, img_url: &str) -> Vec<(String, String)> {
+        let path = img_url.split(['?', '#']).next().unwrap_or_default();
+        let (dir, filename) = path.rsplit_once('/').unwrap_or(("", path));
+        let (stem, ext) = filename.rsplit_once('.').unwrap_or((filename, ""));
+
+        let mut urls = Vec::new();
+        if path != img_url {
+            urls.push((String::from("unsized"), path.to_string()));
+        }
+        for (variant, template) in &self.localizer.image_variants {
+            let url = template
+                .replace("{dir}", dir)
+                .replace("{stem}", stem)
+                .replace("{ext}", ext);
+            if url != img_url && url != path {
+                urls.push((variant.clone(), url));
+            }
+        }
+
+        urls
+    }
+
+    fn fetch_image(&self, img_url: &str) -> Result<Vec<u8>> {
+        Ok(self.fetch_validated_image(img_url)?.0)
+    }
+
+    /// Image of `img_url` along with the validators it was served with
+    fn fetch_validated_image(&self, img_url: &str) -> Result<(Vec<u8>, ImageValidators)> {
+        let full_url = self.get_img_full_url(img_url)?;
+        let part_path = self
+            .partial_dir
+            .as_ref()
+            .map(|dir| dir.join(part_filename(&full_url)));
+
+        // An earlier interrupted download is resumed only if the image is still the one it
+        // started on (`If-Range`), its bytes are requested again otherwise
+        let (mut img_data, mut part_validators) = match &part_path {
+            Some(part_path) if part_path.exists() => match read_part_validators(part_path) {
+                Some(validators) if if_range(&validators).is_some() => {
+                    (fs::read(part_path)?, validators)
+                }
+                _ => {
+                    debug!("cannot resume `{}` without validators", full_url);
+                    remove_part_file(Some(part_path));
+                    (Vec::new(), ImageValidators::default())
+                }
+            },
+            _ => (Vec::new(), ImageValidators::default()),
+        };
+        if !img_data.is_empty() {
+            debug!("resuming `{}` from byte {}", full_url, img_data.len());
+        } else {
+            debug!("downloading image `{}`...", full_url);
+        }
+
+        let mut retries = 3;
+        loop {
+            let mut request = self.get_image(&full_url)?;
+            if !img_data.is_empty() {
+                if let Some(if_range) = if_range(&part_validators) {
+                    request = request
+                        .header(RANGE, format!("bytes={}-", img_data.len()))
+                        .header(IF_RANGE, if_range);
+                }
+            }
+
+            match request.send() {
+                Ok(response) => {
+                    let status = response.status();
+                    if status == StatusCode::RANGE_NOT_SATISFIABLE && !img_data.is_empty() {
+                        // Stale partial file, longer than the image now served
+                        img_data.clear();
+                        remove_part_file(part_path.as_deref());
+                        continue;
+                    }
+                    if !status.is_success() {
+                        return Err(VegaError::network(format!("HTTP {}: {}", status, full_url)));
+                    }
+                    if status == StatusCode::PARTIAL_CONTENT
+                        && content_range_start(response.headers()) != Some(img_data.len())
+                    {
+                        if img_data.is_empty() {
+                            return Err(VegaError::network(format!(
+                                "unexpected partial content: {}",
+                                full_url
+                            )));
+                        }
+                        warn!(
+                            "`{}` resumed at another byte than {}, downloading it again",
+                            full_url,
+                            img_data.len()
+                        );
+                        img_data.clear();
+                        remove_part_file(part_path.as_deref());
+                        continue;
+                    }
+                    if status != StatusCode::PARTIAL_CONTENT {
+                        // The whole image, the site ignoring Range or the image having changed
+                        img_data.clear();
+                    }
+                    let validators = response_validators(response.headers());
+                    if status != StatusCode::PARTIAL_CONTENT {
+                        part_validators = validators.clone();
+                    }
+
+                    if let Err(e) = read_body(
+                        response,
+                        &mut img_data,
+                        part_path.as_deref(),
+                        &part_validators,
+                    ) {
+                        retries -= 1;
+                        if retries == 0 {
+                            return Err(VegaError::network(format!(
+                                "failed after 3 retries: {}",
+                                e
+                            )));
+                        }
+                        warn!(
+                            "download of {} interrupted after {} bytes: {}, resuming",
+                            full_url,
+                            img_data.len(),
+                            e
+                        );
+                        thread::sleep(Duration::from_millis(100));
+                        continue;
+                    }
+                    debug!("downloaded {} bytes from {}", img_data.len(), full_url);
+                    remove_part_file(part_path.as_deref());
+
+                    // Error pages are sometimes served with a 200
+                    let Some(problem) = check_image_bytes(&img_data) else {
+                        return Ok((img_data, validators));
+                    };
+                    img_data.clear();
+                    retries -= 1;
+                    if retries == 0 {
+                        return Err(VegaError::network(format!(
+                            "invalid image from {}: {}",
+                            full_url, problem
+                        )));
+                    }
+                    warn!("invalid image from {}: {}, retrying", full_url, problem);
+                    thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => {
+                    retries -= 1;
+                    if retries == 0 {
+                        return Err(VegaError::network(format!("failed after 3 retries: {}", e)));
+                    }
+                    thread::sleep(Duration::from_millis(100));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    fn test_png() -> Vec<u8> {
+        let mut png = Cursor::new(Vec::new());
+        image::RgbImage::from_fn(64, 64, |x, y| {
+            image::Rgb([(x * y) as u8, (x ^ y) as u8, (x * 7) as u8])
+        })
+        .write_to(&mut png, image::ImageFormat::Png)
+        .unwrap();
+        png.into_inner()
+    }
+
+    /// Responses to a download of `test_png` interrupted halfway through
+    enum Served {
+        Whole,
+        Half,
+        From(usize),
+    }
+
+    /// `Range` and `If-Range` headers of a request
+    type RangeHeaders = (Option<String>, Option<String>);
+
+    /// Fetch `test_png` from a site answering in turn with `responses`, returning the image and
+    /// the `Range` and `If-Range` headers of each request
+    fn fetch_served(responses: Vec<Served>) -> (Vec<u8>, Vec<RangeHeaders>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let hostname = format!("http://{}", listener.local_addr().unwrap());
+
+        let served = test_png();
+        let count = responses.len();
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (response, stream) in responses.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let (mut range, mut if_range) = (None, None);
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let lower = line.to_lowercase();
+                    if let Some(value) = lower.strip_prefix("range: ") {
+                        range = Some(value.to_string());
+                    } else if lower.starts_with("if-range: ") {
+                        if_range = Some(line["if-range: ".len()..].to_string());
+                    }
+                }
+                requests.push((range, if_range));
+
+                match response {
+                    Served::Whole => {
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nETag: \"v2\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            served.len()
+                        )
+                        .unwrap();
+                        stream.write_all(&served).unwrap();
+                    }
+                    Served::Half => {
+                        // Announce the whole image but hang up halfway through
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\r\n",
+                            served.len()
+                        )
+                        .unwrap();
+                        stream.write_all(&served[..served.len() / 2]).unwrap();
+                    }
+                    Served::From(start) => {
+                        write!(
+                            stream,
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                            served.len() - start,
+                            start,
+                            served.len() - 1,
+                            served.len()
+                        )
+                        .unwrap();
+                        stream.write_all(&served[start..]).unwrap();
+                    }
+                }
+            }
+            requests
+        });
+
+        let partial_dir = std::env::temp_dir().join(format!(
+            "vega-test-partial-{}-{}",
+            std::process::id(),
+            hostname.rsplit(':').next().unwrap()
+        ));
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.hostname = hostname;
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .partial_dir(Some(partial_dir.clone()))
+            .build()
+            .unwrap();
+
+        let img_data = scraper.fetch_image("../images/OP01-001.png").unwrap();
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), count);
+        assert_eq!(fs::read_dir(&partial_dir).unwrap().count(), 0);
+        fs::remove_dir_all(partial_dir).unwrap();
+        (img_data, requests)
+    }
+
+    #[test]
+    fn fetch_image_resumes_interrupted_download() {
+        let half = test_png().len() / 2;
+
+        let (img_data, requests) = fetch_served(vec![Served::Half, Served::From(half)]);
+
+        assert_eq!(img_data, test_png());
+        assert_eq!(
+            requests,
+            vec![
+                (None, None),
+                (Some(format!("bytes={}-", half)), Some("\"v1\"".to_string()))
+            ]
+        );
+    }
+
+    #[test]
+    fn fetch_image_of_image_changed_since_interruption_downloads_it_whole() {
+        let (img_data, requests) = fetch_served(vec![Served::Half, Served::Whole]);
+
+        assert_eq!(img_data, test_png());
+        assert_eq!(requests[1].1, Some("\"v1\"".to_string()));
+    }
+
+    #[test]
+    fn fetch_image_resumed_at_another_byte_downloads_it_again() {
+        let half = test_png().len() / 2;
+
+        let (img_data, requests) =
+            fetch_served(vec![Served::Half, Served::From(half - 1), Served::Whole]);
+
+        assert_eq!(img_data, test_png());
+        assert_eq!(requests[2], (None, None));
+    }
+
+    #[test]
+    fn image_changed_sends_stored_validators() {
+        let png = test_png();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let hostname = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut if_none_match = None;
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("if-none-match: ") {
+                        if_none_match = Some(value.to_string());
+                    }
+                }
+
+                if if_none_match.as_deref() == Some("\"v2\"") {
+                    write!(
+                        stream,
+                        "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n"
+                    )
+                    .unwrap();
+                } else {
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nETag: \"v2\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        png.len()
+                    )
+                    .unwrap();
+                    stream.write_all(&png).unwrap();
+                }
+            }
+        });
+
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.hostname = hostname;
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .build()
+            .unwrap();
+        let validators = |etag: &str| ImageValidators {
+            etag: Some(etag.to_string()),
+            last_modified: None,
+        };
+
+        assert!(scraper
+            .image_changed("../images/OP01-001.png", &validators("\"v1\""))
+            .unwrap());
+        assert!(!scraper
+            .image_changed("../images/OP01-001.png", &validators("\"v2\""))
+            .unwrap());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn fetch_all_card_images_hands_over_each_card() {
+        let png = test_png();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let hostname = format!("http://{}", listener.local_addr().unwrap());
+
+        let served = png.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                for line in BufReader::new(&stream).lines() {
+                    if line.unwrap().is_empty() {
+                        break;
+                    }
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    served.len()
+                )
+                .unwrap();
+                stream.write_all(&served).unwrap();
+            }
+        });
+
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.hostname = hostname;
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .image_jobs(2)
+            .build()
+            .unwrap();
+        let cards: Vec<Card> = ["OP01-001", "OP01-002"]
+            .iter()
+            .map(|id| {
+                Card::builder()
+                    .id(id)
+                    .pack_id("569101")
+                    .name("Roronoa Zoro")
+                    .rarity(crate::card::CardRarity::Leader)
+                    .img_url(&format!("../images/{}.png", id))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let cards: Vec<&Card> = cards.iter().collect();
+
+        let handed_over = std::sync::Mutex::new(Vec::new());
+        let fetched = scraper
+            .fetch_all_card_images(&cards, |card, images| {
+                assert_eq!(images.front, png);
+                handed_over.lock().unwrap().push(card.id.to_string());
+                Ok(())
+            })
+            .unwrap();
+
+        let mut handed_over = handed_over.into_inner().unwrap();
+        handed_over.sort();
+        assert_eq!(handed_over, vec!["OP01-001", "OP01-002"]);
+        assert_eq!(fetched.images.values().sum::<usize>(), 2);
+        assert!(fetched.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_packs_async_parses_series() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let hostname = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            let mut stream = listener.incoming().next().unwrap().unwrap();
+            for line in BufReader::new(&stream).lines() {
+                if line.unwrap().is_empty() {
+                    break;
+                }
+            }
+            let html = r#"<div class="seriesCol"><select id="series">
+                <option value="569101">BOOSTER PACK -ROMANCE DAWN- [OP-01]</option>
+                </select></div>"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                html.len(),
+                html
+            )
+            .unwrap();
+        });
+
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.hostname = hostname;
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .build()
+            .unwrap();
+
+        fn assert_send<T: Send>(future: T) -> T {
+            future
+        }
+        let packs = assert_send(scraper.fetch_packs_async()).await.unwrap();
+
+        let pack_id: PackId = "569101".parse().unwrap();
+        assert_eq!(packs.len(), 1);
+        assert_eq!(packs[&pack_id].title_parts.label.as_deref(), Some("OP-01"));
+    }
+
+    /// Card list page with a card that fails to scrape followed by a DON!! card
+    const CARDLIST_HTML: &str = r##"<div class="resultCol">
+        <a data-src="#OP01-999"></a><a data-src="#OP01-404"></a><a data-src="#DON-001"></a>
+        </div>
+        <dl id="OP01-999"><dt></dt></dl>
+        <dl id="DON-001"><dt>
+        <div class="infoCol"><span>DON-001</span><span>C</span><span>DON!!</span></div>
+        <div class="cardName">DON!! Card</div>
+        </dt><dd><div class="frontCol"><img data-src="../images/cardlist/card/DON-001.png"></div></dd></dl>"##;
+
+    /// Serve `html` to a single request, returning the hostname to send it to
+    fn serve_page(html: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let hostname = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            let mut stream = listener.incoming().next().unwrap().unwrap();
+            for line in BufReader::new(&stream).lines() {
+                if line.unwrap().is_empty() {
+                    break;
+                }
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                html.len(),
+                html
+            )
+            .unwrap();
+        });
+
+        hostname
+    }
+
+    #[test]
+    fn stream_cards_yields_each_card_and_goes_on_after_errors() {
+        let hostname = serve_page(CARDLIST_HTML);
+
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.hostname = hostname.clone();
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .build()
+            .unwrap();
+
+        let mut cards = scraper.stream_cards(&"569101".parse().unwrap()).unwrap();
+        assert_eq!(cards.size_hint(), (3, Some(3)));
+        assert!(cards.next().unwrap().is_err());
+        // Listed without a `<dl>` of its own
+        assert!(cards.next().unwrap().is_err());
+
+        let card = cards.next().unwrap().unwrap();
+        assert_eq!(card.id, "DON-001");
+        assert_eq!(card.name, "DON!! Card");
+        assert_eq!(
+            card.img_full_url,
+            Some(format!("{}/images/cardlist/card/DON-001.png", hostname))
+        );
+        assert!(cards.next().is_none());
+    }
+
+    #[test]
+    fn fetch_cards_reports_progress_events() {
+        #[derive(Default)]
+        struct RecordingProgress(Arc<std::sync::Mutex<Vec<String>>>);
+
+        impl ProgressReporter for RecordingProgress {
+            fn on_pack_started(&self, pack_id: &PackId) {
+                self.0.lock().unwrap().push(format!("started {}", pack_id));
+            }
+
+            fn on_pack_finished(&self, pack_id: &PackId, fetched: &FetchedCards) {
+                self.0.lock().unwrap().push(format!(
+                    "finished {} with {} cards",
+                    pack_id,
+                    fetched.cards.len()
+                ));
+            }
+
+            fn on_card_parsed(&self, card: &Card) {
+                self.0.lock().unwrap().push(format!("parsed {}", card.id));
+            }
+
+            fn on_error(&self, subject: &str, _error: &VegaError) {
+                self.0.lock().unwrap().push(format!("failed {}", subject));
+            }
+        }
+
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.hostname = serve_page(CARDLIST_HTML);
+        let progress = RecordingProgress::default();
+        let events = progress.0.clone();
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .progress(progress)
+            .build()
+            .unwrap();
+
+        let fetched = scraper.fetch_cards(&"569101".parse().unwrap()).unwrap();
+
+        assert_eq!(fetched.errors, 2);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "started 569101",
+                "failed OP01-999",
+                "failed OP01-404",
+                "parsed DON-001",
+                "finished 569101 with 1 cards"
+            ]
+        );
+    }
+
+    #[test]
+    fn img_full_url_resolves_relative_and_keeps_absolute_urls() {
+        let localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .build()
+            .unwrap();
+        let base_url = &scraper.base_url;
+
+        assert_eq!(
+            scraper
+                .get_img_full_url("../images/cardlist/card/OP01-001.png")
+                .unwrap(),
+            format!("{}/images/cardlist/card/OP01-001.png", base_url)
+        );
+        assert_eq!(
+            scraper
+                .get_img_full_url("https://cdn.example.com/OP01-001.png")
+                .unwrap(),
+            "https://cdn.example.com/OP01-001.png"
+        );
+        for img_url in ["", "..", "/images/OP01-001.png", "画像/OP01-001.png"] {
+            assert!(scraper.get_img_full_url(img_url).is_err(), "{img_url}");
+        }
+    }
+
+    #[test]
+    fn image_variant_urls_expand_locale_templates() {
+        let mut localizer: Localizer = toml::from_str(crate::localizer::EN_LOCALE_RAW).unwrap();
+        localizer.image_variants = BTreeMap::from([
+            (
+                String::from("large"),
+                String::from("{dir}/large/{stem}.{ext}"),
+            ),
+            (String::from("same"), String::from("{dir}/{stem}.{ext}")),
+        ]);
+        let scraper = OpTcgScraper::builder()
+            .localizer(localizer)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scraper.image_variant_urls("../images/cardlist/card/OP01-001.png?240628"),
+            vec![
+                (
+                    String::from("unsized"),
+                    String::from("../images/cardlist/card/OP01-001.png")
+                ),
+                (
+                    String::from("large"),
+                    String::from("../images/cardlist/card/large/OP01-001.png")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_image_bytes_rejects_error_pages_and_tiny_images() {
+        let png = test_png();
+        assert!(png.len() >= MIN_IMAGE_BYTES);
+
+        assert_eq!(check_image_bytes(&png), None);
+        assert!(check_image_bytes(b"<!DOCTYPE html><html>Not Found</html>")
+            .unwrap()
+            .starts_with("not an image"));
+        assert_eq!(
+            check_image_bytes(&png[..100]).as_deref(),
+            Some("only 100 bytes")
+        );
+    }
+
+    #[test]
+    fn check_card_image_size_flags_placeholders() {
+        let png = |width, height| {
+            let mut png = Cursor::new(Vec::new());
+            image::RgbImage::new(width, height)
+                .write_to(&mut png, image::ImageFormat::Png)
+                .unwrap();
+            png.into_inner()
+        };
+
+        let mut images = CardImages {
+            front: png(600, 838),
+            back: Some(png(600, 838)),
+            ..Default::default()
+        };
+        assert_eq!(check_card_image_size(&images), None);
+
+        images.back = Some(png(120, 168));
+        assert_eq!(
+            check_card_image_size(&images).as_deref(),
+            Some("back is 120x168")
+        );
+    }
+}
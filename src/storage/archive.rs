@@ -0,0 +1,290 @@
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::storage::{atomic, StoreBackend};
+
+use crate::error::{Context, Result, VegaError};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "cli", value(rename_all = "kebab-case"))]
+pub enum ArchiveFormat {
+    /// `.zip` archive with deflated entries
+    Zip,
+    /// `.tar.zst` archive
+    TarZst,
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarZst => "tar.zst",
+        }
+    }
+
+    /// `data/` is archived to `data.zip`
+    pub fn archive_path(self, root_dir: &Path) -> Result<PathBuf> {
+        let name = root_dir.file_name().ok_or_else(|| {
+            VegaError::storage(format!("expected a directory name: {}", root_dir.display()))
+        })?;
+
+        Ok(root_dir.with_file_name(format!("{}.{}", name.to_string_lossy(), self.extension())))
+    }
+}
+
+enum ArchiveWriter {
+    Zip(ZipWriter<File>),
+    TarZst(tar::Builder<zstd::Encoder<'static, File>>),
+}
+
+struct ArchiveState {
+    // None once the archive has been finished
+    writer: Option<ArchiveWriter>,
+    entries: BTreeSet<String>,
+    // Whether the archive was renamed into place
+    finished: bool,
+}
+
+/// Streams every file of a store into a single archive instead of loose files.
+/// Entries are write-once and cannot be read back until the archive is finished, an archive
+/// dropped unfinished is deleted.
+pub struct ArchiveBackend {
+    root_dir: PathBuf,
+    archive_path: PathBuf,
+    temp_path: PathBuf,
+    state: Mutex<ArchiveState>,
+}
+
+impl ArchiveBackend {
+    pub fn create(root_dir: &Path, format: ArchiveFormat) -> Result<Self> {
+        Self::create_at(root_dir, &format.archive_path(root_dir)?, format)
+    }
+
+    /// Archive the files of `root_dir` to an explicit `archive_path`
+    pub fn create_at(root_dir: &Path, archive_path: &Path, format: ArchiveFormat) -> Result<Self> {
+        let archive_path = archive_path.to_path_buf();
+        if let Some(parent_dir) = archive_path.parent() {
+            if !parent_dir.as_os_str().is_empty() && !parent_dir.exists() {
+                fs::create_dir_all(parent_dir)
+                    .with_context(|| format!("failed to create `{}`", parent_dir.display()))?;
+            }
+        }
+
+        // Written next to the final path and renamed once complete, like any other store file
+        let temp_path = atomic::temp_path_for(&archive_path)?;
+        let file = File::create(&temp_path)
+            .with_context(|| format!("failed to create: {}", temp_path.display()))?;
+
+        let writer = match format {
+            ArchiveFormat::Zip => ArchiveWriter::Zip(ZipWriter::new(file)),
+            ArchiveFormat::TarZst => match zstd::Encoder::new(file, 19) {
+                Ok(encoder) => ArchiveWriter::TarZst(tar::Builder::new(encoder)),
+                Err(e) => {
+                    let _ = fs::remove_file(&temp_path);
+                    return Err(e.into());
+                }
+            },
+        };
+        debug!("writing {:?} archive to: {}", format, temp_path.display());
+
+        Ok(Self {
+            root_dir: root_dir.to_path_buf(),
+            archive_path,
+            temp_path,
+            state: Mutex::new(ArchiveState {
+                writer: Some(writer),
+                entries: BTreeSet::new(),
+                finished: false,
+            }),
+        })
+    }
+
+    fn entry_name(&self, path: &Path) -> Result<String> {
+        let relative_path = path.strip_prefix(&self.root_dir).with_context(|| {
+            format!(
+                "`{}` is outside of the archived store `{}`",
+                path.display(),
+                self.root_dir.display()
+            )
+        })?;
+
+        Ok(relative_path.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+impl StoreBackend for ArchiveBackend {
+    fn write(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let name = self.entry_name(path)?;
+        let mut state = self.state.lock().unwrap();
+
+        if state.entries.contains(&name) {
+            return Err(VegaError::storage(format!(
+                "`{}` was already written to the archive",
+                name
+            )));
+        }
+
+        match state.writer.as_mut() {
+            Some(ArchiveWriter::Zip(zip)) => {
+                let options =
+                    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+                zip.start_file(name.as_str(), options)?;
+                zip.write_all(data)?;
+            }
+            Some(ArchiveWriter::TarZst(tar)) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(0); // Keep archives identical for identical data
+                tar.append_data(&mut header, name.as_str(), data)?;
+            }
+            None => {
+                return Err(VegaError::storage(format!(
+                    "archive is already finished: {}",
+                    self.archive_path.display()
+                )))
+            }
+        }
+
+        debug!("archived {} bytes as: {}", data.len(), name);
+        state.entries.insert(name);
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        Err(VegaError::storage(format!(
+            "cannot read `{}` back from an archive being written",
+            path.display()
+        )))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.entry_name(path) {
+            Ok(name) => self.state.lock().unwrap().entries.contains(&name),
+            Err(_) => false,
+        }
+    }
+
+    fn finish(&self) -> Result<()> {
+        let writer = self.state.lock().unwrap().writer.take();
+        let file = match writer {
+            Some(ArchiveWriter::Zip(zip)) => zip.finish()?,
+            Some(ArchiveWriter::TarZst(tar)) => tar.into_inner()?.finish()?,
+            None => return Ok(()),
+        };
+
+        file.sync_all()?;
+        fs::rename(&self.temp_path, &self.archive_path)
+            .with_context(|| format!("failed to write: {}", self.archive_path.display()))?;
+        self.state.lock().unwrap().finished = true;
+
+        info!("wrote archive: {}", self.archive_path.display());
+        Ok(())
+    }
+}
+
+impl Drop for ArchiveBackend {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(|e| e.into_inner());
+        if state.finished {
+            return;
+        }
+
+        // Close the archive before removing it
+        state.writer.take();
+        match fs::remove_file(&self.temp_path) {
+            Ok(()) => debug!("removed unfinished archive: {}", self.temp_path.display()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => warn!(
+                "failed to remove unfinished archive {}: {}",
+                self.temp_path.display(),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn temp_root(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("vega-test-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn archive_path_appends_extension() {
+        let path = ArchiveFormat::TarZst
+            .archive_path(Path::new("out/data-en/"))
+            .unwrap();
+        assert_eq!(path, PathBuf::from("out/data-en.tar.zst"));
+    }
+
+    #[test]
+    fn write_zip_then_finish_returns_entries() {
+        let root = temp_root("archive-zip");
+        let backend = ArchiveBackend::create(&root, ArchiveFormat::Zip).unwrap();
+
+        backend.write(&root.join("json/packs.json"), b"{}").unwrap();
+        assert!(backend.exists(&root.join("json/packs.json")));
+        assert!(backend.write(&root.join("json/packs.json"), b"{}").is_err());
+        assert!(backend
+            .write(Path::new("/elsewhere/packs.json"), b"")
+            .is_err());
+        backend.finish().unwrap();
+
+        let archive_path = ArchiveFormat::Zip.archive_path(&root).unwrap();
+        let mut zip = zip::ZipArchive::new(File::open(&archive_path).unwrap()).unwrap();
+        let mut content = String::new();
+        zip.by_name("json/packs.json")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "{}");
+
+        fs::remove_file(archive_path).unwrap();
+    }
+
+    #[test]
+    fn write_tar_zst_then_finish_returns_entries() {
+        let root = temp_root("archive-tar");
+        let backend = ArchiveBackend::create(&root, ArchiveFormat::TarZst).unwrap();
+        backend.write(&root.join("images/a.png"), b"png").unwrap();
+        backend.finish().unwrap();
+
+        let archive_path = ArchiveFormat::TarZst.archive_path(&root).unwrap();
+        let decoder = zstd::Decoder::new(File::open(&archive_path).unwrap()).unwrap();
+        let mut tar = tar::Archive::new(decoder);
+        let entries: Vec<PathBuf> = tar
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_path_buf())
+            .collect();
+        assert_eq!(entries, vec![PathBuf::from("images/a.png")]);
+
+        fs::remove_file(archive_path).unwrap();
+    }
+
+    #[test]
+    fn dropped_unfinished_archive_is_removed() {
+        let root = temp_root("archive-dropped");
+        let backend = ArchiveBackend::create(&root, ArchiveFormat::Zip).unwrap();
+        backend.write(&root.join("json/packs.json"), b"{}").unwrap();
+        let temp_path = backend.temp_path.clone();
+        assert!(temp_path.exists());
+
+        drop(backend);
+        assert!(!temp_path.exists());
+        assert!(!ArchiveFormat::Zip.archive_path(&root).unwrap().exists());
+    }
+}
@@ -0,0 +1,75 @@
+use log::trace;
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::error::{Context, Result, VegaError};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| VegaError::storage(format!("expected a file path: {}", path.display())))?;
+
+    let count = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let temp_name = format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        process::id(),
+        count
+    );
+
+    Ok(path.with_file_name(temp_name))
+}
+
+/// Write `data` to a temporary file next to `path`, then rename it over `path`.
+/// Readers either see the previous content or the new one, never a partial file.
+pub fn write(path: &Path, data: &[u8]) -> Result<()> {
+    let temp_path = temp_path_for(path)?;
+    trace!("writing {} bytes to: {}", data.len(), temp_path.display());
+
+    let result = (|| -> Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?; // Ensure written to disk before the rename
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result.with_context(|| format!("failed to write: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_replaces_content_and_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join(format!("vega-test-atomic-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("packs.json");
+        write(&path, b"old").unwrap();
+        write(&path, b"new").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_missing_dir_returns_err() {
+        let path = std::env::temp_dir().join("vega-test-missing-dir/packs.json");
+        assert!(write(&path, b"data").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use log::debug;
+use std::{fs, path::Path};
+
+use crate::{
+    error::{Context, Result},
+    storage::atomic,
+};
+
+/// Where the bytes of a `DataStore` end up.
+/// `DataStore` decides paths, formats and compression, backends only move bytes around.
+pub trait StoreBackend: Send + Sync {
+    fn write(&self, path: &Path, data: &[u8]) -> Result<()>;
+    fn read(&self, path: &Path) -> Result<Vec<u8>>;
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Make `link` point to the same content as `original` without copying it.
+    /// Returns `false` when the backend cannot link (the caller then writes a copy).
+    fn link(&self, _original: &Path, _link: &Path) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Called once everything has been written
+    fn finish(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Files on the local filesystem
+#[derive(Debug, Default)]
+pub struct FsBackend;
+
+impl StoreBackend for FsBackend {
+    fn write(&self, path: &Path, data: &[u8]) -> Result<()> {
+        if let Some(parent_dir) = path.parent() {
+            if !parent_dir.exists() {
+                fs::create_dir_all(parent_dir)
+                    .with_context(|| format!("failed to create `{}`", parent_dir.display()))?;
+                debug!("successfully created `{}`", parent_dir.display());
+            }
+        }
+
+        atomic::write(path, data)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        fs::read(path).with_context(|| format!("failed to read: {}", path.display()))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn link(&self, original: &Path, link: &Path) -> Result<bool> {
+        if let Some(parent_dir) = link.parent() {
+            fs::create_dir_all(parent_dir)
+                .with_context(|| format!("failed to create `{}`", parent_dir.display()))?;
+        }
+
+        if link.exists() {
+            fs::remove_file(link)?;
+        }
+
+        match fs::hard_link(original, link) {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                debug!(
+                    "cannot hardlink {} to {}: {}",
+                    link.display(),
+                    original.display(),
+                    e
+                );
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub use self::memory::MemoryBackend;
+
+#[cfg(test)]
+mod memory {
+    use std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    };
+
+    use super::StoreBackend;
+    use crate::error::{Result, VegaError};
+
+    /// Files kept in memory, shared between clones (for tests)
+    #[derive(Debug, Default, Clone)]
+    pub struct MemoryBackend {
+        files: Arc<Mutex<BTreeMap<PathBuf, Vec<u8>>>>,
+    }
+
+    impl MemoryBackend {
+        pub fn paths(&self) -> Vec<PathBuf> {
+            self.files.lock().unwrap().keys().cloned().collect()
+        }
+    }
+
+    impl StoreBackend for MemoryBackend {
+        fn write(&self, path: &Path, data: &[u8]) -> Result<()> {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), data.to_vec());
+            Ok(())
+        }
+
+        fn read(&self, path: &Path) -> Result<Vec<u8>> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| VegaError::storage(format!("file not found: {}", path.display())))
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.files.lock().unwrap().contains_key(path)
+        }
+    }
+}
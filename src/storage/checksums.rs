@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use crate::error::{Result, VegaError};
+
+/// Parse `sha256sum` output into file path to hash
+pub fn parse(data: &str) -> Result<BTreeMap<String, String>> {
+    data.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (hash, file) = line
+                .split_once("  ")
+                .ok_or_else(|| VegaError::storage(format!("invalid checksum line: `{}`", line)))?;
+            Ok((file.to_string(), hash.to_string()))
+        })
+        .collect()
+}
+
+/// Format checksums like `sha256sum` does, so `sha256sum -c` can verify them
+pub fn format(checksums: &BTreeMap<String, String>) -> String {
+    checksums
+        .iter()
+        .map(|(file, hash)| format!("{}  {}\n", hash, file))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_then_parse_returns_same() {
+        let checksums = BTreeMap::from([
+            (String::from("json/packs.json"), String::from("ab12")),
+            (String::from("images/a b.png"), String::from("cd34")),
+        ]);
+        let data = format(&checksums);
+        assert_eq!(data, "cd34  images/a b.png\nab12  json/packs.json\n");
+        assert_eq!(parse(&data).unwrap(), checksums);
+    }
+
+    #[test]
+    fn parse_invalid_line_returns_err() {
+        assert!(parse("not a checksum line").is_err());
+    }
+}
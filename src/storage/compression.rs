@@ -0,0 +1,103 @@
+use flate2::{read::GzDecoder, write::GzEncoder};
+use serde::Serialize;
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::error::Result;
+
+const ZSTD_LEVEL: i32 = 19;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Compression {
+    /// Plain files
+    #[default]
+    None,
+    /// gzip compressed files (.gz)
+    Gzip,
+    /// Zstandard compressed files (.zst)
+    Zstd,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 3] = [Compression::None, Compression::Gzip, Compression::Zstd];
+
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Append the compression extension to `path` (e.g. `cards.json` -> `cards.json.zst`)
+    pub fn apply_to_path(self, path: &Path) -> PathBuf {
+        match self.extension() {
+            Some(ext) => {
+                let mut path = path.as_os_str().to_owned();
+                path.push(".");
+                path.push(ext);
+                PathBuf::from(path)
+            }
+            None => path.to_path_buf(),
+        }
+    }
+
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        let compressed = match self {
+            Compression::None => data.to_vec(),
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Compression::Zstd => zstd::encode_all(data, ZSTD_LEVEL)?,
+        };
+
+        Ok(compressed)
+    }
+
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        let decompressed = match self {
+            Compression::None => data.to_vec(),
+            Compression::Gzip => {
+                let mut decoded = Vec::new();
+                GzDecoder::new(data).read_to_end(&mut decoded)?;
+                decoded
+            }
+            Compression::Zstd => zstd::decode_all(data)?,
+        };
+
+        Ok(decompressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_to_path_appends_extension() {
+        let path = Path::new("json/cards_OP01.json");
+        assert_eq!(Compression::None.apply_to_path(path), path);
+        assert_eq!(
+            Compression::Gzip.apply_to_path(path),
+            Path::new("json/cards_OP01.json.gz")
+        );
+        assert_eq!(
+            Compression::Zstd.apply_to_path(path),
+            Path::new("json/cards_OP01.json.zst")
+        );
+    }
+
+    #[test]
+    fn compress_then_decompress_returns_original() {
+        let data = br#"[{"id":"OP01-001","name":"Roronoa Zoro"}]"#.repeat(10);
+        for compression in Compression::ALL {
+            let compressed = compression.compress(&data).unwrap();
+            assert_eq!(compression.decompress(&compressed).unwrap(), data);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    card::Card,
+    pack::{Pack, PackId},
+    storage::schema::SCHEMA_VERSION,
+};
+
+/// Every pack along with its cards, as written by the `--single-file` layout
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Dataset {
+    pub schema_version: u32,
+    pub packs: BTreeMap<PackId, DatasetPack>,
+}
+
+impl Default for Dataset {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            packs: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DatasetPack {
+    // Missing when cards were pulled without the list of packs
+    #[serde(flatten)]
+    pub pack: Option<Pack>,
+    #[serde(default)]
+    pub cards: Vec<Card>,
+}
+
+impl Dataset {
+    pub fn set_packs(&mut self, packs: &HashMap<PackId, Pack>) {
+        for (pack_id, pack) in packs {
+            self.packs.entry(pack_id.clone()).or_default().pack = Some(pack.clone());
+        }
+    }
+
+    pub fn set_cards(&mut self, pack_id: &PackId, cards: &[Card]) {
+        self.packs.entry(pack_id.clone()).or_default().cards = cards.to_vec();
+    }
+
+    pub fn packs(&self) -> HashMap<PackId, Pack> {
+        self.packs
+            .iter()
+            .filter_map(|(pack_id, entry)| Some((pack_id.clone(), entry.pack.clone()?)))
+            .collect()
+    }
+
+    pub fn cards(&self, pack_id: &PackId) -> Option<&Vec<Card>> {
+        self.packs.get(pack_id).map(|entry| &entry.cards)
+    }
+}
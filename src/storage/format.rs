@@ -0,0 +1,133 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::Result;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OutputFormat {
+    /// One JSON document per file
+    #[default]
+    Json,
+    /// Newline-delimited JSON, one record per line
+    Ndjson,
+    /// YAML documents, easier to review and edit by hand
+    Yaml,
+    /// MessagePack binary documents, smaller and faster to parse
+    Msgpack,
+}
+
+impl OutputFormat {
+    /// Every format, in the order `--format` lists them
+    pub const ALL: [OutputFormat; 4] = [
+        OutputFormat::Json,
+        OutputFormat::Ndjson,
+        OutputFormat::Yaml,
+        OutputFormat::Msgpack,
+    ];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Msgpack => "msgpack",
+        }
+    }
+
+    /// Serialize a whole document (for NDJSON, the document is written as a single line)
+    pub fn serialize<T: Serialize>(self, value: &T, pretty: bool) -> Result<Vec<u8>> {
+        let data = match self {
+            OutputFormat::Json if pretty => {
+                let mut data = serde_json::to_vec_pretty(value)?;
+                data.push(b'\n');
+                data
+            }
+            OutputFormat::Json => serde_json::to_vec(value)?,
+            OutputFormat::Ndjson => {
+                let mut data = serde_json::to_vec(value)?;
+                data.push(b'\n');
+                data
+            }
+            OutputFormat::Yaml => serde_yaml::to_string(value)?.into_bytes(),
+            // Named fields keep the documents self-describing (needed by schema migrations)
+            OutputFormat::Msgpack => rmp_serde::to_vec_named(value)?,
+        };
+
+        Ok(data)
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self, data: &[u8]) -> Result<T> {
+        let value = match self {
+            OutputFormat::Json | OutputFormat::Ndjson => serde_json::from_slice(data)?,
+            OutputFormat::Yaml => serde_yaml::from_slice(data)?,
+            OutputFormat::Msgpack => rmp_serde::from_slice(data)?,
+        };
+
+        Ok(value)
+    }
+
+    /// Serialize a list of records (for NDJSON, one record per line)
+    pub fn serialize_records<T: Serialize>(self, records: &[T], pretty: bool) -> Result<Vec<u8>> {
+        match self {
+            OutputFormat::Ndjson => {
+                let mut data = Vec::new();
+                for record in records {
+                    serde_json::to_writer(&mut data, record)?;
+                    data.push(b'\n');
+                }
+
+                Ok(data)
+            }
+            _ => self.serialize(&records, pretty),
+        }
+    }
+
+    pub fn deserialize_records<T: DeserializeOwned>(self, data: &[u8]) -> Result<Vec<T>> {
+        match self {
+            OutputFormat::Ndjson => serde_json::Deserializer::from_slice(data)
+                .into_iter()
+                .map(|record| Ok(record?))
+                .collect(),
+            _ => self.deserialize(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_records_ndjson_writes_one_line_per_record() {
+        let records = vec![String::from("OP01-001"), String::from("OP01-002")];
+        let data = OutputFormat::Ndjson
+            .serialize_records(&records, false)
+            .unwrap();
+        assert_eq!(data, b"\"OP01-001\"\n\"OP01-002\"\n");
+    }
+
+    #[test]
+    fn serialize_json_pretty_writes_indented_document() {
+        let data = OutputFormat::Json.serialize(&vec![1, 2], true).unwrap();
+        assert_eq!(data, b"[\n  1,\n  2\n]\n");
+    }
+
+    #[test]
+    fn serialize_yaml_writes_readable_document() {
+        let records = vec![String::from("OP01-001"), String::from("OP01-002")];
+        let data = OutputFormat::Yaml
+            .serialize_records(&records, false)
+            .unwrap();
+        assert_eq!(data, b"- OP01-001\n- OP01-002\n");
+    }
+
+    #[test]
+    fn deserialize_records_returns_same() {
+        let records = vec![1, 2, 3];
+        for format in OutputFormat::ALL {
+            let data = format.serialize_records(&records, false).unwrap();
+            let actual: Vec<i32> = format.deserialize_records(&data).unwrap();
+            assert_eq!(actual, records);
+        }
+    }
+}
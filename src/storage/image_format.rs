@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::{io::Cursor, path::Path};
+
+use crate::error::Result;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ImageFormat {
+    /// Images as served by the site (PNG or JPEG)
+    #[default]
+    Original,
+    /// Lossless WebP images (.webp)
+    Webp,
+}
+
+/// Original and converted filenames of an image, recorded in `images/conversions.json`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImageConversion {
+    pub original: String,
+    pub converted: String,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            ImageFormat::Original => None,
+            ImageFormat::Webp => Some("webp"),
+        }
+    }
+
+    /// Replace the extension of `filename` with the format's (e.g. `OP01-001.png` -> `OP01-001.webp`)
+    pub fn apply_to_filename(self, filename: &str) -> String {
+        match self.extension() {
+            Some(ext) => {
+                let stem = Path::new(filename).file_stem().unwrap_or_default();
+                format!("{}.{}", stem.to_string_lossy(), ext)
+            }
+            None => filename.to_string(),
+        }
+    }
+
+    /// Transcode a downloaded PNG or JPEG image to the format
+    pub fn convert(self, img_data: Vec<u8>) -> Result<Vec<u8>> {
+        let output_format = match self {
+            ImageFormat::Original => return Ok(img_data),
+            ImageFormat::Webp => image::ImageFormat::WebP,
+        };
+
+        // The WebP encoder only takes 8-bit RGB(A) pixels
+        let img = image::load_from_memory(&img_data)?.into_rgba8();
+        let mut converted = Cursor::new(Vec::new());
+        img.write_to(&mut converted, output_format)?;
+
+        Ok(converted.into_inner())
+    }
+}
+
+/// Recompress a PNG image losslessly, keeping the original when that is not smaller. Other formats
+/// are returned as they are: JPEGs cannot be recompressed losslessly here and WebP images are
+/// already encoded losslessly by `convert`
+pub fn optimize(img_data: Vec<u8>) -> Result<Vec<u8>> {
+    if image::guess_format(&img_data).ok() != Some(image::ImageFormat::Png) {
+        return Ok(img_data);
+    }
+
+    let optimized = oxipng::optimize_from_memory(&img_data, &oxipng::Options::from_preset(2))?;
+    if optimized.len() < img_data.len() {
+        return Ok(optimized);
+    }
+
+    Ok(img_data)
+}
+
+/// `filename` with the extension of the format `img_data` is actually in, when its own extension
+/// is missing or names another format (e.g. a JPEG served as `OP01-001.png`)
+pub fn correct_extension(filename: &str, img_data: &[u8]) -> String {
+    let Ok(format) = image::guess_format(img_data) else {
+        return filename.to_string();
+    };
+    let extensions = format.extensions_str();
+    let path = Path::new(filename);
+    let matches = path.extension().is_some_and(|ext| {
+        extensions
+            .iter()
+            .any(|known| ext.to_string_lossy().eq_ignore_ascii_case(known))
+    });
+
+    match (matches, extensions.first()) {
+        (false, Some(ext)) => {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            format!("{}.{}", stem, ext)
+        }
+        _ => filename.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageEncoder;
+
+    #[test]
+    fn apply_to_filename_replaces_extension() {
+        assert_eq!(
+            ImageFormat::Webp.apply_to_filename("OP01-001_p1.png"),
+            "OP01-001_p1.webp"
+        );
+        assert_eq!(
+            ImageFormat::Original.apply_to_filename("OP01-001.png"),
+            "OP01-001.png"
+        );
+    }
+
+    #[test]
+    fn optimize_shrinks_png_losslessly() {
+        let img = image::RgbImage::from_fn(64, 64, |x, _| image::Rgb([(x / 8) as u8 * 30, 0, 0]));
+        let mut png = Cursor::new(Vec::new());
+        image::codecs::png::PngEncoder::new_with_quality(
+            &mut png,
+            image::codecs::png::CompressionType::Fast,
+            image::codecs::png::FilterType::NoFilter,
+        )
+        .write_image(&img, 64, 64, image::ExtendedColorType::Rgb8)
+        .unwrap();
+        let png = png.into_inner();
+
+        let optimized = optimize(png.clone()).unwrap();
+        assert!(optimized.len() < png.len());
+        assert_eq!(
+            image::load_from_memory(&optimized).unwrap().into_rgb8(),
+            img
+        );
+        assert_eq!(optimize(b"not a png".to_vec()).unwrap(), b"not a png");
+    }
+
+    #[test]
+    fn correct_extension_follows_image_bytes() {
+        let mut jpeg = Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(4, 4, image::Rgb([200, 30, 30]))
+            .write_to(&mut jpeg, image::ImageFormat::Jpeg)
+            .unwrap();
+        let jpeg = jpeg.into_inner();
+
+        assert_eq!(correct_extension("OP01-001.png", &jpeg), "OP01-001.jpg");
+        assert_eq!(correct_extension("OP01-001", &jpeg), "OP01-001.jpg");
+        assert_eq!(correct_extension("OP01-001.JPEG", &jpeg), "OP01-001.JPEG");
+        assert_eq!(correct_extension("OP01-001.png", b"image"), "OP01-001.png");
+    }
+
+    #[test]
+    fn convert_transcodes_png_to_webp() {
+        let mut png = Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(4, 4, image::Rgb([200, 30, 30]))
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+
+        let webp = ImageFormat::Webp.convert(png.into_inner()).unwrap();
+        assert_eq!(
+            image::guess_format(&webp).unwrap(),
+            image::ImageFormat::WebP
+        );
+        assert!(ImageFormat::Webp.convert(b"not an image".to_vec()).is_err());
+    }
+}
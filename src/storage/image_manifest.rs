@@ -0,0 +1,149 @@
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+
+use crate::error::Result;
+
+/// Image of a card as written to the store, recorded in `images/manifest.json`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImageEntry {
+    /// Path of the image relative to the store (or filename of the image object)
+    pub filename: String,
+    /// Size of the file
+    #[serde(default)]
+    pub bytes: u64,
+    /// SHA-256 of the file, as in `SHA256SUMS`
+    #[serde(default)]
+    pub sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Format of the image read from its bytes (e.g. `png`, `jpeg`, `webp`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Perceptual difference hash of the image (16 hex digits), see `dhash`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dhash: Option<String>,
+    /// Variant of the image picked by `--image-quality best` (e.g. `served`, `unsized`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    #[serde(flatten)]
+    pub validators: ImageValidators,
+    /// Generated by `--image-placeholders` because the image failed to download
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub placeholder: bool,
+}
+
+/// HTTP validators the site served an image with, sent back by `--check-images` to only download
+/// it again if it changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImageValidators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl ImageValidators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+impl ImageEntry {
+    /// Entry of an image written as `filename`, leaving what needs decoding empty when `img_data`
+    /// is not an image
+    pub fn new(filename: &str, img_data: &[u8]) -> Self {
+        let format = image::guess_format(img_data).ok();
+        let dimensions = image::ImageReader::new(Cursor::new(img_data))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_dimensions().ok());
+
+        Self {
+            filename: filename.to_string(),
+            bytes: img_data.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(img_data)),
+            width: dimensions.map(|(width, _)| width),
+            height: dimensions.map(|(_, height)| height),
+            format: format.map(|format| format!("{:?}", format).to_lowercase()),
+            dhash: dhash(img_data).ok().map(|dhash| format!("{:016x}", dhash)),
+            variant: None,
+            validators: ImageValidators::default(),
+            placeholder: false,
+        }
+    }
+}
+
+/// 64-bit difference hash of an image: shrunk to 9x8 grayscale pixels, one bit per pair of
+/// horizontally adjacent pixels, set when the left one is brighter. Near-identical artworks
+/// (re-encodes, resizes, alt-art reprints) differ by a few bits only.
+pub fn dhash(img_data: &[u8]) -> Result<u64> {
+    let pixels = image::load_from_memory(img_data)?
+        .resize_exact(9, 8, FilterType::Triangle)
+        .into_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if pixels.get_pixel(x, y)[0] > pixels.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+
+    Ok(hash)
+}
+
+/// Number of differing bits between two hashes, a distance of 10 or less usually means
+/// the same artwork
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+
+    fn encode(img: &RgbImage, format: ImageFormat) -> Vec<u8> {
+        let mut data = Cursor::new(Vec::new());
+        img.write_to(&mut data, format).unwrap();
+        data.into_inner()
+    }
+
+    #[test]
+    fn new_describes_image_file() {
+        let png = encode(&RgbImage::new(90, 80), ImageFormat::Png);
+        let entry = ImageEntry::new("images/OP01-001.png", &png);
+
+        assert_eq!(entry.bytes, png.len() as u64);
+        assert_eq!(entry.sha256, format!("{:x}", Sha256::digest(&png)));
+        assert_eq!((entry.width, entry.height), (Some(90), Some(80)));
+        assert_eq!(entry.format.as_deref(), Some("png"));
+        assert!(entry.dhash.is_some());
+
+        let entry = ImageEntry::new("images/OP01-002.png", b"<html></html>");
+        assert_eq!(entry.bytes, 13);
+        assert_eq!((entry.width, entry.format, entry.dhash), (None, None, None));
+    }
+
+    #[test]
+    fn dhash_matches_re_encoded_images_only() {
+        let gradient = RgbImage::from_fn(90, 80, |x, y| Rgb([(x * 2) as u8, (y * 3) as u8, 40]));
+        let mirrored = RgbImage::from_fn(90, 80, |x, y| {
+            Rgb([((89 - x) * 2) as u8, (y * 3) as u8, 40])
+        });
+
+        let png = dhash(&encode(&gradient, ImageFormat::Png)).unwrap();
+        let jpeg = dhash(&encode(&gradient, ImageFormat::Jpeg)).unwrap();
+        let other = dhash(&encode(&mirrored, ImageFormat::Png)).unwrap();
+
+        assert!(hamming_distance(png, jpeg) <= 10);
+        assert!(hamming_distance(png, other) > 10);
+        assert!(dhash(b"<html>not found</html>").is_err());
+    }
+}
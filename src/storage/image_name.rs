@@ -0,0 +1,181 @@
+use std::{path::Path, str::FromStr};
+
+use crate::card::{Card, CardVariant};
+
+use crate::error::{Result, VegaError};
+
+const PLACEHOLDERS: [&str; 6] = [
+    "card_id", "base_id", "variant", "pack_id", "ext", "filename",
+];
+
+/// Variant placeholder value for the regular artwork of a card
+const BASE_VARIANT: &str = "base";
+
+/// How image files are named when no template is given
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ImageNames {
+    /// The site's filenames
+    #[default]
+    Site,
+    /// `<card_id>.<ext>`, with the variant suffix of alternate arts (e.g. `OP01-025_p1.png`)
+    CardId,
+}
+
+impl ImageNames {
+    /// Template equivalent to the naming scheme, `None` for the site's filenames
+    pub fn template(self) -> Option<ImageNameTemplate> {
+        match self {
+            ImageNames::Site => None,
+            ImageNames::CardId => Some(ImageNameTemplate {
+                template: String::from("{card_id}.{ext}"),
+            }),
+        }
+    }
+}
+
+/// Template for image file names, relative to the images directory
+/// (e.g. `{card_id}.{ext}` or `{pack_id}/{base_id}_{variant}.{ext}`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageNameTemplate {
+    template: String,
+}
+
+impl FromStr for ImageNameTemplate {
+    type Err = VegaError;
+
+    fn from_str(template: &str) -> Result<Self> {
+        if template.is_empty() {
+            return Err(VegaError::config("image name template is empty"));
+        }
+        if template.starts_with('/') || template.split('/').any(|part| part == "..") {
+            return Err(VegaError::config(format!(
+                "image name template must stay inside the images directory: {}",
+                template
+            )));
+        }
+
+        let mut used = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                return Err(VegaError::config(format!(
+                    "unclosed `{{` in image name template: {}",
+                    template
+                )));
+            };
+
+            let placeholder = &rest[start + 1..start + len];
+            if !PLACEHOLDERS.contains(&placeholder) {
+                return Err(VegaError::config(format!(
+                    "unknown placeholder `{{{}}}` in image name template, expected one of: {}",
+                    placeholder,
+                    PLACEHOLDERS.map(|name| format!("{{{}}}", name)).join(", ")
+                )));
+            }
+            used.push(placeholder);
+            rest = &rest[start + len + 1..];
+        }
+
+        // Each card needs a name of its own, or images of a pack overwrite each other
+        let is_unique = used.contains(&"card_id")
+            || used.contains(&"filename")
+            || (used.contains(&"base_id") && used.contains(&"variant"));
+        if !is_unique {
+            return Err(VegaError::config(format!(
+                "image name template must name each card apart with {{card_id}}, {{filename}} or both {{base_id}} and {{variant}}: {}",
+                template
+            )));
+        }
+
+        Ok(Self {
+            template: template.to_string(),
+        })
+    }
+}
+
+impl ImageNameTemplate {
+    /// File name of the image of `card`, `filename` being the site's original one
+    pub fn render(&self, card: &Card, filename: &str) -> String {
+        let ext = Path::new(filename)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (base_id, variant) = CardVariant::split_id(&card.id);
+        let variant = variant.unwrap_or(BASE_VARIANT);
+
+        self.template
+            .replace("{card_id}", &card.id)
+            .replace("{base_id}", base_id)
+            .replace("{variant}", variant)
+            .replace("{pack_id}", &card.pack_id)
+            .replace("{ext}", &ext)
+            .replace("{filename}", filename)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardRarity;
+
+    fn test_card(id: &str) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id("569101")
+            .name("Roronoa Zoro")
+            .rarity(CardRarity::SuperRare)
+            .img_url(&format!("../images/cardlist/card/{}.png?240912", id))
+            .cost(3)
+            .power(5000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn render_replaces_placeholders() {
+        let template: ImageNameTemplate = "{pack_id}/{base_id}_{variant}.{ext}".parse().unwrap();
+
+        assert_eq!(
+            template.render(&test_card("OP01-025"), "OP01-025.png"),
+            "569101/OP01-025_base.png"
+        );
+        assert_eq!(
+            template.render(&test_card("OP01-025_p1"), "OP01-025_p1.png"),
+            "569101/OP01-025_p1.png"
+        );
+    }
+
+    #[test]
+    fn card_id_names_ignore_site_filename() {
+        let template = ImageNames::CardId.template().unwrap();
+
+        assert_eq!(
+            template.render(&test_card("OP01-025_p1"), "a8f3c2e1.jpg"),
+            "OP01-025_p1.jpg"
+        );
+        assert_eq!(ImageNames::Site.template(), None);
+    }
+
+    #[test]
+    fn parse_invalid_template_returns_err() {
+        assert!("".parse::<ImageNameTemplate>().is_err());
+        assert!("{card}.{ext}".parse::<ImageNameTemplate>().is_err());
+        assert!("{card_id.{ext}".parse::<ImageNameTemplate>().is_err());
+        assert!("../{card_id}.{ext}".parse::<ImageNameTemplate>().is_err());
+        assert!("/tmp/{card_id}.{ext}".parse::<ImageNameTemplate>().is_err());
+    }
+
+    #[test]
+    fn parse_template_naming_cards_alike_returns_err() {
+        assert!("{pack_id}.{ext}".parse::<ImageNameTemplate>().is_err());
+        assert!("{pack_id}/{base_id}.{ext}"
+            .parse::<ImageNameTemplate>()
+            .is_err());
+        assert!("{variant}.{ext}".parse::<ImageNameTemplate>().is_err());
+        assert!("{pack_id}/{filename}".parse::<ImageNameTemplate>().is_ok());
+        assert!("{base_id}/{variant}.{ext}"
+            .parse::<ImageNameTemplate>()
+            .is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    card::{effect::SegmentKind, Card},
+    localizer::Localizer,
+    storage::schema::SCHEMA_VERSION,
+};
+
+/// Bracketed term found in card effects, with the locale key it maps to if any
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct KeywordEntry {
+    pub kind: SegmentKind,
+    pub text: String,
+    pub canonical: Option<String>,
+    pub count: usize,
+}
+
+/// Every keyword, timing and condition used by the effects of a dataset
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeywordTaxonomy {
+    pub schema_version: u32,
+    pub keywords: Vec<KeywordEntry>,
+}
+
+impl KeywordTaxonomy {
+    pub fn collect<'a>(localizer: &Localizer, cards: impl IntoIterator<Item = &'a Card>) -> Self {
+        let mut counts: BTreeMap<(SegmentKind, &str), usize> = BTreeMap::new();
+        for card in cards {
+            for segment in &card.effect_segments {
+                if matches!(
+                    segment.kind,
+                    SegmentKind::Keyword | SegmentKind::Timing | SegmentKind::Condition
+                ) {
+                    *counts.entry((segment.kind, &segment.text)).or_default() += 1;
+                }
+            }
+        }
+
+        let keywords = counts
+            .into_iter()
+            .map(|((kind, text), count)| KeywordEntry {
+                kind,
+                text: text.to_string(),
+                canonical: match kind {
+                    SegmentKind::Keyword => localizer.match_keyword(text),
+                    SegmentKind::Timing => localizer.match_timing(text),
+                    _ => localizer.match_condition(text),
+                },
+                count,
+            })
+            .collect();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            keywords,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::EffectSegment, localizer::EN_LOCALE_RAW};
+
+    fn test_card(effect: &str, localizer: &Localizer) -> Card {
+        let mut card = Card::builder()
+            .id("OP01-001")
+            .pack_id("569101")
+            .name("Nami")
+            .effect(effect)
+            .build()
+            .unwrap();
+        card.effect_segments = EffectSegment::parse_all(localizer, effect);
+        card
+    }
+
+    #[test]
+    fn collect_counts_keywords_with_canonical_forms() {
+        let localizer: Localizer = toml::from_str(EN_LOCALE_RAW).unwrap();
+        let cards = [
+            test_card("[Rush]\n[On Play] Draw 1 card.", &localizer),
+            test_card("[Rush] [Sparkle]", &localizer),
+        ];
+
+        let taxonomy = KeywordTaxonomy::collect(&localizer, &cards);
+
+        assert_eq!(taxonomy.keywords.len(), 3);
+        assert_eq!(
+            taxonomy.keywords[0],
+            KeywordEntry {
+                kind: SegmentKind::Keyword,
+                text: String::from("Rush"),
+                canonical: Some(String::from("rush")),
+                count: 2,
+            }
+        );
+        assert_eq!(taxonomy.keywords[1].text, "Sparkle");
+        assert_eq!(taxonomy.keywords[1].canonical, None);
+        assert_eq!(taxonomy.keywords[2].kind, SegmentKind::Timing);
+        assert_eq!(taxonomy.keywords[2].canonical.as_deref(), Some("on_play"));
+    }
+}
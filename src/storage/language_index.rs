@@ -0,0 +1,109 @@
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use crate::{
+    card::{Card, CardId},
+    error::Result,
+    language::LanguageCode,
+    pack::PackId,
+    storage::{schema::SCHEMA_VERSION, FsBackend, OutputFormat, StoreBackend},
+};
+
+const LANGUAGE_INDEX_FILE: &str = "index.json";
+
+/// Top-level index of a multi-language root: for every card ID, the packs holding it in
+/// each language sub-store
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LanguageIndex {
+    pub schema_version: u32,
+    pub languages: BTreeSet<String>,
+    pub cards: BTreeMap<CardId, BTreeMap<String, BTreeSet<PackId>>>,
+}
+
+impl Default for LanguageIndex {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            languages: BTreeSet::new(),
+            cards: BTreeMap::new(),
+        }
+    }
+}
+
+impl LanguageIndex {
+    pub fn add_cards<'a>(
+        &mut self,
+        language: LanguageCode,
+        cards: impl IntoIterator<Item = &'a Card>,
+    ) {
+        let language = language.to_string();
+        for card in cards {
+            self.cards
+                .entry(card.id.clone())
+                .or_default()
+                .entry(language.clone())
+                .or_default()
+                .insert(card.pack_id.clone());
+        }
+
+        self.languages.insert(language);
+    }
+
+    /// Write the index at the top of a multi-language root
+    pub fn write(&self, root_dir: &Path, pretty: bool) -> Result<()> {
+        let path = root_dir.join(LANGUAGE_INDEX_FILE);
+        let data = OutputFormat::Json.serialize(self, pretty)?;
+        FsBackend.write(&path, &data)?;
+        debug!(
+            "wrote index of {} cards in {} languages to: {}",
+            self.cards.len(),
+            self.languages.len(),
+            path.display()
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardRarity;
+
+    fn test_card(id: &str, pack_id: &str) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id(pack_id)
+            .name("Roronoa Zoro")
+            .rarity(CardRarity::SuperRare)
+            .cost(3)
+            .power(5000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn add_cards_maps_ids_across_languages() {
+        let mut index = LanguageIndex::default();
+        index.add_cards(
+            LanguageCode::English,
+            &[
+                test_card("OP01-025", "569101"),
+                test_card("ST01-001", "569001"),
+            ],
+        );
+        index.add_cards(LanguageCode::Japanese, &[test_card("OP01-025", "550101")]);
+
+        assert_eq!(index.languages.len(), 2);
+        assert_eq!(index.cards["OP01-025"].len(), 2);
+        assert_eq!(index.cards["ST01-001"].len(), 1);
+        assert_eq!(
+            index.cards["OP01-025"]["japanese"],
+            BTreeSet::from(["550101".parse().unwrap()])
+        );
+    }
+}
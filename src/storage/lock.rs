@@ -0,0 +1,144 @@
+use log::{debug, warn};
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+use crate::error::{Context, Result, VegaError};
+
+pub const LOCK_FILE: &str = ".vega.lock";
+
+/// Advisory lock held by the process writing to a store, released on drop. Records the PID of
+/// that process so that locks left by a process that is gone can be taken over
+#[derive(Debug)]
+pub struct StoreLock {
+    path: PathBuf,
+}
+
+impl StoreLock {
+    pub fn acquire(path: &Path) -> Result<Self> {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir)
+                .with_context(|| format!("failed to create `{}`", parent_dir.display()))?;
+        }
+
+        match Self::create(path) {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let owner = fs::read_to_string(path).unwrap_or_default();
+                match owner.trim().parse::<u32>() {
+                    Ok(pid) if !is_running(pid) => {
+                        warn!(
+                            "removing stale store lock of process {} which is gone: {}",
+                            pid,
+                            path.display()
+                        );
+                        fs::remove_file(path).with_context(|| {
+                            format!("failed to remove stale lock: {}", path.display())
+                        })?;
+                        Self::create(path)
+                    }
+                    _ => {
+                        return Err(VegaError::storage(format!(
+                            "store is already in use by another vega process (pid {}), if that process is gone remove: {}",
+                            owner.trim(),
+                            path.display()
+                        )))
+                    }
+                }
+            }
+            result => result,
+        }
+        .with_context(|| format!("failed to create lock: {}", path.display()))
+    }
+
+    fn create(path: &Path) -> std::io::Result<Self> {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        writeln!(file, "{}", process::id())?;
+        debug!("acquired store lock: {}", path.display());
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+/// Whether process `pid` is running, assumed so when that cannot be told
+fn is_running(pid: u32) -> bool {
+    let proc_dir = Path::new("/proc");
+    if proc_dir.join("self").exists() {
+        return proc_dir.join(pid.to_string()).exists();
+    }
+
+    let status = if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+    } else {
+        Command::new("ps")
+            .args(["-p", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+    };
+    status.unwrap_or(true)
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        match fs::remove_file(&self.path) {
+            Ok(_) => debug!("released store lock: {}", self.path.display()),
+            Err(e) => warn!("failed to remove lock {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_twice_returns_err_until_released() {
+        let dir = std::env::temp_dir().join(format!("vega-test-lock-{}", process::id()));
+        let path = dir.join(".vega.lock");
+
+        let lock = StoreLock::acquire(&path).unwrap();
+        let err = StoreLock::acquire(&path).unwrap_err();
+        assert!(err.to_string().contains("already in use"));
+
+        drop(lock);
+        assert!(!path.exists());
+        assert!(StoreLock::acquire(&path).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn acquire_takes_over_lock_of_process_gone() {
+        let dir = std::env::temp_dir().join(format!("vega-test-stale-lock-{}", process::id()));
+        let path = dir.join(".vega.lock");
+
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert!(!is_running(pid));
+        assert!(is_running(process::id()));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, format!("{}\n", pid)).unwrap();
+        let lock = StoreLock::acquire(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n", process::id())
+        );
+
+        drop(lock);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    card::{Card, CardId},
+    pack::PackId,
+    storage::schema::SCHEMA_VERSION,
+};
+
+/// IDs of cards that disappeared from a pack since a previous pull
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Tombstones {
+    pub schema_version: u32,
+    pub packs: BTreeMap<PackId, BTreeSet<CardId>>,
+}
+
+impl Default for Tombstones {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            packs: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MergeStats {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+fn same_content(old: &Card, new: &Card) -> bool {
+    // A newer scrape of the same data is not a change
+    let mut old = old.clone();
+    old.scraped_at = new.scraped_at;
+
+    // Neither is a new cache-busting query string on the same image
+    if old.img_url_canonical.is_some() && old.img_url_canonical == new.img_url_canonical {
+        old.img_url = new.img_url.clone();
+        old.img_full_url = new.img_full_url.clone();
+    }
+
+    &old == new
+}
+
+/// Merge freshly scraped cards into the cards already stored for a pack.
+/// Unchanged cards are kept as they were, changed ones are replaced, new ones added and
+/// cards that are gone get their ID recorded in `tombstones`.
+pub fn merge_cards(
+    stored: Vec<Card>,
+    scraped: &[Card],
+    tombstones: &mut BTreeSet<CardId>,
+) -> (Vec<Card>, MergeStats) {
+    let mut stats = MergeStats::default();
+    let mut stored: HashMap<CardId, Card> = stored
+        .into_iter()
+        .map(|card| (card.id.clone(), card))
+        .collect();
+
+    let mut merged = Vec::with_capacity(scraped.len());
+    for card in scraped {
+        tombstones.remove(&card.id);
+
+        match stored.remove(&card.id) {
+            Some(old) if same_content(&old, card) => {
+                stats.unchanged += 1;
+                merged.push(old);
+            }
+            Some(_) => {
+                stats.updated += 1;
+                merged.push(card.clone());
+            }
+            None => {
+                stats.added += 1;
+                merged.push(card.clone());
+            }
+        }
+    }
+
+    stats.removed = stored.len();
+    tombstones.extend(stored.into_keys());
+
+    (merged, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_card(id: &str, power: i32) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id("569101")
+            .name("Nami")
+            .cost(1)
+            .power(power)
+            .counter(1000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn merge_cards_records_changes_and_tombstones() {
+        let stored = vec![
+            test_card("OP01-001", 1000),
+            test_card("OP01-002", 2000),
+            test_card("OP01-003", 3000),
+        ];
+        let scraped = vec![
+            test_card("OP01-001", 1000),
+            test_card("OP01-002", 5000),
+            test_card("OP01-004", 4000),
+        ];
+
+        let mut tombstones = BTreeSet::from(["OP01-004".parse().unwrap()]);
+        let (merged, stats) = merge_cards(stored, &scraped, &mut tombstones);
+
+        assert_eq!(
+            stats,
+            MergeStats {
+                added: 1,
+                updated: 1,
+                unchanged: 1,
+                removed: 1,
+            }
+        );
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[1].power, Some(5000));
+        assert_eq!(tombstones, BTreeSet::from(["OP01-003".parse().unwrap()]));
+    }
+
+    #[test]
+    fn merge_cards_ignores_new_scrape_timestamp() {
+        let stored = vec![test_card("OP01-001", 1000)];
+        let mut scraped = test_card("OP01-001", 1000);
+        scraped.scraped_at = Some(chrono::Utc::now());
+
+        let (_, stats) = merge_cards(stored, &[scraped], &mut BTreeSet::new());
+        assert_eq!(stats.unchanged, 1);
+    }
+
+    #[test]
+    fn merge_cards_ignores_new_image_query_string() {
+        let mut stored = test_card("OP01-001", 1000);
+        stored.img_url_canonical = Some(String::from("/images/cardlist/card/OP01-001.png"));
+        let mut scraped = stored.clone();
+        scraped.img_url = String::from("../images/cardlist/card/OP01-001.png?250301");
+
+        let (merged, stats) = merge_cards(vec![stored], &[scraped], &mut BTreeSet::new());
+        assert_eq!(stats.unchanged, 1);
+        assert_eq!(merged[0].img_url, "../images/cardlist/card/OP01-001.png");
+    }
+}
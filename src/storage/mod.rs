@@ -0,0 +1,2541 @@
+use chrono::{DateTime, Local};
+use log::{debug, info, trace, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+};
+
+use crate::{
+    card::{text, variant, Card, CardId, CardOrder, TextNormalization},
+    error::{Context, Result, VegaError},
+    language::LanguageCode,
+    localizer::Localizer,
+    pack::{Pack, PackId},
+};
+
+pub mod archive;
+pub mod atomic;
+pub mod backend;
+pub mod checksums;
+pub mod compression;
+pub mod dataset;
+pub mod format;
+pub mod image_format;
+pub mod image_manifest;
+pub mod image_name;
+pub mod keywords;
+pub mod language_index;
+pub mod lock;
+pub mod merge;
+pub mod options;
+pub mod placeholder;
+pub mod reprints;
+pub mod schema;
+
+pub use self::archive::{ArchiveBackend, ArchiveFormat};
+pub use self::backend::{FsBackend, StoreBackend};
+pub use self::compression::Compression;
+pub use self::dataset::Dataset;
+pub use self::format::OutputFormat;
+pub use self::image_format::{ImageConversion, ImageFormat};
+pub use self::image_manifest::{ImageEntry, ImageValidators};
+pub use self::image_name::{ImageNameTemplate, ImageNames};
+pub use self::keywords::KeywordTaxonomy;
+pub use self::language_index::LanguageIndex;
+pub use self::lock::StoreLock;
+use self::lock::LOCK_FILE;
+use self::merge::Tombstones;
+pub use self::options::StoreOptions;
+pub use self::reprints::Reprints;
+use self::schema::{CardsDocument, CardsFile, PacksDocument, PacksFile, SCHEMA_VERSION};
+
+const VEGA_META_FILE: &str = "vega.meta.toml";
+const IMAGE_INDEX_FILE: &str = "index.json";
+const IMAGE_CONVERSIONS_FILE: &str = "conversions.json";
+const IMAGE_MANIFEST_FILE: &str = "manifest.json";
+const ERRORS_FILE: &str = "errors.json";
+const DATASET_FILE: &str = "dataset";
+const TOMBSTONES_FILE: &str = "tombstones";
+const REPRINTS_FILE: &str = "reprints";
+const KEYWORDS_FILE: &str = "keywords";
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+pub struct DataStore {
+    backend: Box<dyn StoreBackend>,
+    root_dir: PathBuf,
+    #[allow(dead_code)]
+    language: LanguageCode,
+    image_layout: ImageLayout,
+    image_name_template: Option<ImageNameTemplate>,
+    image_format: ImageFormat,
+    compression: Compression,
+    format: OutputFormat,
+    pretty: bool,
+    single_file: bool,
+    layout: StoreLayout,
+    scrape_timestamps: bool,
+    merge: bool,
+    group_variants: bool,
+    text_normalization: Vec<TextNormalization>,
+    include_raw: bool,
+    card_order: CardOrder,
+    force_images: bool,
+    check_images: bool,
+    image_placeholders: bool,
+    materialize_images: bool,
+    optimize_images: bool,
+    objects_dir: Option<PathBuf>,
+    shared_images: Option<Arc<SharedImages>>,
+    image_index: Mutex<BTreeMap<String, String>>,
+    image_conversions: Mutex<BTreeMap<String, ImageConversion>>,
+    image_manifest: Mutex<BTreeMap<String, ImageEntry>>,
+    // Filename of every image written by this store, by SHA-256
+    written_images: Mutex<HashMap<String, String>>,
+    // Checksums of `SHA256SUMS`, read on first use, updated with every file written
+    checksums: OnceLock<Mutex<BTreeMap<String, String>>>,
+    strict_checksums: bool,
+    lock: Option<StoreLock>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "cli", value(rename_all = "kebab-case"))]
+pub enum ImageLayout {
+    /// One file per card, named after the site's filename
+    #[default]
+    Flat,
+    /// One file per distinct image, named after its SHA-256, with a card to hash index
+    ContentAddressed,
+}
+
+/// Images written by any store of a multi-language pull, by SHA-256, so identical
+/// artwork can be hardlinked instead of stored again
+#[derive(Debug, Default)]
+pub struct SharedImages {
+    paths: Mutex<HashMap<String, PathBuf>>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum PullMode {
+    All,
+    PackListOnly,
+    SinglePack,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VegaMetaStats {
+    schema_version: u32,
+    vegapull_version: String,
+    language: LanguageCode,
+    pull_start: DateTime<Local>,
+    pull_duration_ms: usize,
+    images_included: bool,
+    mode: PullMode,
+    packs: BTreeSet<PackId>,
+    // Path of the card back image in the store
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card_back: Option<String>,
+    // SHA-256 of every locale file used, by file name
+    locale_files: BTreeMap<String, String>,
+    pack_results: BTreeMap<PackId, PackStats>,
+}
+
+/// What a pull covered for a single pack
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct PackStats {
+    pub cards: usize,
+    pub images: usize,
+    pub duration_ms: usize,
+    pub errors: usize,
+    /// Fields left empty on cards that were still scraped
+    pub warnings: usize,
+    /// Unexpected card IDs found while validating the pack
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub anomalies: Vec<String>,
+    /// Cards whose images could not be downloaded, with the reason
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub image_errors: Vec<String>,
+    /// Cards whose images are too small to be card art (e.g. "no image" placeholders), stored
+    /// anyway
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub small_images: Vec<String>,
+    /// Cards whose stored images the site reported as changed with `--check-images`, downloaded
+    /// again
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed_images: Vec<String>,
+}
+
+impl VegaMetaStats {
+    pub fn new(
+        language: LanguageCode,
+        pull_start: DateTime<Local>,
+        pull_duration_ms: usize,
+        images_included: bool,
+        mode: PullMode,
+        packs: HashSet<PackId>,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            vegapull_version: env!("CARGO_PKG_VERSION").to_string(),
+            language,
+            pull_start,
+            pull_duration_ms,
+            images_included,
+            mode,
+            packs: packs.into_iter().collect(),
+            card_back: None,
+            locale_files: BTreeMap::new(),
+            pack_results: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_locale(mut self, localizer: &Localizer) -> Self {
+        if let Some(source) = &localizer.source {
+            self.locale_files
+                .insert(source.file_name.clone(), source.sha256.clone());
+        }
+        self
+    }
+
+    pub fn with_pack_results(mut self, pack_results: BTreeMap<PackId, PackStats>) -> Self {
+        self.pack_results = pack_results;
+        self
+    }
+
+    pub fn with_card_back(mut self, card_back: Option<String>) -> Self {
+        self.card_back = card_back;
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "cli", value(rename_all = "kebab-case"))]
+pub enum StoreLayout {
+    /// All data files in `json/` and all images in `images/`
+    #[default]
+    Flat,
+    /// One directory per pack holding its cards and images (`packs/<pack_id>/`)
+    PerPack,
+}
+
+#[derive(Clone, Copy)]
+pub enum StoreLocation<'a> {
+    RootDir,
+    LockFile,
+    VegaMetaFile,
+    ChecksumsFile,
+    PacksListFile,
+    ImagesDir,
+    ImageObjectsDir,
+    ImageIndexFile,
+    ImageConversionsFile,
+    ImageManifestFile,
+    ErrorsFile,
+    JsonDir,
+    DatasetFile,
+    TombstonesFile,
+    ReprintsFile,
+    KeywordsFile,
+    CardsFile(&'a PackId),
+    ImageFile(&'a Card),
+    ImageObjectFile(&'a str),
+    PacksDir,
+    PackDir(&'a str),
+    PackFile(&'a str),
+    PackImagesDir(&'a str),
+    PackArtworkDir,
+    PackArtworkFile(&'a str),
+    CardBackFile(&'a str),
+}
+
+impl StoreLocation<'_> {
+    fn is_data_file(&self) -> bool {
+        matches!(
+            self,
+            StoreLocation::PacksListFile
+                | StoreLocation::DatasetFile
+                | StoreLocation::TombstonesFile
+                | StoreLocation::ReprintsFile
+                | StoreLocation::KeywordsFile
+                | StoreLocation::CardsFile(_)
+                | StoreLocation::PackFile(_)
+        )
+    }
+}
+
+impl DataStore {
+    pub fn new(root_dir: &Path, language: LanguageCode) -> Self {
+        Self {
+            backend: Box::new(FsBackend),
+            root_dir: root_dir.to_path_buf(),
+            language,
+            image_layout: ImageLayout::default(),
+            image_name_template: None,
+            image_format: ImageFormat::default(),
+            compression: Compression::default(),
+            format: OutputFormat::default(),
+            pretty: false,
+            single_file: false,
+            layout: StoreLayout::default(),
+            scrape_timestamps: false,
+            merge: false,
+            group_variants: false,
+            text_normalization: Vec::new(),
+            include_raw: false,
+            card_order: CardOrder::default(),
+            force_images: false,
+            check_images: false,
+            image_placeholders: false,
+            materialize_images: false,
+            optimize_images: false,
+            objects_dir: None,
+            shared_images: None,
+            image_index: Mutex::new(BTreeMap::new()),
+            image_conversions: Mutex::new(BTreeMap::new()),
+            image_manifest: Mutex::new(BTreeMap::new()),
+            written_images: Mutex::new(HashMap::new()),
+            checksums: OnceLock::new(),
+            strict_checksums: false,
+            lock: None,
+        }
+    }
+
+    /// Store to write to, locked against other writers
+    pub fn create(root_dir: &Path, language: LanguageCode, options: &StoreOptions) -> Result<Self> {
+        let store = Self::new(root_dir, language);
+        let store = match options.archive {
+            Some(format) => store.with_backend(Box::new(ArchiveBackend::create(root_dir, format)?)),
+            // Archives are renamed into place once complete, loose files need a lock
+            None => store.with_lock()?,
+        };
+
+        Ok(store.with_options(options))
+    }
+
+    /// Existing store to read only (e.g. to export it), not locked so that a pull can write to
+    /// it meanwhile
+    pub fn open(root_dir: &Path, language: LanguageCode, options: &StoreOptions) -> Result<Self> {
+        if !root_dir.is_dir() {
+            return Err(VegaError::storage(format!(
+                "no store found at: {}",
+                root_dir.display()
+            )));
+        }
+
+        Ok(Self::new(root_dir, language).with_options(options))
+    }
+
+    /// Same store, with every option of `options` but its archive (see `create`)
+    pub fn with_options(self, options: &StoreOptions) -> Self {
+        self.with_image_layout(options.image_layout)
+            .with_image_name_template(options.image_name_template.clone())
+            .with_image_format(options.image_format)
+            .with_compression(options.compression)
+            .with_format(options.format)
+            .with_pretty(options.pretty)
+            .with_single_file(options.single_file)
+            .with_layout(options.layout)
+            .with_scrape_timestamps(options.scrape_timestamps)
+            .with_merge(options.merge)
+            .with_group_variants(options.group_variants)
+            .with_text_normalization(&options.text_normalization)
+            .with_include_raw(options.include_raw)
+            .with_card_order(options.card_order)
+            .with_force_images(options.force_images)
+            .with_check_images(options.check_images)
+            .with_image_placeholders(options.image_placeholders)
+            .with_materialize_images(options.materialize_images)
+            .with_optimize_images(options.optimize_images)
+            .with_strict_checksums(options.strict_checksums)
+    }
+
+    /// Convert downloaded images to `image_format` before writing them
+    pub fn with_image_format(mut self, image_format: ImageFormat) -> Self {
+        self.image_format = image_format;
+        self
+    }
+
+    /// Download images again even if they are already stored (see `missing_images`)
+    pub fn with_force_images(mut self, force_images: bool) -> Self {
+        self.force_images = force_images;
+        self
+    }
+
+    /// Ask the site whether stored images changed instead of keeping them (see `images_to_check`)
+    pub fn with_check_images(mut self, check_images: bool) -> Self {
+        self.check_images = check_images;
+        self
+    }
+
+    /// Write placeholders for cards whose images fail (see `write_image_placeholders`)
+    pub fn with_image_placeholders(mut self, image_placeholders: bool) -> Self {
+        self.image_placeholders = image_placeholders;
+        self
+    }
+
+    /// Write a file for every card even when its image is byte-identical to one already written,
+    /// instead of pointing its manifest entry to that one
+    pub fn with_materialize_images(mut self, materialize_images: bool) -> Self {
+        self.materialize_images = materialize_images;
+        self
+    }
+
+    /// Recompress PNG images losslessly before writing them, slower but smaller
+    pub fn with_optimize_images(mut self, optimize_images: bool) -> Self {
+        self.optimize_images = optimize_images;
+        self
+    }
+
+    /// Fail to read data files that do not match `SHA256SUMS` instead of warning about them
+    pub fn with_strict_checksums(mut self, strict_checksums: bool) -> Self {
+        self.strict_checksums = strict_checksums;
+        self
+    }
+
+    /// Merge written cards into the existing ones and keep track of removed cards
+    pub fn with_merge(mut self, merge: bool) -> Self {
+        self.merge = merge;
+        self
+    }
+
+    /// List alternate printings under their base card instead of as cards of their own
+    pub fn with_group_variants(mut self, group_variants: bool) -> Self {
+        self.group_variants = group_variants;
+        self
+    }
+
+    /// Normalize the text of written cards (name, effect, trigger and types)
+    pub fn with_text_normalization(mut self, steps: &[TextNormalization]) -> Self {
+        self.text_normalization = steps.to_vec();
+        self
+    }
+
+    /// Keep the labels of cards as shown on the site next to their parsed values
+    pub fn with_include_raw(mut self, include_raw: bool) -> Self {
+        self.include_raw = include_raw;
+        self
+    }
+
+    /// Order of the cards inside data files
+    pub fn with_card_order(mut self, card_order: CardOrder) -> Self {
+        self.card_order = card_order;
+        self
+    }
+
+    /// Keep `scraped_at` on every card (output then differs between otherwise identical pulls)
+    pub fn with_scrape_timestamps(mut self, scrape_timestamps: bool) -> Self {
+        self.scrape_timestamps = scrape_timestamps;
+        self
+    }
+
+    /// Sub-store of a multi-language root (`<root>/<language>/`)
+    pub fn for_language(
+        root_dir: &Path,
+        language: LanguageCode,
+        options: &StoreOptions,
+    ) -> Result<Self> {
+        let mut store = Self::create(&root_dir.join(language.to_path()), language, options)?;
+
+        // Image objects are shared by all languages so identical artwork is stored once
+        // (archives are self-contained so they keep their own)
+        if options.archive.is_none() {
+            store = store.with_objects_dir(&root_dir.join("images/objects/"));
+        }
+
+        Ok(store)
+    }
+
+    /// Hardlink images identical to one already written by another store sharing `shared_images`
+    pub fn with_shared_images(mut self, shared_images: Arc<SharedImages>) -> Self {
+        self.shared_images = Some(shared_images);
+        self
+    }
+
+    /// Hold the store lock until the store is dropped, so concurrent writers fail early
+    pub fn with_lock(mut self) -> Result<Self> {
+        let path = self.get_path(StoreLocation::LockFile)?;
+        self.lock = Some(StoreLock::acquire(&path)?);
+        Ok(self)
+    }
+
+    pub fn with_backend(mut self, backend: Box<dyn StoreBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: StoreLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Indent JSON output, for readable diffs between pulls
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Write all packs and cards into a single dataset file instead of one file per pack
+    pub fn with_single_file(mut self, single_file: bool) -> Self {
+        self.single_file = single_file;
+        self
+    }
+
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn with_image_layout(mut self, image_layout: ImageLayout) -> Self {
+        self.image_layout = image_layout;
+        self
+    }
+
+    /// Name images after a template instead of the site's filename (flat image layout only)
+    pub fn with_image_name_template(mut self, template: Option<ImageNameTemplate>) -> Self {
+        self.image_name_template = template;
+        self
+    }
+
+    /// Store image objects in a shared directory (e.g. one per multi-language root)
+    pub fn with_objects_dir(mut self, objects_dir: &Path) -> Self {
+        self.objects_dir = Some(objects_dir.to_path_buf());
+        self
+    }
+
+    pub fn get_path(&self, location: StoreLocation) -> Result<PathBuf> {
+        let path = self.get_raw_path(location)?;
+        if location.is_data_file() {
+            return Ok(self.compression.apply_to_path(&path));
+        }
+
+        Ok(path)
+    }
+
+    /// Path of `location` before any compression extension is applied
+    fn get_raw_path(&self, location: StoreLocation) -> Result<PathBuf> {
+        let path =
+            match location {
+                StoreLocation::RootDir => self.root_dir.clone(),
+                StoreLocation::VegaMetaFile => {
+                    self.get_path(StoreLocation::RootDir)?.join(VEGA_META_FILE)
+                }
+                StoreLocation::LockFile => self.get_path(StoreLocation::RootDir)?.join(LOCK_FILE),
+                StoreLocation::ChecksumsFile => {
+                    self.get_path(StoreLocation::RootDir)?.join(CHECKSUMS_FILE)
+                }
+                StoreLocation::ImagesDir => self.get_path(StoreLocation::RootDir)?.join("images/"),
+                StoreLocation::ImageObjectsDir => match &self.objects_dir {
+                    Some(objects_dir) => objects_dir.clone(),
+                    None => self.get_path(StoreLocation::ImagesDir)?.join("objects/"),
+                },
+                StoreLocation::ImageIndexFile => self
+                    .get_path(StoreLocation::ImagesDir)?
+                    .join(IMAGE_INDEX_FILE),
+                StoreLocation::ImageConversionsFile => self
+                    .get_path(StoreLocation::ImagesDir)?
+                    .join(IMAGE_CONVERSIONS_FILE),
+                StoreLocation::ImageManifestFile => self
+                    .get_path(StoreLocation::ImagesDir)?
+                    .join(IMAGE_MANIFEST_FILE),
+                StoreLocation::ErrorsFile => {
+                    self.get_path(StoreLocation::RootDir)?.join(ERRORS_FILE)
+                }
+                StoreLocation::JsonDir => self.get_path(StoreLocation::RootDir)?.join("json/"),
+                StoreLocation::PacksListFile => {
+                    let parent_dir = match self.layout {
+                        StoreLayout::Flat => self.get_path(StoreLocation::JsonDir)?,
+                        StoreLayout::PerPack => self.get_path(StoreLocation::PacksDir)?,
+                    };
+                    parent_dir.join(format!("packs.{}", self.format.extension()))
+                }
+                StoreLocation::DatasetFile => self.get_path(StoreLocation::JsonDir)?.join(format!(
+                    "{}.{}",
+                    DATASET_FILE,
+                    self.format.extension()
+                )),
+                StoreLocation::TombstonesFile => {
+                    let parent_dir = match self.layout {
+                        StoreLayout::PerPack if !self.single_file => {
+                            self.get_path(StoreLocation::PacksDir)?
+                        }
+                        _ => self.get_path(StoreLocation::JsonDir)?,
+                    };
+                    parent_dir.join(format!("{}.{}", TOMBSTONES_FILE, self.format.extension()))
+                }
+                StoreLocation::ReprintsFile => self
+                    .get_path(StoreLocation::JsonDir)?
+                    .join(format!("{}.{}", REPRINTS_FILE, self.format.extension())),
+                StoreLocation::KeywordsFile => self
+                    .get_path(StoreLocation::JsonDir)?
+                    .join(format!("{}.{}", KEYWORDS_FILE, self.format.extension())),
+                StoreLocation::CardsFile(pack_id) => self.get_cards_filename(pack_id)?,
+                StoreLocation::ImageFile(card) => {
+                    let filename = self.get_image_name(card)?;
+                    self.get_path(StoreLocation::PackImagesDir(&card.pack_id))?
+                        .join(filename)
+                }
+                StoreLocation::ImageObjectFile(filename) => self
+                    .get_path(StoreLocation::ImageObjectsDir)?
+                    .join(filename),
+                StoreLocation::PacksDir => self.get_path(StoreLocation::RootDir)?.join("packs/"),
+                StoreLocation::PackDir(pack_id) => self
+                    .get_path(StoreLocation::PacksDir)?
+                    .join(format!("{}/", pack_id)),
+                StoreLocation::PackFile(pack_id) => self
+                    .get_path(StoreLocation::PackDir(pack_id))?
+                    .join(format!("pack.{}", self.format.extension())),
+                StoreLocation::PackArtworkDir => {
+                    self.get_path(StoreLocation::ImagesDir)?.join("packs/")
+                }
+                StoreLocation::PackArtworkFile(filename) => {
+                    self.get_path(StoreLocation::PackArtworkDir)?.join(filename)
+                }
+                StoreLocation::CardBackFile(filename) => {
+                    self.get_path(StoreLocation::ImagesDir)?.join(filename)
+                }
+                StoreLocation::PackImagesDir(pack_id) => match self.layout {
+                    StoreLayout::Flat => self.get_path(StoreLocation::ImagesDir)?,
+                    StoreLayout::PerPack => self
+                        .get_path(StoreLocation::PackDir(pack_id))?
+                        .join("images/"),
+                },
+            };
+
+        Ok(path.to_path_buf())
+    }
+
+    fn get_cards_filename(&self, pack_id: &PackId) -> Result<PathBuf> {
+        let path = match self.layout {
+            StoreLayout::Flat => self.get_path(StoreLocation::JsonDir)?.join(format!(
+                "cards_{}.{}",
+                pack_id,
+                self.format.extension()
+            )),
+            StoreLayout::PerPack => self
+                .get_path(StoreLocation::PackDir(pack_id))?
+                .join(format!("cards.{}", self.format.extension())),
+        };
+
+        Ok(path)
+    }
+
+    pub fn get_img_filename(card: &Card) -> Result<String> {
+        let img_file_name = Self::get_url_filename(&card.img_url)?;
+
+        debug!("filename for `{}` is: {}", card.id, img_file_name);
+        Ok(img_file_name.to_string())
+    }
+
+    fn get_url_filename(img_url: &str) -> Result<&str> {
+        let last_slash_pos = img_url
+            .rfind('/')
+            .ok_or_else(|| VegaError::storage(format!("expected to find `/` in: {}", img_url)))?;
+
+        Ok(match img_url.find('?') {
+            Some(quest_mark_pos) => &img_url[last_slash_pos + 1..quest_mark_pos],
+            None => &img_url[last_slash_pos + 1..],
+        })
+    }
+
+    /// Name of the image file of `card`, following the image name template if any
+    fn get_image_name(&self, card: &Card) -> Result<String> {
+        let filename = self
+            .image_format
+            .apply_to_filename(&Self::get_img_filename(card)?);
+        Ok(match &self.image_name_template {
+            Some(template) => template.render(card, &filename),
+            None => filename,
+        })
+    }
+
+    /// Write the list of packs along with the cards of every pack
+    pub fn write_all(
+        &self,
+        packs: &HashMap<PackId, Pack>,
+        cards: &HashMap<PackId, Vec<Card>>,
+    ) -> Result<()> {
+        if self.single_file {
+            let mut dataset = match self.merge {
+                true => self.read_dataset_or_default()?,
+                false => Dataset::default(),
+            };
+            dataset.set_packs(packs);
+            for (pack_id, cards) in cards {
+                let cards = self.merge_cards(pack_id, cards, dataset.cards(pack_id).cloned())?;
+                dataset.set_cards(pack_id, &cards);
+            }
+
+            return self.write_dataset(&dataset);
+        }
+
+        self.write_packs(packs)?;
+        for (pack_id, cards) in cards {
+            self.write_cards(pack_id, cards)?;
+            debug!("wrote cards for: `{}`", pack_id);
+        }
+
+        Ok(())
+    }
+
+    pub fn write_dataset(&self, dataset: &Dataset) -> Result<()> {
+        let path = self.get_path(StoreLocation::DatasetFile)?;
+        debug!(
+            "about to write {} packs to dataset file: `{}`",
+            dataset.packs.len(),
+            path.display()
+        );
+
+        let mut dataset = dataset.clone();
+        for entry in dataset.packs.values_mut() {
+            self.normalize_cards(&mut entry.cards);
+        }
+
+        let data = self.format.serialize(&dataset, self.pretty)?;
+        self.write_data_file(&path, &data)?;
+        debug!("wrote dataset to file");
+
+        Ok(())
+    }
+
+    pub fn read_dataset(&self) -> Result<Dataset> {
+        let data = self.read_data_file(StoreLocation::DatasetFile)?;
+        let mut dataset: Dataset = self.format.deserialize(&data)?;
+
+        schema::ensure_supported(dataset.schema_version)?;
+        dataset.schema_version = SCHEMA_VERSION;
+        Ok(dataset)
+    }
+
+    fn read_dataset_or_default(&self) -> Result<Dataset> {
+        if self.has_data_file(StoreLocation::DatasetFile)? {
+            return self.read_dataset();
+        }
+
+        Ok(Dataset::default())
+    }
+
+    pub fn write_packs(&self, packs: &HashMap<PackId, Pack>) -> Result<()> {
+        if self.single_file {
+            let mut dataset = self.read_dataset_or_default()?;
+            dataset.set_packs(packs);
+            return self.write_dataset(&dataset);
+        }
+
+        let path = self.get_path(StoreLocation::PacksListFile)?;
+        debug!(
+            "about to write {} packs to file: `{}`",
+            packs.len(),
+            path.display()
+        );
+
+        trace!("serialize data: `{:?}`", packs);
+        let data = match self.format {
+            OutputFormat::Ndjson => {
+                let mut packs: Vec<&Pack> = packs.values().collect();
+                packs.sort_by(|a, b| a.id.cmp(&b.id));
+                self.format.serialize_records(&packs, self.pretty)?
+            }
+            _ => self
+                .format
+                .serialize(&PacksFile::new(packs.clone()), self.pretty)?,
+        };
+
+        self.write_data_file(&path, &data)?;
+        debug!("wrote packs data to file");
+
+        if self.layout == StoreLayout::PerPack {
+            for (pack_id, pack) in packs {
+                let path = self.get_path(StoreLocation::PackFile(pack_id))?;
+                self.write_data_file(&path, &self.format.serialize(pack, self.pretty)?)?;
+            }
+            debug!("wrote pack data to every pack directory");
+        }
+
+        Ok(())
+    }
+
+    pub fn read_packs(&self) -> Result<HashMap<PackId, Pack>> {
+        if self.single_file {
+            return Ok(self.read_dataset()?.packs());
+        }
+
+        let data = self.read_data_file(StoreLocation::PacksListFile)?;
+        let packs = match self.format {
+            OutputFormat::Ndjson => self
+                .format
+                .deserialize_records::<Pack>(&data)?
+                .into_iter()
+                .map(|pack| (pack.id.clone(), pack))
+                .collect(),
+            _ => self
+                .format
+                .deserialize::<PacksDocument>(&data)?
+                .migrate()?
+                .packs
+                .into_iter()
+                .collect(),
+        };
+
+        Ok(packs)
+    }
+
+    pub fn write_cards(&self, pack_id: &PackId, cards: &[Card]) -> Result<()> {
+        if self.single_file {
+            let mut dataset = self.read_dataset_or_default()?;
+            let cards = self.merge_cards(pack_id, cards, dataset.cards(pack_id).cloned())?;
+            dataset.set_cards(pack_id, &cards);
+            return self.write_dataset(&dataset);
+        }
+
+        let stored = match self.merge && self.has_data_file(StoreLocation::CardsFile(pack_id))? {
+            true => Some(self.read_cards(pack_id)?),
+            false => None,
+        };
+        let mut cards = self.merge_cards(pack_id, cards, stored)?;
+
+        let path = self.get_path(StoreLocation::CardsFile(pack_id))?;
+        debug!(
+            "about to write {} cards from `{}` to file: `{}`",
+            cards.len(),
+            &pack_id,
+            path.display()
+        );
+
+        trace!("serialize data: `{:?}`", cards);
+        self.normalize_cards(&mut cards);
+
+        let data = match self.format {
+            // NDJSON records stay bare cards, the schema version is kept in vega.meta.toml
+            OutputFormat::Ndjson => self.format.serialize_records(&cards, self.pretty)?,
+            _ => self.format.serialize(&CardsFile::new(cards), self.pretty)?,
+        };
+
+        self.write_data_file(&path, &data)?;
+        debug!("wrote cards data to file");
+
+        Ok(())
+    }
+
+    pub fn read_cards(&self, pack_id: &PackId) -> Result<Vec<Card>> {
+        if self.single_file {
+            let dataset = self.read_dataset()?;
+            let cards = dataset.cards(pack_id).ok_or_else(|| {
+                VegaError::storage(format!("pack `{}` not found in dataset", pack_id))
+            })?;
+            return Ok(cards.clone());
+        }
+
+        let data = self.read_data_file(StoreLocation::CardsFile(pack_id))?;
+        let cards = match self.format {
+            OutputFormat::Ndjson => self.format.deserialize_records(&data)?,
+            _ => {
+                self.format
+                    .deserialize::<CardsDocument>(&data)?
+                    .migrate()?
+                    .cards
+            }
+        };
+
+        Ok(cards)
+    }
+
+    /// Cards of every stored pack, packs without a cards file are skipped
+    pub fn read_all_cards(&self) -> Result<BTreeMap<PackId, Vec<Card>>> {
+        if self.single_file {
+            let dataset = self.read_dataset()?;
+            return Ok(dataset
+                .packs
+                .into_iter()
+                .map(|(pack_id, entry)| (pack_id, entry.cards))
+                .collect());
+        }
+
+        let mut all_cards = BTreeMap::new();
+        for pack_id in self.read_packs()?.into_keys() {
+            if !self.has_data_file(StoreLocation::CardsFile(&pack_id))? {
+                debug!("no cards stored for `{}`", pack_id);
+                continue;
+            }
+
+            let cards = self.read_cards(&pack_id)?;
+            all_cards.insert(pack_id, cards);
+        }
+
+        Ok(all_cards)
+    }
+
+    /// With `--merge`, combine `cards` with the `stored` ones and update the tombstones.
+    /// Without it, `cards` are returned as is.
+    fn merge_cards(
+        &self,
+        pack_id: &PackId,
+        cards: &[Card],
+        stored: Option<Vec<Card>>,
+    ) -> Result<Vec<Card>> {
+        let mut cards = match self.group_variants {
+            true => variant::group_variants(cards),
+            false => cards.to_vec(),
+        };
+        for card in cards.iter_mut() {
+            text::normalize_card_text(card, &self.text_normalization);
+            if !self.include_raw {
+                card.raw_rarity = None;
+                card.raw_category = None;
+                card.raw_colors = None;
+                card.raw_attributes = None;
+            }
+        }
+
+        if !self.merge {
+            return Ok(cards);
+        }
+
+        let mut tombstones = self.read_tombstones()?;
+        let pack_tombstones = tombstones.packs.entry(pack_id.clone()).or_default();
+        let (cards, stats) =
+            merge::merge_cards(stored.unwrap_or_default(), &cards, pack_tombstones);
+
+        info!(
+            "merged cards of `{}`: {} added, {} updated, {} unchanged, {} removed",
+            pack_id, stats.added, stats.updated, stats.unchanged, stats.removed
+        );
+
+        tombstones.packs.retain(|_, ids| !ids.is_empty());
+        self.write_tombstones(&tombstones)?;
+        Ok(cards)
+    }
+
+    pub fn read_tombstones(&self) -> Result<Tombstones> {
+        if !self.has_data_file(StoreLocation::TombstonesFile)? {
+            return Ok(Tombstones::default());
+        }
+
+        let data = self.read_data_file(StoreLocation::TombstonesFile)?;
+        let mut tombstones: Tombstones = self.format.deserialize(&data)?;
+
+        schema::ensure_supported(tombstones.schema_version)?;
+        tombstones.schema_version = SCHEMA_VERSION;
+        Ok(tombstones)
+    }
+
+    fn write_tombstones(&self, tombstones: &Tombstones) -> Result<()> {
+        let path = self.get_path(StoreLocation::TombstonesFile)?;
+        let data = self.format.serialize(tombstones, self.pretty)?;
+        self.write_data_file(&path, &data)?;
+        debug!("wrote tombstones to: {}", path.display());
+
+        Ok(())
+    }
+
+    /// Write the cross-reference of cards printed in several packs
+    pub fn write_reprints(&self, reprints: &Reprints) -> Result<()> {
+        let path = self.get_path(StoreLocation::ReprintsFile)?;
+        let data = self.format.serialize(reprints, self.pretty)?;
+        self.write_data_file(&path, &data)?;
+        debug!(
+            "wrote {} reprints to: {}",
+            reprints.cards.len(),
+            path.display()
+        );
+
+        Ok(())
+    }
+
+    /// Write the keywords, timings and conditions used by the effects of the pulled cards
+    pub fn write_keywords(&self, keywords: &KeywordTaxonomy) -> Result<()> {
+        let path = self.get_path(StoreLocation::KeywordsFile)?;
+        let data = self.format.serialize(keywords, self.pretty)?;
+        self.write_data_file(&path, &data)?;
+        debug!(
+            "wrote {} keywords to: {}",
+            keywords.keywords.len(),
+            path.display()
+        );
+
+        Ok(())
+    }
+
+    /// Data files must be byte-identical for identical source data, so cards are always
+    /// sorted and timestamps only ever go to vega.meta.toml
+    fn normalize_cards(&self, cards: &mut [Card]) {
+        cards.sort_by(|a, b| self.card_order.compare(a, b));
+
+        if !self.scrape_timestamps {
+            for card in cards.iter_mut() {
+                card.scraped_at = None;
+            }
+        }
+    }
+
+    fn write_data_file(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let data = self.compression.compress(data)?;
+        self.write_checked(path, &data)
+    }
+
+    /// Write a file and remember its checksum for `SHA256SUMS`
+    fn write_checked(&self, path: &Path, data: &[u8]) -> Result<()> {
+        self.backend.write(path, data)?;
+        self.checksums()?
+            .insert(self.checksum_key(path), Self::sha256_hex(data));
+        Ok(())
+    }
+
+    /// Paths in `SHA256SUMS` are relative to the store root (as `sha256sum -c` expects)
+    fn checksum_key(&self, path: &Path) -> String {
+        let path = path.strip_prefix(&self.root_dir).unwrap_or(path);
+        path.to_string_lossy().into_owned()
+    }
+
+    fn read_checksums_file(&self) -> Result<BTreeMap<String, String>> {
+        let path = self.get_path(StoreLocation::ChecksumsFile)?;
+        if !self.backend.exists(&path) {
+            return Ok(BTreeMap::new());
+        }
+
+        let data = String::from_utf8(self.backend.read(&path)?)
+            .with_context(|| format!("invalid checksums file: {}", path.display()))?;
+
+        checksums::parse(&data)
+    }
+
+    /// Checksums recorded in `SHA256SUMS` (read once) and of every file written since
+    fn checksums(&self) -> Result<MutexGuard<'_, BTreeMap<String, String>>> {
+        if self.checksums.get().is_none() {
+            let recorded = self.read_checksums_file()?;
+            // Another thread may have read them first, its copy is kept
+            let _ = self.checksums.set(Mutex::new(recorded));
+        }
+        Ok(self.checksums.get().unwrap().lock().unwrap())
+    }
+
+    /// Flush checksums of every file written so far to `SHA256SUMS`, keeping older entries
+    pub fn write_checksums(&self) -> Result<()> {
+        let checksums = self.checksums()?.clone();
+
+        let data = checksums::format(&checksums);
+        let path = self.get_path(StoreLocation::ChecksumsFile)?;
+        self.backend.write(&path, data.as_bytes())?;
+        debug!("wrote {} checksums to: {}", checksums.len(), path.display());
+
+        Ok(())
+    }
+
+    /// Warn if `data` does not match the checksum recorded for `path` (if any), as files may be
+    /// edited by hand after a pull. Fail instead with strict checksums
+    fn verify_checksum(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let expected = self.checksums()?.get(&self.checksum_key(path)).cloned();
+
+        if let Some(expected) = expected {
+            let actual = Self::sha256_hex(data);
+            if actual != expected {
+                let message = format!(
+                    "checksum mismatch for {}: expected {} but got {}, the file was edited or is corrupted",
+                    path.display(),
+                    expected,
+                    actual
+                );
+                if self.strict_checksums {
+                    return Err(VegaError::storage(message));
+                }
+                warn!("{}", message);
+                return Ok(());
+            }
+            trace!("checksum ok for: {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    fn has_data_file(&self, location: StoreLocation) -> Result<bool> {
+        let raw_path = self.get_raw_path(location)?;
+        Ok(Compression::ALL
+            .iter()
+            .any(|compression| self.backend.exists(&compression.apply_to_path(&raw_path))))
+    }
+
+    /// Read a data file whatever compression it was written with, preferring the store's own
+    fn read_data_file(&self, location: StoreLocation) -> Result<Vec<u8>> {
+        let raw_path = self.get_raw_path(location)?;
+
+        let candidates = std::iter::once(self.compression).chain(Compression::ALL);
+        for compression in candidates {
+            let path = compression.apply_to_path(&raw_path);
+            if self.backend.exists(&path) {
+                debug!("reading {:?} data file: {}", compression, path.display());
+                let data = self.backend.read(&path)?;
+                self.verify_checksum(&path, &data)?;
+                return compression
+                    .decompress(&data)
+                    .with_context(|| format!("failed to decompress: {}", path.display()));
+            }
+        }
+
+        Err(VegaError::storage(format!(
+            "data file not found: {}",
+            raw_path.display()
+        )))
+    }
+
+    fn write_image_to_file(&self, img_data: Vec<u8>, path: &Path) -> Result<()> {
+        debug!("about to save image to file: `{}`", path.display());
+
+        self.write_checked(path, &img_data)?;
+
+        debug!("saved {} bytes to {}", img_data.len(), path.display());
+
+        Ok(())
+    }
+
+    pub fn write_image(&self, card: &Card, img_data: Vec<u8>) -> Result<()> {
+        let path = match self.image_layout {
+            ImageLayout::ContentAddressed => None,
+            _ => Some(self.get_path(StoreLocation::ImageFile(card))?),
+        };
+
+        self.write_card_image(card.id.to_string(), &card.img_url, path, img_data)
+    }
+
+    /// Front images of `cards` as stored, by card ID, leaving out cards without one
+    pub fn read_card_images<'a>(&self, cards: &[&'a Card]) -> Result<HashMap<&'a CardId, Vec<u8>>> {
+        let manifest = self.read_image_manifest()?;
+
+        let mut images = HashMap::new();
+        for card in cards {
+            let entry = manifest.get(card.id.as_str());
+            let path = match (self.image_layout, entry) {
+                (ImageLayout::ContentAddressed, Some(entry)) => {
+                    self.get_path(StoreLocation::ImageObjectFile(&entry.filename))?
+                }
+                (ImageLayout::ContentAddressed, None) => continue,
+                (_, entry) => {
+                    let path = self.get_path(StoreLocation::ImageFile(card))?;
+                    match entry {
+                        Some(entry) if !self.backend.exists(&path) => {
+                            self.root_dir.join(&entry.filename)
+                        }
+                        _ => path,
+                    }
+                }
+            };
+
+            if self.backend.exists(&path) {
+                images.insert(&card.id, self.backend.read(&path)?);
+            } else {
+                trace!("no image stored for `{}`", card.id);
+            }
+        }
+
+        Ok(images)
+    }
+
+    /// Cards of `cards` missing an image in the store, or whose stored image does not match
+    /// its recorded checksum, so that re-runs only download those (all of them if forced)
+    pub fn missing_images<'a>(&self, cards: Vec<&'a Card>) -> Result<Vec<&'a Card>> {
+        if self.force_images {
+            return Ok(cards);
+        }
+
+        let checksums = self.checksums()?.clone();
+        let index: BTreeMap<String, String> = match self.image_layout {
+            ImageLayout::ContentAddressed => {
+                let path = self.get_path(StoreLocation::ImageIndexFile)?;
+                match self.backend.exists(&path) {
+                    true => serde_json::from_slice(&self.backend.read(&path)?)
+                        .with_context(|| format!("invalid image index: {}", path.display()))?,
+                    false => BTreeMap::new(),
+                }
+            }
+            _ => BTreeMap::new(),
+        };
+
+        // Images whose extension was corrected are found through the manifest
+        let manifest = self.read_image_manifest()?;
+        let stored_path = |key: String, path: PathBuf| -> PathBuf {
+            match manifest.get(&key) {
+                Some(entry) if !self.backend.exists(&path) => self.root_dir.join(&entry.filename),
+                _ => path,
+            }
+        };
+
+        let is_stored = |path: &Path| -> Result<bool> {
+            if !self.backend.exists(path) {
+                return Ok(false);
+            }
+            match checksums.get(&self.checksum_key(path)) {
+                Some(expected) => Ok(Self::sha256_hex(&self.backend.read(path)?) == *expected),
+                None => Ok(true),
+            }
+        };
+
+        let mut missing = Vec::new();
+        for card in cards {
+            if manifest
+                .get(&card.id.to_string())
+                .is_some_and(|entry| entry.placeholder)
+            {
+                missing.push(card);
+                continue;
+            }
+
+            let mut paths = Vec::new();
+            if self.image_layout == ImageLayout::ContentAddressed {
+                let mut keys = vec![card.id.to_string()];
+                if card.img_back_url.is_some() {
+                    keys.push(format!("{}_back", card.id));
+                }
+                for key in keys {
+                    match index.get(&key) {
+                        Some(filename) => {
+                            paths.push(self.get_path(StoreLocation::ImageObjectFile(filename))?)
+                        }
+                        None => paths.clear(),
+                    }
+                    if paths.is_empty() {
+                        break;
+                    }
+                }
+            } else {
+                let front_path = self.get_path(StoreLocation::ImageFile(card))?;
+                if card.img_back_url.is_some() {
+                    let back_path = Self::get_back_image_path(&front_path);
+                    paths.push(stored_path(format!("{}_back", card.id), back_path));
+                }
+                paths.push(stored_path(card.id.to_string(), front_path));
+            }
+
+            let mut stored = !paths.is_empty();
+            for path in &paths {
+                stored = stored && is_stored(path)?;
+            }
+            if stored {
+                trace!("images of `{}` already stored", card.id);
+            } else {
+                missing.push(card);
+            }
+        }
+
+        Ok(missing)
+    }
+
+    /// Write an artwork of a pack downloaded from `img_url` to `images/packs/<pack>_<kind>.<ext>`,
+    /// returning its path relative to the store for `packs.json`
+    pub fn write_pack_image(
+        &self,
+        pack_id: &PackId,
+        kind: &str,
+        img_url: &str,
+        img_data: Vec<u8>,
+    ) -> Result<String> {
+        let stem = format!("{}_{}", pack_id, kind);
+        self.write_asset_image(&stem, img_url, img_data, |filename| {
+            StoreLocation::PackArtworkFile(filename)
+        })
+    }
+
+    /// Write the back of every card downloaded from `img_url` to `images/card_back.<ext>`,
+    /// returning its path relative to the store for `vega.meta.toml`
+    pub fn write_card_back(&self, img_url: &str, img_data: Vec<u8>) -> Result<String> {
+        self.write_asset_image("card_back", img_url, img_data, |filename| {
+            StoreLocation::CardBackFile(filename)
+        })
+    }
+
+    /// Write an image that is not of a card as `<stem>.<ext>` at `location`, `<ext>` being the
+    /// one of its actual format
+    fn write_asset_image(
+        &self,
+        stem: &str,
+        img_url: &str,
+        img_data: Vec<u8>,
+        location: fn(&str) -> StoreLocation,
+    ) -> Result<String> {
+        let ext = Path::new(Self::get_url_filename(img_url)?)
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("png"));
+        let img_data = self.convert_image(img_url, img_data)?;
+        let filename = image_format::correct_extension(
+            &self
+                .image_format
+                .apply_to_filename(&format!("{}.{}", stem, ext)),
+            &img_data,
+        );
+
+        let path = self.get_path(location(&filename))?;
+        self.write_image_to_file(img_data, &path)?;
+
+        Ok(self.checksum_key(&path))
+    }
+
+    /// Write the back face image of `card`, next to the front one with a `_back` suffix
+    pub fn write_back_image(&self, card: &Card, img_data: Vec<u8>) -> Result<()> {
+        let img_back_url = card
+            .img_back_url
+            .as_deref()
+            .ok_or_else(|| VegaError::storage(format!("card `{}` has no back face", card.id)))?;
+
+        let path = match self.image_layout {
+            ImageLayout::ContentAddressed => None,
+            _ => Some(Self::get_back_image_path(
+                &self.get_path(StoreLocation::ImageFile(card))?,
+            )),
+        };
+
+        self.write_card_image(format!("{}_back", card.id), img_back_url, path, img_data)
+    }
+
+    /// Convert and write the image of `key` at `path`, or as an image object without one,
+    /// recording it in the image manifest
+    fn write_card_image(
+        &self,
+        key: String,
+        img_url: &str,
+        path: Option<PathBuf>,
+        img_data: Vec<u8>,
+    ) -> Result<()> {
+        let img_data = self.convert_image(img_url, img_data)?;
+        // The extension of the site's filename is not always the format of the image
+        let path = path.map(|path| {
+            let filename = path.file_name().unwrap_or_default().to_string_lossy();
+            path.with_file_name(image_format::correct_extension(&filename, &img_data))
+        });
+        let filename = match &path {
+            Some(path) => self.checksum_key(path),
+            None => image_format::correct_extension(
+                &self.get_object_filename(img_url, &Self::sha256_hex(&img_data))?,
+                &img_data,
+            ),
+        };
+
+        let path = match path {
+            Some(path) if !self.materialize_images => {
+                let hash = Self::sha256_hex(&img_data);
+                let mut written_images = self.written_images.lock().unwrap();
+                match written_images.get(&hash) {
+                    Some(original) if *original != filename => {
+                        debug!("image of `{}` is the same as {}", key, original);
+                        let original = original.clone();
+                        drop(written_images);
+                        self.record_conversion(key.clone(), img_url, &original)?;
+                        self.record_manifest_entry(key, &original, &img_data);
+                        return Ok(());
+                    }
+                    _ => written_images.insert(hash, filename.clone()),
+                };
+                Some(path)
+            }
+            path => path,
+        };
+
+        self.record_conversion(key.clone(), img_url, &filename)?;
+        self.record_manifest_entry(key.clone(), &filename, &img_data);
+        match path {
+            Some(path) => self.write_image_at(img_data, &path),
+            None => self.write_image_object(key, &filename, img_data),
+        }
+    }
+
+    fn record_manifest_entry(&self, key: String, filename: &str, img_data: &[u8]) {
+        let entry = ImageEntry::new(filename, img_data);
+        if entry.format.is_none() {
+            warn!(
+                "failed to read image of `{}`, its size is not recorded",
+                key
+            );
+        }
+        self.image_manifest.lock().unwrap().insert(key, entry);
+    }
+
+    /// Record in the image manifest which variants the written images of `card` come from
+    pub fn record_image_variants(&self, card: &Card, front: Option<&str>, back: Option<&str>) {
+        let mut manifest = self.image_manifest.lock().unwrap();
+        let faces = [
+            (card.id.to_string(), front),
+            (format!("{}_back", card.id), back),
+        ];
+        for (key, variant) in faces {
+            if let (Some(entry), Some(variant)) = (manifest.get_mut(&key), variant) {
+                entry.variant = Some(variant.to_string());
+            }
+        }
+    }
+
+    /// Record in the image manifest the HTTP validators the written images of `card` were served
+    /// with
+    pub fn record_image_validators(
+        &self,
+        card: &Card,
+        front: &ImageValidators,
+        back: &ImageValidators,
+    ) {
+        let mut manifest = self.image_manifest.lock().unwrap();
+        let faces = [
+            (card.id.to_string(), front),
+            (format!("{}_back", card.id), back),
+        ];
+        for (key, validators) in faces {
+            if let Some(entry) = manifest.get_mut(&key) {
+                entry.validators = validators.clone();
+            }
+        }
+    }
+
+    /// With `--image-placeholders`, write a generated placeholder as every image of the `failed`
+    /// cards and list them with their error in `errors.json`. Placeholders are marked as such in
+    /// the image manifest so that the next pull downloads them again
+    pub fn write_image_placeholders(&self, failed: &[(&Card, &str)]) -> Result<()> {
+        if !self.image_placeholders {
+            return Ok(());
+        }
+
+        let mut errors = BTreeMap::new();
+        for (card, error) in failed {
+            let img_data = placeholder::render(card)?;
+            if card.img_back_url.is_some() {
+                self.write_back_image(card, img_data.clone())?;
+            }
+            self.write_image(card, img_data)?;
+
+            let mut manifest = self.image_manifest.lock().unwrap();
+            for key in [card.id.to_string(), format!("{}_back", card.id)] {
+                if let Some(entry) = manifest.get_mut(&key) {
+                    entry.placeholder = true;
+                }
+            }
+            errors.insert(card.id.clone(), error.to_string());
+        }
+
+        let path = self.get_path(StoreLocation::ErrorsFile)?;
+        let json = serde_json::to_string_pretty(&errors)?;
+        self.write_checked(&path, json.as_bytes())?;
+        info!("wrote placeholders for {} cards", errors.len());
+
+        Ok(())
+    }
+
+    /// Stored cards of `cards` to ask the site about with `--check-images`, along with the
+    /// validators their faces were served with (front first, empty when not recorded)
+    pub fn images_to_check<'a>(
+        &self,
+        cards: Vec<&'a Card>,
+    ) -> Result<Vec<(&'a Card, Vec<ImageValidators>)>> {
+        if !self.check_images || self.force_images {
+            return Ok(Vec::new());
+        }
+
+        let manifest = self.read_image_manifest()?;
+        let validators = |key: String| {
+            manifest
+                .get(&key)
+                .map(|entry| entry.validators.clone())
+                .unwrap_or_default()
+        };
+
+        Ok(cards
+            .into_iter()
+            .map(|card| {
+                let mut faces = vec![validators(card.id.to_string())];
+                if card.img_back_url.is_some() {
+                    faces.push(validators(format!("{}_back", card.id)));
+                }
+                (card, faces)
+            })
+            .collect())
+    }
+
+    /// `images/OP01-001.png` -> `images/OP01-001_back.png`
+    fn get_back_image_path(front_path: &Path) -> PathBuf {
+        let stem = front_path.file_stem().unwrap_or_default().to_string_lossy();
+        let filename = match front_path.extension() {
+            Some(ext) => format!("{}_back.{}", stem, ext.to_string_lossy()),
+            None => format!("{}_back", stem),
+        };
+
+        front_path.with_file_name(filename)
+    }
+
+    /// Transcode an image downloaded from `img_url` to the store's image format, and recompress it
+    /// with `optimize_images`
+    fn convert_image(&self, img_url: &str, img_data: Vec<u8>) -> Result<Vec<u8>> {
+        let img_data = self
+            .image_format
+            .convert(img_data)
+            .with_context(|| format!("failed to convert {} to {:?}", img_url, self.image_format))?;
+        if !self.optimize_images {
+            return Ok(img_data);
+        }
+
+        image_format::optimize(img_data).with_context(|| format!("failed to optimize {}", img_url))
+    }
+
+    /// Remember the site's filename of a converted image along with the one it was stored as
+    fn record_conversion(&self, key: String, img_url: &str, converted: &str) -> Result<()> {
+        if self.image_format == ImageFormat::Original {
+            return Ok(());
+        }
+
+        let conversion = ImageConversion {
+            original: Self::get_url_filename(img_url)?.to_string(),
+            converted: converted.to_string(),
+        };
+        self.image_conversions
+            .lock()
+            .unwrap()
+            .insert(key, conversion);
+        Ok(())
+    }
+
+    fn write_image_at(&self, img_data: Vec<u8>, path: &Path) -> Result<()> {
+        if let Some(shared_images) = &self.shared_images {
+            return self.write_shared_image(shared_images, img_data, path);
+        }
+
+        self.write_image_to_file(img_data, path)
+    }
+
+    fn write_shared_image(
+        &self,
+        shared_images: &SharedImages,
+        img_data: Vec<u8>,
+        path: &Path,
+    ) -> Result<()> {
+        let hash = Self::sha256_hex(&img_data);
+        let original = shared_images.paths.lock().unwrap().get(&hash).cloned();
+
+        if let Some(original) = original {
+            if original != path && self.backend.link(&original, path)? {
+                debug!("linked {} to {}", path.display(), original.display());
+                self.checksums()?.insert(self.checksum_key(path), hash);
+                return Ok(());
+            }
+        }
+
+        self.write_image_to_file(img_data, path)?;
+        shared_images
+            .paths
+            .lock()
+            .unwrap()
+            .entry(hash)
+            .or_insert_with(|| path.to_path_buf());
+        Ok(())
+    }
+
+    pub fn sha256_hex(data: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    fn get_object_filename(&self, img_url: &str, hash: &str) -> Result<String> {
+        let img_filename = self
+            .image_format
+            .apply_to_filename(Self::get_url_filename(img_url)?);
+        let img_filename = img_filename.as_str();
+        let filename = match Path::new(img_filename).extension() {
+            Some(ext) => format!("{}.{}", hash, ext.to_string_lossy()),
+            None => hash.to_string(),
+        };
+
+        Ok(filename)
+    }
+
+    fn write_image_object(&self, key: String, filename: &str, img_data: Vec<u8>) -> Result<()> {
+        let path = self.get_path(StoreLocation::ImageObjectFile(filename))?;
+
+        if self.backend.exists(&path) {
+            debug!("image object already stored for `{}`: {}", key, filename);
+        } else {
+            self.write_image_to_file(img_data, &path)?;
+        }
+
+        self.image_index
+            .lock()
+            .unwrap()
+            .insert(key, filename.to_string());
+        Ok(())
+    }
+
+    /// Flush the card to image object index (only used by the content-addressed layout),
+    /// the image manifest and the filenames of converted images
+    pub fn write_image_index(&self) -> Result<()> {
+        self.write_image_conversions()?;
+        self.write_image_manifest()?;
+        if self.image_layout != ImageLayout::ContentAddressed {
+            return Ok(());
+        }
+
+        let path = self.get_path(StoreLocation::ImageIndexFile)?;
+
+        let mut index: BTreeMap<String, String> = if self.backend.exists(&path) {
+            let data = self.backend.read(&path)?;
+            serde_json::from_slice(&data)
+                .with_context(|| format!("invalid image index: {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+
+        index.append(&mut self.image_index.lock().unwrap());
+
+        let json = serde_json::to_string(&index)?;
+        self.write_checked(&path, json.as_bytes())?;
+        debug!("wrote {} entries to image index", index.len());
+
+        Ok(())
+    }
+
+    /// Add the images written since the last flush to `images/manifest.json`
+    fn write_image_manifest(&self) -> Result<()> {
+        let mut entries = self.image_manifest.lock().unwrap();
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.get_path(StoreLocation::ImageManifestFile)?;
+        let mut manifest = self.read_image_manifest()?;
+        manifest.append(&mut entries);
+
+        let json = serde_json::to_string(&manifest)?;
+        self.write_checked(&path, json.as_bytes())?;
+        debug!("wrote {} entries to image manifest", manifest.len());
+
+        Ok(())
+    }
+
+    fn read_image_manifest(&self) -> Result<BTreeMap<String, ImageEntry>> {
+        let path = self.get_path(StoreLocation::ImageManifestFile)?;
+        if !self.backend.exists(&path) {
+            return Ok(BTreeMap::new());
+        }
+
+        serde_json::from_slice(&self.backend.read(&path)?)
+            .with_context(|| format!("invalid image manifest: {}", path.display()))
+    }
+
+    fn write_image_conversions(&self) -> Result<()> {
+        let mut conversions = self.image_conversions.lock().unwrap();
+        if conversions.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.get_path(StoreLocation::ImageConversionsFile)?;
+        let mut recorded: BTreeMap<String, ImageConversion> = if self.backend.exists(&path) {
+            serde_json::from_slice(&self.backend.read(&path)?)
+                .with_context(|| format!("invalid image conversions: {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        recorded.append(&mut conversions);
+
+        let json = serde_json::to_string(&recorded)?;
+        self.write_checked(&path, json.as_bytes())?;
+        debug!("wrote {} image conversions", recorded.len());
+
+        Ok(())
+    }
+
+    /// Complete the store once every file has been written (e.g. close archives)
+    pub fn finish(&self) -> Result<()> {
+        self.backend.finish()
+    }
+
+    pub fn write_vega_stats(&self, stats: VegaMetaStats) -> Result<()> {
+        let path = self.get_path(StoreLocation::VegaMetaFile)?;
+        let toml = toml::to_string_pretty(&stats)?;
+
+        self.backend.write(&path, toml.as_bytes())?;
+        debug!("wrote vega stats to: {} {:#?}", path.display(), stats);
+        Ok(())
+    }
+
+    fn read_vega_meta(&self) -> Result<Option<toml::Table>> {
+        let path = self.get_path(StoreLocation::VegaMetaFile)?;
+        if !self.backend.exists(&path) {
+            return Ok(None);
+        }
+
+        let data = String::from_utf8(self.backend.read(&path)?)?;
+        let meta = toml::from_str(&data)
+            .with_context(|| format!("invalid meta file: {}", path.display()))?;
+        Ok(Some(meta))
+    }
+
+    /// Upgrade the data files and vega.meta.toml of a store written by an older vegapull,
+    /// returns how many data files were rewritten
+    pub fn migrate(&self) -> Result<usize> {
+        let meta = self.read_vega_meta()?;
+        let mut pack_ids: BTreeSet<PackId> = meta
+            .as_ref()
+            .and_then(|meta| meta.get("packs")?.as_array().cloned())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|pack_id| pack_id.as_str()?.parse().ok())
+            .collect();
+
+        let mut migrated = 0;
+        if self.single_file {
+            if self.has_data_file(StoreLocation::DatasetFile)? {
+                self.write_dataset(&self.read_dataset()?)?;
+                migrated += 1;
+            }
+        } else {
+            if self.has_data_file(StoreLocation::PacksListFile)? {
+                let packs = self.read_packs()?;
+                pack_ids.extend(packs.keys().cloned());
+                self.write_packs(&packs)?;
+                migrated += 1;
+            }
+
+            // Packs pulled with `vega pull cards` are only listed in the meta file
+            for pack_id in &pack_ids {
+                if self.has_data_file(StoreLocation::CardsFile(pack_id))? {
+                    let cards = self.read_cards(pack_id)?;
+                    self.write_cards(pack_id, &cards)?;
+                    migrated += 1;
+                }
+            }
+        }
+
+        if let Some(mut meta) = meta {
+            let schema_version = meta.get("schema_version").and_then(toml::Value::as_integer);
+            if schema_version != Some(SCHEMA_VERSION.into()) {
+                meta.insert(
+                    String::from("schema_version"),
+                    toml::Value::Integer(SCHEMA_VERSION.into()),
+                );
+
+                let path = self.get_path(StoreLocation::VegaMetaFile)?;
+                self.backend
+                    .write(&path, toml::to_string_pretty(&meta)?.as_bytes())?;
+                info!("migrated meta file: {}", path.display());
+            }
+        }
+
+        self.write_checksums()?;
+        Ok(migrated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{CardCategory, CardRarity};
+    use crate::storage::backend::MemoryBackend;
+    use std::fs;
+
+    fn test_card(id: &str, img_url: &str) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id("569101")
+            .name("Monkey.D.Luffy")
+            .rarity(CardRarity::Leader)
+            .category(CardCategory::Leader)
+            .img_url(img_url)
+            .power(5000)
+            .build()
+            .unwrap()
+    }
+
+    fn temp_store_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vega-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn sha256_hex_returns_digest() {
+        assert_eq!(
+            DataStore::sha256_hex(b"vega"),
+            "313ce7d71787960e3bb5f8258c173ae466b4e08e1e7d24b9c7a5ba81c9a02d96"
+        );
+    }
+
+    #[test]
+    fn open_reads_locked_store_and_does_not_create_missing_one() {
+        let dir = temp_store_dir("open");
+        let options = StoreOptions::default();
+        assert!(DataStore::open(&dir, LanguageCode::English, &options).is_err());
+        assert!(!dir.exists());
+
+        let writer = DataStore::create(&dir, LanguageCode::English, &options).unwrap();
+        writer.write_packs(&HashMap::new()).unwrap();
+        assert!(DataStore::create(&dir, LanguageCode::English, &options).is_err());
+        let reader = DataStore::open(&dir, LanguageCode::English, &options).unwrap();
+        assert!(reader.read_packs().unwrap().is_empty());
+
+        drop(writer);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_compressed_then_read_cards_returns_same() {
+        let dir = temp_store_dir("compressed");
+        let cards = vec![test_card(
+            "OP01-001",
+            "../images/cardlist/card/OP01-001.png",
+        )];
+
+        DataStore::new(&dir, LanguageCode::English)
+            .with_compression(Compression::Zstd)
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+
+        let store = DataStore::new(&dir, LanguageCode::English);
+        assert!(dir.join("json/cards_569101.json.zst").exists());
+
+        let read_cards = store.read_cards(&"569101".parse().unwrap()).unwrap();
+        assert_eq!(read_cards.len(), 1);
+        assert_eq!(read_cards[0].id, "OP01-001");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_cards_legacy_file_migrates() {
+        let dir = temp_store_dir("legacy");
+        let store = DataStore::new(&dir, LanguageCode::English);
+
+        let cards = vec![test_card(
+            "OP01-001",
+            "../images/cardlist/card/OP01-001.png",
+        )];
+        fs::create_dir_all(dir.join("json")).unwrap();
+        fs::write(
+            dir.join("json/cards_569101.json"),
+            serde_json::to_string(&cards).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[0].id,
+            "OP01-001"
+        );
+
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+        let data = fs::read_to_string(dir.join("json/cards_569101.json")).unwrap();
+        assert!(data.starts_with(&format!("{{\"schema_version\":{}", SCHEMA_VERSION)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_is_deterministic() {
+        let dir = temp_store_dir("deterministic");
+        let store = DataStore::new(&dir, LanguageCode::English);
+        let path = dir.join("json/cards_569101.json");
+
+        let mut cards = vec![
+            test_card("OP01-001", "../images/cardlist/card/OP01-001.png"),
+            test_card("OP01-002", "../images/cardlist/card/OP01-002.png"),
+        ];
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+        let first = fs::read(&path).unwrap();
+
+        cards.reverse();
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+        assert_eq!(fs::read(&path).unwrap(), first);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_keeps_scrape_timestamps_only_when_enabled() {
+        let dir = temp_store_dir("provenance");
+        let mut card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png");
+        card.source_url = Some(String::from(
+            "https://en.onepiece-cardgame.com/cardlist?series=569101",
+        ));
+        card.scraped_at = Some(chrono::Utc::now());
+
+        let store = DataStore::new(&dir, LanguageCode::English);
+        store
+            .write_cards(&"569101".parse().unwrap(), std::slice::from_ref(&card))
+            .unwrap();
+        let read_card = &store.read_cards(&"569101".parse().unwrap()).unwrap()[0];
+        assert_eq!(read_card.source_url, card.source_url);
+        assert_eq!(read_card.scraped_at, None);
+
+        let store = store.with_scrape_timestamps(true);
+        store
+            .write_cards(&"569101".parse().unwrap(), std::slice::from_ref(&card))
+            .unwrap();
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[0].scraped_at,
+            card.scraped_at
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_pretty_sorts_cards_by_id() {
+        let dir = temp_store_dir("pretty");
+        let store = DataStore::new(&dir, LanguageCode::English).with_pretty(true);
+
+        let cards = vec![
+            test_card("OP01-002", "../images/cardlist/card/OP01-002.png"),
+            test_card("OP01-001", "../images/cardlist/card/OP01-001.png"),
+        ];
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+
+        let data = fs::read_to_string(dir.join("json/cards_569101.json")).unwrap();
+        assert!(data.contains("\n  \"cards\": [\n"));
+
+        let read_cards = store.read_cards(&"569101".parse().unwrap()).unwrap();
+        assert_eq!(read_cards[0].id, "OP01-001");
+        assert_eq!(read_cards[1].id, "OP01-002");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_packs_ndjson_then_read_packs_returns_same() {
+        let dir = temp_store_dir("ndjson");
+        let store = DataStore::new(&dir, LanguageCode::English).with_format(OutputFormat::Ndjson);
+
+        let cards = vec![
+            test_card("OP01-001", "../images/cardlist/card/OP01-001.png"),
+            test_card("OP01-002", "../images/cardlist/card/OP01-002.png"),
+        ];
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+
+        let data = fs::read_to_string(dir.join("json/cards_569101.ndjson")).unwrap();
+        assert_eq!(data.lines().count(), 2);
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[1].id,
+            "OP01-002"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_then_read_cards_returns_same_for_every_format() {
+        for format in OutputFormat::ALL {
+            let dir = temp_store_dir(format.extension());
+            let store = DataStore::new(&dir, LanguageCode::English).with_format(format);
+
+            let cards = vec![test_card(
+                "OP01-001",
+                "../images/cardlist/card/OP01-001.png",
+            )];
+            store
+                .write_cards(&"569101".parse().unwrap(), &cards)
+                .unwrap();
+
+            let read_cards = store.read_cards(&"569101".parse().unwrap()).unwrap();
+            assert_eq!(read_cards[0].id, "OP01-001");
+            assert_eq!(read_cards[0].rarity, CardRarity::Leader);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn write_all_single_file_msgpack_then_read_packs_returns_same() {
+        let dir = temp_store_dir("single-file-msgpack");
+        let store = DataStore::new(&dir, LanguageCode::English)
+            .with_format(OutputFormat::Msgpack)
+            .with_single_file(true);
+
+        let raw = r#"{"id":"569101","raw_title":"ROMANCE DAWN [OP-01]","title_parts":{"prefix":null,"title":"ROMANCE DAWN","label":"OP-01"}}"#;
+        let pack: Pack = serde_json::from_str(raw).unwrap();
+        let packs = HashMap::from([(pack.id.clone(), pack)]);
+        let cards = HashMap::from([(
+            "569101".parse().unwrap(),
+            vec![test_card(
+                "OP01-001",
+                "../images/cardlist/card/OP01-001.png",
+            )],
+        )]);
+        store.write_all(&packs, &cards).unwrap();
+
+        assert!(dir.join("json/dataset.msgpack").exists());
+        assert_eq!(
+            store.read_packs().unwrap()["569101"].raw_title,
+            "ROMANCE DAWN [OP-01]"
+        );
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap().len(),
+            1
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_all_memory_backend_does_not_touch_disk() {
+        let dir = temp_store_dir("memory");
+        let backend = MemoryBackend::default();
+        let store =
+            DataStore::new(&dir, LanguageCode::English).with_backend(Box::new(backend.clone()));
+
+        let cards = HashMap::from([(
+            "569101".parse().unwrap(),
+            vec![test_card(
+                "OP01-001",
+                "../images/cardlist/card/OP01-001.png",
+            )],
+        )]);
+        store.write_all(&HashMap::new(), &cards).unwrap();
+
+        assert!(!dir.exists());
+        assert_eq!(
+            backend.paths(),
+            vec![
+                dir.join("json/cards_569101.json"),
+                dir.join("json/packs.json")
+            ]
+        );
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[0].id,
+            "OP01-001"
+        );
+    }
+
+    #[test]
+    fn write_per_pack_layout_uses_pack_directories() {
+        let dir = temp_store_dir("per-pack");
+        let store = DataStore::new(&dir, LanguageCode::English).with_layout(StoreLayout::PerPack);
+
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png?250101");
+        store
+            .write_cards(&"569101".parse().unwrap(), std::slice::from_ref(&card))
+            .unwrap();
+        store.write_image(&card, b"image".to_vec()).unwrap();
+
+        assert!(dir.join("packs/569101/cards.json").exists());
+        assert!(dir.join("packs/569101/images/OP01-001.png").exists());
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[0].id,
+            "OP01-001"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_single_file_merges_into_dataset() {
+        let dir = temp_store_dir("single-file");
+        let store = DataStore::new(&dir, LanguageCode::English).with_single_file(true);
+
+        let op01 = vec![test_card(
+            "OP01-001",
+            "../images/cardlist/card/OP01-001.png",
+        )];
+        let op02 = vec![test_card(
+            "OP02-001",
+            "../images/cardlist/card/OP02-001.png",
+        )];
+        store
+            .write_cards(&"569101".parse().unwrap(), &op01)
+            .unwrap();
+        store
+            .write_cards(&"569102".parse().unwrap(), &op02)
+            .unwrap();
+
+        assert!(!dir.join("json/cards_569101.json").exists());
+        assert!(dir.join("json/dataset.json").exists());
+
+        let dataset = store.read_dataset().unwrap();
+        assert_eq!(dataset.packs.len(), 2);
+        assert!(dataset.packs["569101"].pack.is_none());
+        assert_eq!(
+            store.read_cards(&"569102".parse().unwrap()).unwrap()[0].id,
+            "OP02-001"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_merge_records_tombstones() {
+        let dir = temp_store_dir("merge");
+        let store = DataStore::new(&dir, LanguageCode::English)
+            .with_backend(Box::new(MemoryBackend::default()))
+            .with_merge(true);
+
+        let cards = vec![
+            test_card("OP01-001", "../images/cardlist/card/OP01-001.png"),
+            test_card("OP01-002", "../images/cardlist/card/OP01-002.png"),
+        ];
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+        assert!(store.read_tombstones().unwrap().packs.is_empty());
+
+        let mut updated = test_card("OP01-001", "../images/cardlist/card/OP01-001.png");
+        updated.power = Some(6000);
+        let added = test_card("OP01-003", "../images/cardlist/card/OP01-003.png");
+        store
+            .write_cards(&"569101".parse().unwrap(), &[updated, added])
+            .unwrap();
+
+        let read_cards = store.read_cards(&"569101".parse().unwrap()).unwrap();
+        assert_eq!(read_cards.len(), 2);
+        assert_eq!(read_cards[0].power, Some(6000));
+        assert_eq!(read_cards[1].id, "OP01-003");
+
+        let tombstones = store.read_tombstones().unwrap();
+        assert_eq!(
+            tombstones.packs["569101"],
+            BTreeSet::from(["OP01-002".parse().unwrap()])
+        );
+    }
+
+    #[test]
+    fn write_vega_stats_includes_pack_results() {
+        let dir = temp_store_dir("meta");
+        let backend = MemoryBackend::default();
+        let store =
+            DataStore::new(&dir, LanguageCode::English).with_backend(Box::new(backend.clone()));
+
+        let stats = PackStats {
+            cards: 2,
+            images: 1,
+            duration_ms: 30,
+            errors: 1,
+            warnings: 2,
+            anomalies: vec![String::from("duplicate card ID `OP01-001`")],
+            image_errors: vec![],
+            small_images: vec![],
+            changed_images: vec![],
+        };
+        let meta = VegaMetaStats::new(
+            LanguageCode::English,
+            Local::now(),
+            42,
+            true,
+            PullMode::SinglePack,
+            HashSet::from(["569101".parse().unwrap()]),
+        )
+        .with_pack_results(BTreeMap::from([("569101".parse().unwrap(), stats)]));
+        store.write_vega_stats(meta).unwrap();
+
+        let data = backend.read(&dir.join(VEGA_META_FILE)).unwrap();
+        let meta: toml::Value = toml::from_str(&String::from_utf8(data).unwrap()).unwrap();
+        assert_eq!(
+            meta["vegapull_version"].as_str(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            meta["pack_results"]["569101"]["errors"].as_integer(),
+            Some(1)
+        );
+        assert_eq!(
+            meta["pack_results"]["569101"]["anomalies"][0].as_str(),
+            Some("duplicate card ID `OP01-001`")
+        );
+    }
+
+    #[test]
+    fn read_cards_corrupted_file_returns_err() {
+        let dir = temp_store_dir("checksums");
+        let backend = MemoryBackend::default();
+        let store =
+            DataStore::new(&dir, LanguageCode::English).with_backend(Box::new(backend.clone()));
+
+        let cards = vec![test_card(
+            "OP01-001",
+            "../images/cardlist/card/OP01-001.png",
+        )];
+        store
+            .write_cards(&"569101".parse().unwrap(), &cards)
+            .unwrap();
+        store.write_checksums().unwrap();
+
+        let sums = backend.read(&dir.join(CHECKSUMS_FILE)).unwrap();
+        let sums = String::from_utf8(sums).unwrap();
+        assert!(sums.ends_with("  json/cards_569101.json\n"));
+
+        // A fresh store only knows about the checksums file
+        let store = DataStore::new(&dir, LanguageCode::English)
+            .with_backend(Box::new(backend.clone()))
+            .with_strict_checksums(true);
+        assert!(store.read_cards(&"569101".parse().unwrap()).is_ok());
+
+        backend
+            .write(&dir.join("json/cards_569101.json"), b"[]")
+            .unwrap();
+        assert!(store.read_cards(&"569101".parse().unwrap()).is_err());
+
+        // Files edited by hand are read anyway unless checksums are strict
+        let store =
+            DataStore::new(&dir, LanguageCode::English).with_backend(Box::new(backend.clone()));
+        assert!(store
+            .read_cards(&"569101".parse().unwrap())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn write_image_shared_images_hardlinks_duplicates() {
+        let dir = temp_store_dir("shared-images");
+        let shared_images = Arc::new(SharedImages::default());
+        let english = DataStore::new(&dir.join("english"), LanguageCode::English)
+            .with_shared_images(shared_images.clone());
+        let french = DataStore::new(&dir.join("french"), LanguageCode::French)
+            .with_shared_images(shared_images);
+
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png");
+        english.write_image(&card, b"same".to_vec()).unwrap();
+        french.write_image(&card, b"same".to_vec()).unwrap();
+
+        let french_path = dir.join("french/images/OP01-001.png");
+        assert_eq!(fs::read(&french_path).unwrap(), b"same");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(fs::metadata(&french_path).unwrap().nlink(), 2);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_legacy_store_adds_schema_version() {
+        let dir = temp_store_dir("migrate");
+        let backend = MemoryBackend::default();
+        let store =
+            DataStore::new(&dir, LanguageCode::English).with_backend(Box::new(backend.clone()));
+
+        let cards = vec![test_card(
+            "OP01-001",
+            "../images/cardlist/card/OP01-001.png",
+        )];
+        backend
+            .write(
+                &dir.join("json/cards_569101.json"),
+                serde_json::to_string(&cards).unwrap().as_bytes(),
+            )
+            .unwrap();
+        backend
+            .write(
+                &dir.join(VEGA_META_FILE),
+                b"language = \"English\"\nmode = \"SinglePack\"\npacks = [\"569101\"]\n",
+            )
+            .unwrap();
+
+        assert_eq!(store.migrate().unwrap(), 1);
+
+        let data = backend.read(&dir.join("json/cards_569101.json")).unwrap();
+        let file: CardsFile = serde_json::from_slice(&data).unwrap();
+        assert_eq!(file.schema_version, SCHEMA_VERSION);
+        assert_eq!(file.cards.len(), 1);
+
+        let meta = store.read_vega_meta().unwrap().unwrap();
+        assert_eq!(
+            meta["schema_version"].as_integer(),
+            Some(SCHEMA_VERSION.into())
+        );
+        assert_eq!(meta["mode"].as_str(), Some("SinglePack"));
+    }
+
+    #[test]
+    fn write_image_deduplicates_identical_images_unless_materialized() {
+        let dir = temp_store_dir("dedup");
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png?250101");
+        let alt_card = test_card("OP01-001_p1", "../images/cardlist/card/OP01-001_p1.png");
+
+        let store = DataStore::new(&dir, LanguageCode::English);
+        store.write_image(&card, b"same".to_vec()).unwrap();
+        store.write_image(&alt_card, b"same".to_vec()).unwrap();
+        store.write_image_index().unwrap();
+
+        assert!(dir.join("images/OP01-001.png").exists());
+        assert!(!dir.join("images/OP01-001_p1.png").exists());
+        let manifest = store.read_image_manifest().unwrap();
+        assert_eq!(manifest["OP01-001_p1"].filename, "images/OP01-001.png");
+        assert!(store.missing_images(vec![&alt_card]).unwrap().is_empty());
+
+        let store = DataStore::new(&dir, LanguageCode::English).with_materialize_images(true);
+        store.write_image(&alt_card, b"same".to_vec()).unwrap();
+        store.write_image_index().unwrap();
+
+        assert!(dir.join("images/OP01-001_p1.png").exists());
+        let manifest = store.read_image_manifest().unwrap();
+        assert_eq!(manifest["OP01-001_p1"].filename, "images/OP01-001_p1.png");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_image_content_addressed_deduplicates() {
+        let dir = temp_store_dir("objects");
+        let store = DataStore::new(&dir, LanguageCode::English)
+            .with_image_layout(ImageLayout::ContentAddressed);
+
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png?250101");
+        let alt_card = test_card("OP01-001_p1", "../images/cardlist/card/OP01-001_p1.png");
+        store.write_image(&card, b"same".to_vec()).unwrap();
+        store.write_image(&alt_card, b"same".to_vec()).unwrap();
+        store.write_image_index().unwrap();
+
+        let objects_dir = store.get_path(StoreLocation::ImageObjectsDir).unwrap();
+        assert_eq!(fs::read_dir(objects_dir).unwrap().count(), 1);
+
+        let index = fs::read_to_string(store.get_path(StoreLocation::ImageIndexFile).unwrap());
+        let index: BTreeMap<String, String> = serde_json::from_str(&index.unwrap()).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index["OP01-001"], index["OP01-001_p1"]);
+        assert!(index["OP01-001"].ends_with(".png"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_image_name_template_returns_templated_path() {
+        let dir = temp_store_dir("image-name");
+        let store = DataStore::new(&dir, LanguageCode::English)
+            .with_image_name_template(Some("{pack_id}/{card_id}.{ext}".parse().unwrap()));
+
+        let card = test_card(
+            "OP01-001_p1",
+            "../images/cardlist/card/OP01-001_p1.png?250101",
+        );
+        store.write_image(&card, b"image".to_vec()).unwrap();
+
+        assert!(dir.join("images/569101/OP01-001_p1.png").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_back_image_adds_back_suffix() {
+        let dir = temp_store_dir("back-image");
+        let store = DataStore::new(&dir, LanguageCode::English);
+
+        let mut card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png?250101");
+        assert!(store.write_back_image(&card, b"back".to_vec()).is_err());
+
+        card.img_back_url = Some(String::from("../images/cardlist/card/OP01-001_b.png"));
+        store.write_image(&card, b"front".to_vec()).unwrap();
+        store.write_back_image(&card, b"back".to_vec()).unwrap();
+
+        assert_eq!(fs::read(dir.join("images/OP01-001.png")).unwrap(), b"front");
+        assert_eq!(
+            fs::read(dir.join("images/OP01-001_back.png")).unwrap(),
+            b"back"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_pack_image_writes_to_pack_artwork_dir() {
+        let dir = temp_store_dir("pack-image");
+        let store = DataStore::new(&dir, LanguageCode::English);
+        let pack_id: PackId = "569101".parse().unwrap();
+
+        let path = store
+            .write_pack_image(
+                &pack_id,
+                "cover",
+                "../images/products/op01/img_item01.jpg?240101",
+                b"cover".to_vec(),
+            )
+            .unwrap();
+
+        assert_eq!(path, "images/packs/569101_cover.jpg");
+        assert_eq!(fs::read(dir.join(&path)).unwrap(), b"cover");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_card_back_is_referenced_from_meta() {
+        let dir = temp_store_dir("card-back");
+        let store = DataStore::new(&dir, LanguageCode::English);
+
+        let path = store
+            .write_card_back("../images/cardlist/card/card_back.png", b"back".to_vec())
+            .unwrap();
+        assert_eq!(path, "images/card_back.png");
+        assert_eq!(fs::read(dir.join(&path)).unwrap(), b"back");
+
+        let meta = VegaMetaStats::new(
+            LanguageCode::English,
+            Local::now(),
+            42,
+            true,
+            PullMode::All,
+            HashSet::new(),
+        )
+        .with_card_back(Some(path));
+        store.write_vega_stats(meta).unwrap();
+
+        let meta: toml::Value =
+            toml::from_str(&fs::read_to_string(dir.join(VEGA_META_FILE)).unwrap()).unwrap();
+        assert_eq!(meta["card_back"].as_str(), Some("images/card_back.png"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_images_skips_stored_images() {
+        let dir = temp_store_dir("missing-images");
+        let store = DataStore::new(&dir, LanguageCode::English);
+
+        let stored = test_card("OP01-001", "../images/cardlist/card/OP01-001.png");
+        let corrupted = test_card("OP01-002", "../images/cardlist/card/OP01-002.png");
+        let mut missing_back = test_card("OP01-003", "../images/cardlist/card/OP01-003.png");
+        missing_back.img_back_url = Some(String::from("../images/cardlist/card/OP01-003_b.png"));
+        let new = test_card("OP01-004", "../images/cardlist/card/OP01-004.png");
+
+        for card in [&stored, &corrupted, &missing_back] {
+            let front = format!("front of {}", card.id);
+            store.write_image(card, front.into_bytes()).unwrap();
+        }
+        store.write_checksums().unwrap();
+        fs::write(dir.join("images/OP01-002.png"), b"truncated").unwrap();
+
+        let cards = vec![&stored, &corrupted, &missing_back, &new];
+        let missing: Vec<String> = store
+            .missing_images(cards)
+            .unwrap()
+            .iter()
+            .map(|card| card.id.to_string())
+            .collect();
+        assert_eq!(missing, vec!["OP01-002", "OP01-003", "OP01-004"]);
+
+        let store = store.with_force_images(true);
+        assert_eq!(store.missing_images(vec![&stored, &new]).unwrap().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_image_placeholders_lists_errors_and_stays_missing() {
+        let dir = temp_store_dir("placeholders");
+        let store = DataStore::new(&dir, LanguageCode::English).with_image_placeholders(true);
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png");
+
+        store
+            .write_image_placeholders(&[(&card, "HTTP 404 Not Found")])
+            .unwrap();
+        store.write_image_index().unwrap();
+        store.write_checksums().unwrap();
+
+        assert!(
+            image::load_from_memory(&fs::read(dir.join("images/OP01-001.png")).unwrap()).is_ok()
+        );
+        let errors: BTreeMap<String, String> =
+            serde_json::from_slice(&fs::read(dir.join("errors.json")).unwrap()).unwrap();
+        assert_eq!(errors["OP01-001"], "HTTP 404 Not Found");
+        assert!(store.read_image_manifest().unwrap()["OP01-001"].placeholder);
+        assert_eq!(store.missing_images(vec![&card]).unwrap().len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_image_corrects_extension_from_image_bytes() {
+        let dir = temp_store_dir("extension");
+        let store = DataStore::new(&dir, LanguageCode::English);
+
+        let mut jpeg = std::io::Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(2, 2, image::Rgb([0, 0, 255]))
+            .write_to(&mut jpeg, image::ImageFormat::Jpeg)
+            .unwrap();
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png?250101");
+        store.write_image(&card, jpeg.into_inner()).unwrap();
+        store.write_image_index().unwrap();
+        store.write_checksums().unwrap();
+
+        assert!(dir.join("images/OP01-001.jpg").exists());
+        assert!(!dir.join("images/OP01-001.png").exists());
+        assert!(store.missing_images(vec![&card]).unwrap().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_image_converts_to_image_format() {
+        let dir = temp_store_dir("webp");
+        let store =
+            DataStore::new(&dir, LanguageCode::English).with_image_format(ImageFormat::Webp);
+
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbImage::from_pixel(2, 2, image::Rgb([0, 0, 255]))
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png?250101");
+        store.write_image(&card, png.into_inner()).unwrap();
+        store.write_image_index().unwrap();
+
+        let webp = fs::read(dir.join("images/OP01-001.webp")).unwrap();
+        assert_eq!(
+            image::guess_format(&webp).unwrap(),
+            image::ImageFormat::WebP
+        );
+
+        let conversions = fs::read(store.get_path(StoreLocation::ImageConversionsFile).unwrap());
+        let conversions: BTreeMap<String, ImageConversion> =
+            serde_json::from_slice(&conversions.unwrap()).unwrap();
+        assert_eq!(
+            conversions["OP01-001"],
+            ImageConversion {
+                original: String::from("OP01-001.png"),
+                converted: String::from("images/OP01-001.webp"),
+            }
+        );
+        assert_eq!(store.missing_images(vec![&card]).unwrap().len(), 0);
+
+        let manifest = fs::read(store.get_path(StoreLocation::ImageManifestFile).unwrap());
+        let manifest: BTreeMap<String, ImageEntry> =
+            serde_json::from_slice(&manifest.unwrap()).unwrap();
+        assert_eq!(manifest["OP01-001"].filename, "images/OP01-001.webp");
+        assert_eq!(
+            manifest["OP01-001"].dhash.as_ref().map(String::len),
+            Some(16)
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_cards_include_raw_keeps_raw_fields() {
+        let dir = temp_store_dir("raw");
+        let mut card = test_card("OP01-001", "../images/cardlist/card/OP01-001.png");
+        card.raw_rarity = Some(String::from("L"));
+
+        let store = DataStore::new(&dir, LanguageCode::English);
+        store
+            .write_cards(&"569101".parse().unwrap(), std::slice::from_ref(&card))
+            .unwrap();
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[0].raw_rarity,
+            None
+        );
+
+        let store = store.with_include_raw(true);
+        store
+            .write_cards(&"569101".parse().unwrap(), &[card])
+            .unwrap();
+        assert_eq!(
+            store.read_cards(&"569101".parse().unwrap()).unwrap()[0]
+                .raw_rarity
+                .as_deref(),
+            Some("L")
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+use crate::card::{CardOrder, TextNormalization};
+
+use super::{
+    ArchiveFormat, Compression, ImageFormat, ImageLayout, ImageNameTemplate, OutputFormat,
+    StoreLayout,
+};
+
+/// How a store lays out and writes its data files and images, see the `with_*` methods of
+/// `DataStore` for what each option does
+#[derive(Debug, Clone, Default)]
+pub struct StoreOptions {
+    pub image_layout: ImageLayout,
+    pub image_name_template: Option<ImageNameTemplate>,
+    pub image_format: ImageFormat,
+    pub compression: Compression,
+    pub format: OutputFormat,
+    pub pretty: bool,
+    pub single_file: bool,
+    pub layout: StoreLayout,
+    pub scrape_timestamps: bool,
+    pub merge: bool,
+    pub group_variants: bool,
+    pub text_normalization: Vec<TextNormalization>,
+    pub include_raw: bool,
+    pub card_order: CardOrder,
+    pub force_images: bool,
+    pub check_images: bool,
+    pub image_placeholders: bool,
+    pub materialize_images: bool,
+    pub optimize_images: bool,
+    pub strict_checksums: bool,
+    /// Write everything into a single archive next to the store directory instead
+    pub archive: Option<ArchiveFormat>,
+}
@@ -0,0 +1,174 @@
+use image::{Rgb, RgbImage};
+use std::io::Cursor;
+
+use crate::{card::Card, error::Result};
+
+/// Size of the site's card images
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 838;
+const MARGIN: u32 = 40;
+
+const BACKGROUND: Rgb<u8> = Rgb([40, 40, 48]);
+const BORDER: Rgb<u8> = Rgb([120, 120, 132]);
+const TEXT: Rgb<u8> = Rgb([235, 235, 240]);
+
+/// Placeholder image of `card`, showing its ID and name, written when its images cannot be
+/// downloaded
+pub fn render(card: &Card) -> Result<Vec<u8>> {
+    let mut img = RgbImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        if x < 12 || y < 12 || x >= WIDTH - 12 || y >= HEIGHT - 12 {
+            *pixel = BORDER;
+        }
+    }
+
+    let id_scale = fitting_scale(&card.id, 10);
+    draw_text(&mut img, &card.id, MARGIN, 120, id_scale);
+
+    let mut y = 120 + 7 * id_scale + 80;
+    for line in wrap(&card.name, (WIDTH - 2 * MARGIN) / (6 * 5))
+        .iter()
+        .take(8)
+    {
+        draw_text(&mut img, line, MARGIN, y, 5);
+        y += 8 * 5 + 10;
+    }
+
+    draw_text(&mut img, "NO IMAGE", MARGIN, HEIGHT - MARGIN - 7 * 4, 4);
+
+    let mut png = Cursor::new(Vec::new());
+    img.write_to(&mut png, image::ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+/// Largest scale up to `max` at which `text` fits on one line
+fn fitting_scale(text: &str, max: u32) -> u32 {
+    let chars = text.chars().count().max(1) as u32;
+    ((WIDTH - 2 * MARGIN) / (6 * chars)).clamp(1, max)
+}
+
+/// `text` split on spaces into lines of at most `width` characters
+fn wrap(text: &str, width: u32) -> Vec<String> {
+    let width = width as usize;
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => {
+                let chars: Vec<char> = word.chars().collect();
+                lines.extend(chars.chunks(width).map(|chunk| chunk.iter().collect()));
+            }
+        }
+    }
+
+    lines
+}
+
+fn draw_text(img: &mut RgbImage, text: &str, x: u32, y: u32, scale: u32) {
+    for (i, c) in text.chars().enumerate() {
+        let left = x + i as u32 * 6 * scale;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let (px, py) = (left + col * scale + dx, y + row as u32 * scale + dy);
+                        if px < img.width() && py < img.height() {
+                            img.put_pixel(px, py, TEXT);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Rows of a 5x7 glyph, top to bottom. Characters without one (e.g. Japanese names) are drawn
+/// as `?`
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        ' ' => [0; 7],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '\'' => [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '&' => [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardRarity;
+
+    #[test]
+    fn render_draws_card_size_png() {
+        let card = Card::builder()
+            .id("OP01-025_p1")
+            .pack_id("569101")
+            .name("Roronoa Zoro")
+            .rarity(CardRarity::SuperRare)
+            .img_url("../images/cardlist/card/OP01-025_p1.png")
+            .build()
+            .unwrap();
+
+        let img = image::load_from_memory(&render(&card).unwrap())
+            .unwrap()
+            .into_rgb8();
+
+        assert_eq!(img.dimensions(), (WIDTH, HEIGHT));
+        assert!(img.pixels().any(|pixel| *pixel == TEXT));
+        assert_eq!(
+            wrap("Monkey.D.Luffy & Roronoa Zoro", 14),
+            vec!["Monkey.D.Luffy", "& Roronoa Zoro"]
+        );
+    }
+}
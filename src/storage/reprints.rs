@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    card::{Card, CardVariant},
+    pack::{Pack, PackId},
+    storage::schema::SCHEMA_VERSION,
+};
+
+/// Packs a card is printed in, along with the one it was first printed in
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ReprintEntry {
+    pub original_pack: PackId,
+    pub appears_in: BTreeSet<PackId>,
+}
+
+/// Cross-reference of cards printed in more than one pack, keyed by base card ID
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reprints {
+    pub schema_version: u32,
+    pub cards: BTreeMap<String, ReprintEntry>,
+}
+
+impl Default for Reprints {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            cards: BTreeMap::new(),
+        }
+    }
+}
+
+impl Reprints {
+    /// Find the cards whose base ID shows up in several packs and fill their `reprint_of`
+    /// and `appears_in`. The original pack is the one labelled with the card's set code,
+    /// or the oldest pack holding it if none is.
+    pub fn link(packs: &HashMap<PackId, Pack>, cards: &mut HashMap<PackId, Vec<Card>>) -> Self {
+        let mut appears_in: BTreeMap<String, BTreeSet<PackId>> = BTreeMap::new();
+        for (pack_id, pack_cards) in cards.iter() {
+            for card in pack_cards {
+                let (base_id, _) = CardVariant::split_id(&card.id);
+                appears_in
+                    .entry(base_id.to_string())
+                    .or_default()
+                    .insert(pack_id.clone());
+            }
+        }
+        appears_in.retain(|_, pack_ids| pack_ids.len() > 1);
+
+        let mut reprints = Self::default();
+        for (base_id, pack_ids) in appears_in {
+            let set_code = base_id.split('-').next().unwrap_or_default();
+            let original_pack = pack_ids
+                .iter()
+                .find(|pack_id| {
+                    packs
+                        .get(*pack_id)
+                        .and_then(|pack| pack.title_parts.label.as_ref())
+                        .is_some_and(|label| label.replace('-', "") == set_code)
+                })
+                .or_else(|| pack_ids.iter().next())
+                .cloned()
+                .expect("reprints appear in several packs");
+
+            reprints.cards.insert(
+                base_id,
+                ReprintEntry {
+                    original_pack,
+                    appears_in: pack_ids,
+                },
+            );
+        }
+
+        for card in cards.values_mut().flatten() {
+            let (base_id, _) = CardVariant::split_id(&card.id);
+            if let Some(entry) = reprints.cards.get(base_id) {
+                card.appears_in = entry.appears_in.iter().cloned().collect();
+                if card.pack_id != entry.original_pack {
+                    card.reprint_of = Some(entry.original_pack.clone());
+                }
+            }
+        }
+
+        reprints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::TitleParts;
+
+    fn test_card(id: &str, pack_id: &str) -> Card {
+        Card::builder()
+            .id(id)
+            .pack_id(pack_id)
+            .name("Nami")
+            .cost(1)
+            .power(2000)
+            .counter(1000)
+            .build()
+            .unwrap()
+    }
+
+    fn test_pack(id: &str, label: &str) -> (PackId, Pack) {
+        let pack = Pack {
+            id: id.parse().unwrap(),
+            raw_title: format!("PACK [{}]", label),
+            title_parts: TitleParts {
+                prefix: None,
+                title: String::from("PACK"),
+                label: Some(label.to_string()),
+            },
+            cover_image: None,
+            logo_image: None,
+        };
+        (pack.id.clone(), pack)
+    }
+
+    #[test]
+    fn link_records_reprints_across_packs() {
+        let packs = HashMap::from([test_pack("569101", "OP-01"), test_pack("569301", "PRB-01")]);
+        let mut cards = HashMap::from([
+            (
+                "569101".parse().unwrap(),
+                vec![
+                    test_card("OP01-016", "569101"),
+                    test_card("OP01-017", "569101"),
+                ],
+            ),
+            (
+                "569301".parse().unwrap(),
+                vec![test_card("OP01-016_p2", "569301")],
+            ),
+        ]);
+
+        let reprints = Reprints::link(&packs, &mut cards);
+
+        assert_eq!(reprints.cards.len(), 1);
+        assert_eq!(reprints.cards["OP01-016"].original_pack, "569101");
+        let reprint = &cards["569301"][0];
+        assert_eq!(reprint.reprint_of, Some("569101".parse().unwrap()));
+        assert_eq!(reprint.appears_in.len(), 2);
+        let original = &cards["569101"][0];
+        assert_eq!(original.reprint_of, None);
+        assert_eq!(original.appears_in.len(), 2);
+        assert!(cards["569101"][1].appears_in.is_empty());
+    }
+}
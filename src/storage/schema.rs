@@ -0,0 +1,140 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    card::Card,
+    error::{Result, VegaError},
+    pack::{Pack, PackId},
+};
+
+/// Version of the data files layout.
+/// 1: bare `packs.json` map and `cards_*.json` arrays (vegapull <= 1.2.2)
+/// 2: documents wrapped in an object carrying `schema_version`
+pub const SCHEMA_VERSION: u32 = 2;
+
+const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PacksFile {
+    pub schema_version: u32,
+    // Sorted by pack ID so output is stable across runs
+    pub packs: BTreeMap<PackId, Pack>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CardsFile {
+    pub schema_version: u32,
+    pub cards: Vec<Card>,
+}
+
+/// Any packs file vegapull ever produced
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PacksDocument {
+    Versioned(PacksFile),
+    Legacy(HashMap<PackId, Pack>),
+}
+
+/// Any cards file vegapull ever produced
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum CardsDocument {
+    Versioned(CardsFile),
+    Legacy(Vec<Card>),
+}
+
+pub fn ensure_supported(schema_version: u32) -> Result<()> {
+    if schema_version > SCHEMA_VERSION {
+        return Err(VegaError::storage(format!(
+            "data files use schema version {} but this vegapull only supports up to {}, please upgrade",
+            schema_version, SCHEMA_VERSION
+        )));
+    }
+
+    Ok(())
+}
+
+impl PacksFile {
+    pub fn new(packs: impl IntoIterator<Item = (PackId, Pack)>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            packs: packs.into_iter().collect(),
+        }
+    }
+}
+
+impl CardsFile {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            cards,
+        }
+    }
+}
+
+impl PacksDocument {
+    pub fn migrate(self) -> Result<PacksFile> {
+        match self {
+            PacksDocument::Versioned(file) => {
+                ensure_supported(file.schema_version)?;
+                Ok(PacksFile::new(file.packs))
+            }
+            PacksDocument::Legacy(packs) => {
+                info!(
+                    "migrating packs from schema version {}",
+                    LEGACY_SCHEMA_VERSION
+                );
+                Ok(PacksFile::new(packs))
+            }
+        }
+    }
+}
+
+impl CardsDocument {
+    pub fn migrate(self) -> Result<CardsFile> {
+        match self {
+            CardsDocument::Versioned(file) => {
+                ensure_supported(file.schema_version)?;
+                Ok(CardsFile::new(file.cards))
+            }
+            CardsDocument::Legacy(cards) => {
+                info!(
+                    "migrating cards from schema version {}",
+                    LEGACY_SCHEMA_VERSION
+                );
+                Ok(CardsFile::new(cards))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_document_legacy_migrates() {
+        let raw = r#"{"569101":{"id":"569101","raw_title":"ROMANCE DAWN [OP-01]","title_parts":{"prefix":null,"title":"ROMANCE DAWN","label":"OP-01"}}}"#;
+        let document: PacksDocument = serde_json::from_str(raw).unwrap();
+        assert!(matches!(document, PacksDocument::Legacy(_)));
+
+        let file = document.migrate().unwrap();
+        assert_eq!(file.schema_version, SCHEMA_VERSION);
+        assert_eq!(file.packs["569101"].raw_title, "ROMANCE DAWN [OP-01]");
+    }
+
+    #[test]
+    fn cards_document_legacy_migrates() {
+        let document: CardsDocument = serde_json::from_str("[]").unwrap();
+        assert!(matches!(document, CardsDocument::Legacy(_)));
+        assert_eq!(document.migrate().unwrap().schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn cards_document_newer_version_returns_err() {
+        let raw = format!(r#"{{"schema_version":{},"cards":[]}}"#, SCHEMA_VERSION + 1);
+        let document: CardsDocument = serde_json::from_str(&raw).unwrap();
+        assert!(document.migrate().is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    config::Settings,
+    error::{Result, VegaError},
+};
+
+pub static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+static CONTACT_INFO: &str = concat!("(+", env!("CARGO_PKG_REPOSITORY"), ")");
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum UserAgentStrategy {
+    /// Always send the first User-Agent of the list
+    #[default]
+    Fixed,
+    /// Cycle through the list, one User-Agent per request
+    Rotate,
+    /// Pick a random User-Agent from the list for every request
+    Random,
+}
+
+#[derive(Debug)]
+pub struct UserAgentPool {
+    agents: Vec<String>,
+    strategy: UserAgentStrategy,
+    counter: AtomicUsize,
+    random_state: RandomState,
+}
+
+impl UserAgentPool {
+    pub fn new(agents: Vec<String>, strategy: UserAgentStrategy) -> Result<Self> {
+        if agents.is_empty() {
+            return Err(VegaError::config("at least one User-Agent is required"));
+        }
+
+        let agents = agents
+            .iter()
+            .map(|agent| Self::with_contact_info(agent))
+            .collect();
+
+        Ok(Self {
+            agents,
+            strategy,
+            counter: AtomicUsize::new(0),
+            random_state: RandomState::new(),
+        })
+    }
+
+    /// Build the pool from the command line, falling back to the settings file and then to
+    /// the default vegapull User-Agent.
+    pub fn resolve(
+        settings: &Settings,
+        user_agent: Option<String>,
+        strategy: Option<UserAgentStrategy>,
+    ) -> Result<Self> {
+        let agents = match user_agent {
+            Some(user_agent) => vec![user_agent],
+            None if !settings.user_agent.list.is_empty() => settings.user_agent.list.clone(),
+            None => vec![APP_USER_AGENT.to_string()],
+        };
+
+        let strategy = strategy.unwrap_or(settings.user_agent.strategy);
+        info!(
+            "using {} User-Agent(s) with strategy: {:?}",
+            agents.len(),
+            strategy
+        );
+
+        Self::new(agents, strategy)
+    }
+
+    fn with_contact_info(agent: &str) -> String {
+        let agent = agent.trim();
+        if agent.contains(CONTACT_INFO) {
+            return agent.to_string();
+        }
+
+        format!("{} {}", agent, CONTACT_INFO)
+    }
+
+    pub fn next(&self) -> &str {
+        let count = self.counter.fetch_add(1, Ordering::Relaxed);
+        let index = match self.strategy {
+            UserAgentStrategy::Fixed => 0,
+            UserAgentStrategy::Rotate => count % self.agents.len(),
+            UserAgentStrategy::Random => {
+                self.random_state.hash_one(count) as usize % self.agents.len()
+            }
+        };
+
+        let agent = &self.agents[index];
+        debug!("picked User-Agent: {}", agent);
+        agent
+    }
+}
+
+impl Default for UserAgentPool {
+    fn default() -> Self {
+        Self::new(vec![APP_USER_AGENT.to_string()], UserAgentStrategy::Fixed)
+            .expect("default User-Agent list should not be empty")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agents() -> Vec<String> {
+        vec![String::from("foo/1.0"), String::from("bar/2.0")]
+    }
+
+    #[test]
+    fn new_appends_contact_info() {
+        let pool = UserAgentPool::new(agents(), UserAgentStrategy::Fixed).unwrap();
+        assert_eq!(pool.next(), format!("foo/1.0 {}", CONTACT_INFO));
+    }
+
+    #[test]
+    fn new_does_not_append_contact_info_twice() {
+        let agent = format!("foo/1.0 {}", CONTACT_INFO);
+        let pool = UserAgentPool::new(vec![agent.clone()], UserAgentStrategy::Fixed).unwrap();
+        assert_eq!(pool.next(), agent);
+    }
+
+    #[test]
+    fn new_empty_list_returns_err() {
+        assert!(UserAgentPool::new(Vec::new(), UserAgentStrategy::Fixed).is_err());
+    }
+
+    #[test]
+    fn next_fixed_always_returns_first() {
+        let pool = UserAgentPool::new(agents(), UserAgentStrategy::Fixed).unwrap();
+        assert!(pool.next().starts_with("foo/1.0"));
+        assert!(pool.next().starts_with("foo/1.0"));
+    }
+
+    #[test]
+    fn next_rotate_cycles_through_list() {
+        let pool = UserAgentPool::new(agents(), UserAgentStrategy::Rotate).unwrap();
+        assert!(pool.next().starts_with("foo/1.0"));
+        assert!(pool.next().starts_with("bar/2.0"));
+        assert!(pool.next().starts_with("foo/1.0"));
+    }
+
+    #[test]
+    fn next_random_returns_agent_from_list() {
+        let pool = UserAgentPool::new(agents(), UserAgentStrategy::Random).unwrap();
+        for _ in 0..10 {
+            let agent = pool.next();
+            assert!(agent.starts_with("foo/1.0") || agent.starts_with("bar/2.0"));
+        }
+    }
+}
@@ -1,8 +1,11 @@
-use anyhow::Result;
 use chrono::Local;
+use serde::Serialize;
 use std::{env::current_dir, path::PathBuf};
 
-use crate::cli::LanguageCode;
+use crate::{
+    error::{Result, VegaError},
+    language::LanguageCode,
+};
 
 pub fn get_default_data_dirname(language: LanguageCode) -> String {
     let timestamp = Local::now().format("%y%m%d_%H%M").to_string();
@@ -13,3 +16,71 @@ pub fn get_default_data_dir(language: LanguageCode) -> Result<PathBuf> {
     let dir_name = get_default_data_dirname(language);
     Ok(current_dir()?.join(dir_name))
 }
+
+pub fn get_default_multi_data_dir() -> Result<PathBuf> {
+    let timestamp = Local::now().format("%y%m%d_%H%M").to_string();
+    Ok(current_dir()?.join(format!("data-{timestamp}-multi")))
+}
+
+/// Check that an ID is non-empty and safe to use as part of a file name
+pub fn validate_id(kind: &str, value: &str) -> Result<()> {
+    if value.is_empty() {
+        return Err(VegaError::config(format!("empty {}", kind)));
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(VegaError::config(format!("invalid {} `{}`", kind, value)));
+    }
+
+    Ok(())
+}
+
+/// Parse a CSS selector, which may come from a locale file
+pub fn parse_selector(selector: &str) -> Result<scraper::Selector> {
+    scraper::Selector::parse(selector)
+        .map_err(|e| VegaError::locale(format!("invalid selector `{}`: {}", selector, e)))
+}
+
+/// Name of an enum variant as written in the data files (e.g. `SuperRare`)
+pub fn variant_name<T: Serialize>(value: &T) -> Result<String> {
+    let value = serde_json::to_value(value)?;
+    let name = value
+        .as_str()
+        .ok_or_else(|| VegaError::storage(format!("expected a unit variant, got: {}", value)))?;
+
+    Ok(name.to_string())
+}
+
+pub fn variant_names<T: Serialize>(values: &[T]) -> Result<Vec<String>> {
+    values.iter().map(variant_name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{CardColor, CardRarity};
+
+    #[test]
+    fn validate_id_rejects_unsafe_values() {
+        assert!(validate_id("card ID", "OP01-001_p1").is_ok());
+        assert!(validate_id("card ID", "").is_err());
+        assert!(validate_id("pack ID", "../569101").is_err());
+        assert!(validate_id("pack ID", "569 101").is_err());
+    }
+
+    #[test]
+    fn variant_name_returns_serialized_name() {
+        assert_eq!(variant_name(&CardRarity::SuperRare).unwrap(), "SuperRare");
+        assert_eq!(
+            variant_names(&[CardColor::Red, CardColor::Green]).unwrap(),
+            vec!["Red", "Green"]
+        );
+    }
+
+    #[test]
+    fn variant_name_struct_returns_err() {
+        assert!(variant_name(&vec![1]).is_err());
+    }
+}